@@ -1,7 +1,15 @@
 from zarr.registry import register_pipeline
 
-from ._internal import __version__
+from ._internal import (
+    __version__,
+    experimental_codecs,
+    register_codec,
+    register_data_type,
+    train_zstd_dictionary,
+)
+from .codecs import SqueezeCodec, ZstdDictionaryCodec
 from .pipeline import ZarrsCodecPipeline as _ZarrsCodecPipeline
+from .selection import get_orthogonal_selection, set_orthogonal_selection
 from .utils import CollapsedDimensionError, DiscontiguousArrayError
 
 
@@ -16,5 +24,13 @@ __all__ = [
     "ZarrsCodecPipeline",
     "DiscontiguousArrayError",
     "CollapsedDimensionError",
+    "get_orthogonal_selection",
+    "set_orthogonal_selection",
+    "experimental_codecs",
+    "register_codec",
+    "register_data_type",
+    "train_zstd_dictionary",
+    "ZstdDictionaryCodec",
+    "SqueezeCodec",
     "__version__",
 ]
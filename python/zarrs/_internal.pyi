@@ -2,36 +2,318 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import os
+import pathlib
 import typing
 from enum import Enum, auto
 
 import numpy.typing
 
 class Basic:
-    def __new__(cls, byte_interface: typing.Any, chunk_spec: typing.Any): ...
+    def __new__(
+        cls,
+        byte_interface: typing.Any,
+        chunk_spec: typing.Any,
+        *,
+        data_type: builtins.str | None = None,
+    ):
+        r"""
+        `data_type` is the zarr data type of the chunk if it is not implied by the numpy data type of `chunk_spec`, e.g. `bytes` for an object array.
+        """
+        ...
     ...
 
+class ChunkVerification:
+    r"""
+    A report of the chunks verified by `CodecPipelineImpl.verify_chunks`.
+    """
+
+    valid: builtins.int
+    r"""
+    The number of chunks which were decoded with valid checksums.
+    """
+    missing: builtins.list[builtins.str]
+    r"""
+    The keys of the missing chunks.
+    """
+    corrupt: builtins.list[tuple[builtins.str, builtins.str]]
+    r"""
+    The keys of the chunks with an invalid checksum, and the error.
+    """
+    undecodable: builtins.list[tuple[builtins.str, builtins.str]]
+    r"""
+    The keys of the chunks which could not be retrieved or decoded for another reason, and the error.
+    """
+
+class CodecInfo:
+    r"""
+    A codec of the codec chain of a pipeline.
+    """
+
+    name: builtins.str
+    r"""
+    The name of the codec in the array metadata (e.g. `zstd`).
+    """
+    kind: builtins.str
+    r"""
+    The kind of the codec, `array_to_array`, `array_to_bytes`, or `bytes_to_bytes`.
+    """
+    partial_decoding: builtins.bool
+    r"""
+    Whether the codec can decode a subset of a chunk without decoding the whole chunk.
+    """
+    @property
+    def configuration(self) -> typing.Any:
+        r"""
+        The configuration of the codec in the array metadata (a `dict`), or `None`.
+        """
+        ...
+    def __repr__(self) -> builtins.str: ...
+
 class CodecPipelineImpl:
     def __new__(
         cls,
-        metadata: builtins.str,
+        metadata: builtins.str | typing.Sequence[typing.Any] | typing.Mapping[builtins.str, typing.Any],
         *,
         validate_checksums: builtins.bool | None = None,
         store_empty_chunks: builtins.bool | None = None,
         chunk_concurrent_minimum: builtins.int | None = None,
         chunk_concurrent_maximum: builtins.int | None = None,
         num_threads: builtins.int | None = None,
+        storage_options: typing.Mapping[builtins.str, typing.Any] | None = None,
+        cache_max_bytes: builtins.int | None = None,
+        cache_max_entries: builtins.int | None = None,
+        storage_transformers: typing.Sequence[builtins.str] | None = None,
+        memory_map: builtins.bool | None = None,
+        direct_io: builtins.bool | None = None,
+        retry_max_attempts: builtins.int | None = None,
+        retry_base_delay: builtins.float | None = None,
+        retry_jitter: builtins.float | None = None,
+        store_timeout: builtins.float | None = None,
+        timeout: builtins.float | None = None,
+        read_only: builtins.bool = False,
+        http_options: typing.Mapping[builtins.str, typing.Any] | None = None,
+        disk_cache_directory: builtins.str | os.PathLike | pathlib.Path | None = None,
+        disk_cache_max_bytes: builtins.int | None = None,
+        key_transforms: typing.Sequence[builtins.str] | None = None,
+        io_concurrent_limit: builtins.int | None = None,
+        multipart_part_size: builtins.int | None = None,
+        multipart_concurrency: builtins.int | None = None,
+        write_buffer_max_bytes: builtins.int | None = None,
+        scale_offset: tuple[builtins.float, builtins.float] | None = None,
+        partial_encoding: builtins.bool | None = None,
+        partial_decoder_cache_max_entries: builtins.int | None = None,
+        blosc_nthreads: builtins.int | None = None,
+        blosc_blocksize: builtins.int | None = None,
+        blosc_shuffle: builtins.str | None = None,
+        unsafe_skip_write_checksums: builtins.bool | None = None,
+        codec_concurrent_target: builtins.int | None = None,
+        experimental_codecs: builtins.bool | None = None,
     ): ...
+    def codecs(self) -> builtins.list[CodecInfo]:
+        r"""
+        The codecs of the codec chain of the pipeline, in the order they are applied when encoding.
+        """
+        ...
+    def validate(self, chunk_description: Basic) -> None:
+        r"""
+        Check that the codecs support the chunks of an array (e.g. its data type), otherwise raise a `TypeError` naming the codec which does not.
+
+        Batched methods check their chunks the same way, this allows a caller to fall back to another codec pipeline for an array before reading or writing it.
+        """
+        ...
+    def memory_order(self, ndim: builtins.int) -> builtins.list[builtins.int]:
+        r"""
+        The memory order of the elements of encoded chunks with `ndim` dimensions, as the dimensions from the slowest to the fastest varying (e.g. `[0, 1]` for C order, `[1, 0]` for F order).
+
+        The dimensions are permuted by `transpose` codecs (including those within shards), decoded chunks are always in C order.
+        """
+        ...
+    def encoded_size_estimate(
+        self, chunk_shape: typing.Sequence[builtins.int], dtype: builtins.str
+    ) -> builtins.int | None:
+        r"""
+        The size in bytes of an encoded chunk with shape `chunk_shape` and data type `dtype` (e.g. `float32` or `<f4`), or `None` if it is unbounded.
+
+        The size is exact if the codecs encode chunks to a fixed size (e.g. without compression), otherwise it is an upper bound (e.g. the worst case of a compressor).
+        """
+        ...
+    def performance_metrics(self) -> PerformanceMetrics | None:
+        r"""
+        The store access counts of the pipeline, if the `performance_metrics` storage transformer is enabled.
+        """
+        ...
+    def stats(self) -> StoreStats:
+        r"""
+        The store I/O and encoding statistics of the pipeline since it was created or the statistics were reset.
+        """
+        ...
+    def reset_stats(self) -> None:
+        r"""
+        Reset the store I/O statistics of the pipeline.
+        """
+        ...
+    def list(self, store: typing.Any) -> builtins.list[builtins.str]:
+        r"""
+        List the keys of a zarr-python store through the store of the pipeline.
+        """
+        ...
+    def list_prefix(
+        self, store: typing.Any, prefix: builtins.str
+    ) -> builtins.list[builtins.str]:
+        r"""
+        List the keys under a prefix (e.g. `group/array/`) of a zarr-python store through the store of the pipeline.
+        """
+        ...
+    def list_dir(
+        self, store: typing.Any, prefix: builtins.str
+    ) -> tuple[builtins.list[builtins.str], builtins.list[builtins.str]]:
+        r"""
+        List the keys and prefixes immediately under a prefix of a zarr-python store through the store of the pipeline.
+        """
+        ...
+    def erase_prefix(self, store: typing.Any, prefix: builtins.str) -> None:
+        r"""
+        Erase every key under a prefix (e.g. the chunks of an array, `group/array/c/`) of a zarr-python store, in parallel.
+        """
+        ...
     def retrieve_chunks_and_apply_index(
         self,
         chunk_descriptions: typing.Sequence[WithSubset],
         value: numpy.typing.NDArray[typing.Any],
-    ) -> None: ...
+        *,
+        astype: builtins.str | None = None,
+        scale_offset: tuple[builtins.float, builtins.float] | None = None,
+        mask: numpy.typing.NDArray[numpy.bool_] | None = None,
+        validate_checksums: builtins.bool | None = None,
+        concurrent_target: builtins.int | None = None,
+    ) -> None:
+        r"""
+        Retrieve the selected elements of chunks into `value`.
+
+        `astype` is the data type of the elements of `value` (e.g. `float32`) if it differs from the data type of the chunks, which are cast while they are copied into `value`.
+        `scale_offset` is the `(scale, offset)` of packed elements, which are unpacked to `element * scale + offset` (overriding the `scale_offset` of the pipeline).
+        `mask` is a `bool` array with the shape of `value`, whose selected elements are set to whether they are stored rather than the fill value of a missing chunk.
+        `validate_checksums` and `concurrent_target` override the codec options of the pipeline for this call.
+        """
+        ...
     def store_chunks_with_indices(
         self,
         chunk_descriptions: typing.Sequence[WithSubset],
         value: numpy.typing.NDArray[typing.Any],
-    ) -> None: ...
+        *,
+        astype: builtins.str | None = None,
+        scale_offset: tuple[builtins.float, builtins.float] | None = None,
+        validate_checksums: builtins.bool | None = None,
+        store_empty_chunks: builtins.bool | None = None,
+        concurrent_target: builtins.int | None = None,
+    ) -> None:
+        r"""
+        Store the selected elements of `value` to chunks.
+
+        `astype` is the data type of the elements of `value` (e.g. `float32`) if it differs from the data type of the chunks, which are cast while they are copied from `value`.
+        `scale_offset` is the `(scale, offset)` of packed elements, which are packed to `(element - offset) / scale` (overriding the `scale_offset` of the pipeline).
+        `validate_checksums`, `store_empty_chunks`, and `concurrent_target` override the codec options of the pipeline for this call.
+        Chunks held in the write buffer are stored with the codec options of the pipeline when it is flushed.
+        """
+        ...
+    def retrieve_chunks_encoded(
+        self, chunk_descriptions: typing.Sequence[Basic]
+    ) -> builtins.list[builtins.bytes | None]:
+        r"""
+        Retrieve the encoded bytes of chunks as they are stored, without decoding them (e.g. to copy chunks without recompressing them).
+
+        Missing chunks are `None`.
+        """
+        ...
+    def retrieve_chunks_vlen(
+        self, chunk_descriptions: typing.Sequence[Basic]
+    ) -> builtins.list[builtins.list[typing.Any]]:
+        r"""
+        Retrieve every element of chunks with a variable length data type (e.g. `string`), in C order.
+
+        Missing chunks are filled with the fill value.
+        """
+        ...
+    def store_chunks_encoded(
+        self,
+        chunks: typing.Sequence[tuple[Basic, builtins.bytes]],
+        *,
+        validate: builtins.bool = False,
+    ) -> None:
+        r"""
+        Store the encoded bytes of chunks as they are, without encoding them (e.g. chunks retrieved with `retrieve_chunks_encoded`).
+
+        If `validate` is true, every chunk is decoded first and no chunk is stored if any of them cannot be decoded.
+        """
+        ...
+    def store_chunks_vlen(
+        self,
+        chunks: typing.Sequence[tuple[Basic, typing.Sequence[typing.Any]]],
+    ) -> None:
+        r"""
+        Store every element of chunks with a variable length data type (e.g. `string`), in C order.
+        """
+        ...
+    def verify_chunks(
+        self, chunk_descriptions: typing.Sequence[Basic]
+    ) -> ChunkVerification:
+        r"""
+        Decode every chunk with checksum validation, and report the chunks which are missing, corrupt (with an invalid checksum), or cannot be retrieved or decoded, rather than raising on the first failure.
+        """
+        ...
+    def transcode(
+        self,
+        src_descriptions: typing.Sequence[Basic],
+        dst_codec_metadata: builtins.str | typing.Sequence[typing.Any] | typing.Mapping[builtins.str, typing.Any],
+        *,
+        dst_descriptions: typing.Sequence[Basic] | None = None,
+    ) -> None:
+        r"""
+        Decode chunks with the codecs of the pipeline and encode them with the codecs of `dst_codec_metadata` (e.g. to change the compression of an array).
+
+        `dst_codec_metadata` is the metadata of the codecs, like the metadata of the pipeline.
+        Chunks are transcoded in place, or to `dst_descriptions` (e.g. the chunks of another array with the same chunk shape and data type) if it is given.
+        Chunks are transcoded in parallel and independently, so only the chunks in flight are held in memory.
+        The array metadata is not updated, so the array must be reopened with the new codecs once its chunks are transcoded in place.
+        """
+        ...
+    def flush(self) -> None:
+        r"""
+        Store the chunks with partial writes pending in the write buffer, if it is enabled.
+        """
+        ...
+    def retrieve_selection(
+        self,
+        store: typing.Any,
+        chunk_key_encoding: tuple[builtins.str, builtins.str],
+        chunk_spec: typing.Any,
+        array_shape: typing.Sequence[builtins.int],
+        selection: typing.Sequence[typing.Any],
+        value: numpy.typing.NDArray[typing.Any],
+    ) -> None:
+        r"""
+        Retrieve an orthogonal selection of an array with a regular chunk grid into `value`.
+
+        The selection of each chunk is computed from the selection of the array in Rust, rather than being passed from zarr-python chunk by chunk.
+        `value` keeps a dimension for each dimension of the array, including those selected with an integer.
+        """
+        ...
+    def store_selection(
+        self,
+        store: typing.Any,
+        chunk_key_encoding: tuple[builtins.str, builtins.str],
+        chunk_spec: typing.Any,
+        array_shape: typing.Sequence[builtins.int],
+        selection: typing.Sequence[typing.Any],
+        value: numpy.typing.NDArray[typing.Any],
+    ) -> None:
+        r"""
+        Store `value` to an orthogonal selection of an array with a regular chunk grid.
+
+        See [`CodecPipelineImpl::retrieve_selection`].
+        """
+        ...
 
 class FilesystemStoreConfig:
     root: builtins.str
@@ -39,16 +321,141 @@ class FilesystemStoreConfig:
 class HttpStoreConfig:
     endpoint: builtins.str
 
+class MemoryStoreConfig:
+    r"""
+    A zarr-python `MemoryStore`, identified by its underlying dictionary.
+    """
+
+    read_only: builtins.bool
+
+class OpendalStoreConfig:
+    r"""
+    A store backed by any [OpenDAL](https://opendal.apache.org/) service.
+
+    The `scheme` selects the service (e.g. `s3`, `gcs`, `azblob`, `webdav`, `redis`) and the remaining options are passed through as the service configuration.
+    """
+
+    scheme: builtins.str
+    options: builtins.dict[builtins.str, builtins.str]
+
+class PerformanceMetrics:
+    r"""
+    Store access counts recorded by the `performance_metrics` storage transformer.
+    """
+
+    bytes_read: builtins.int
+    bytes_written: builtins.int
+    reads: builtins.int
+    writes: builtins.int
+    keys_erased: builtins.int
+
+class PythonStoreConfig:
+    r"""
+    An arbitrary zarr-python `Store` without a native equivalent, identified by the store object.
+    """
+
+    name: builtins.str
+
+class S3StoreConfig:
+    bucket: builtins.str
+    root: builtins.str
+    region: builtins.str | None
+    endpoint: builtins.str | None
+    anonymous: builtins.bool
+
+class SqliteStoreConfig:
+    r"""
+    A `zarrs.storage.SQLiteStore`, where each key is a row of a single SQLite database file.
+    """
+
+    path: builtins.str
+    read_only: builtins.bool
+
+class StoreStats:
+    r"""
+    Store I/O and encoding statistics of a pipeline.
+    """
+
+    bytes_read: builtins.int
+    bytes_written: builtins.int
+    gets: builtins.int
+    sets: builtins.int
+    erases: builtins.int
+    cache_hits: builtins.int
+    cache_misses: builtins.int
+    chunks_encoded: builtins.int
+    bytes_before_encoding: builtins.int
+    bytes_after_encoding: builtins.int
+
 class WithSubset:
     def __new__(
         cls,
         item: Basic,
-        chunk_subset: typing.Sequence[slice],
-        subset: typing.Sequence[slice],
+        chunk_subset: typing.Sequence[typing.Any],
+        subset: typing.Sequence[typing.Any],
         shape: typing.Sequence[builtins.int],
+        *,
+        chunk_mask: numpy.typing.NDArray[numpy.bool_] | None = None,
+        chunk_coordinates: typing.Sequence[typing.Any] | None = None,
+        output_axes: typing.Sequence[builtins.int | None] | None = None,
     ): ...
     ...
 
+class ZipStoreConfig:
+    path: builtins.str
+
 class StoreConfig(Enum):
     Filesystem = auto()
     Http = auto()
+    Memory = auto()
+    Opendal = auto()
+    Python = auto()
+    S3 = auto()
+    Sqlite = auto()
+    Zip = auto()
+
+def experimental_codecs() -> builtins.list[builtins.str]:
+    r"""
+    The names of the experimental codecs of `zarrs` compiled into the extension.
+
+    They are enabled unless a `CodecPipelineImpl` is created with `experimental_codecs=False`.
+    Codecs behind `zarrs` features which are not compiled into the extension cannot be enabled at runtime.
+    """
+    ...
+
+def register_codec(
+    name: builtins.str,
+    encode: typing.Callable[[builtins.bytes, typing.Any], builtins.bytes],
+    decode: typing.Callable[[builtins.bytes, typing.Any], builtins.bytes],
+) -> None:
+    r"""
+    Register a bytes to bytes codec (e.g. a compressor) named `name`, which is implemented by Python callables.
+
+    `encode(data, configuration)` and `decode(data, configuration)` are called with the `bytes` of a chunk and the `configuration` of the codec in the array metadata (a `dict`, or `None`), and return `bytes`.
+    The GIL is only held while they run, so the other codecs of the pipeline are still applied to chunks in parallel.
+    Registering a name again replaces its codec. Names of codecs implemented by `zarrs` should not be registered, as either implementation may be used.
+    """
+    ...
+
+def register_data_type(
+    name: builtins.str,
+    size: builtins.int,
+    fill_value_parser: typing.Callable[[typing.Any], builtins.bytes] | None = None,
+) -> None:
+    r"""
+    Register a fixed size data type, so arrays with a numpy data type named `name` (i.e. `str(dtype)`) use the codec pipeline.
+
+    The elements of the data type are `size` bytes, which are encoded as is (raw bits).
+    `fill_value_parser` converts the fill value of an array to the `bytes` of an element, otherwise the fill value must be `bytes` or have a `tobytes` method (e.g. a numpy scalar).
+    Registering a name again replaces its data type.
+    """
+
+def train_zstd_dictionary(
+    samples: typing.Sequence[builtins.bytes], max_size: builtins.int = 112640
+) -> builtins.bytes:
+    r"""
+    Train a zstd dictionary of at most `max_size` bytes from sample chunks (e.g. the bytes of decoded chunks of an array).
+
+    The dictionary is the `dictionary` of the `zarrs_python.zstd_dictionary` codec, which improves the compression of many small, similar chunks.
+    """
+    ...
@@ -0,0 +1,126 @@
+from __future__ import annotations
+
+import base64
+from dataclasses import dataclass
+from typing import TYPE_CHECKING, Any
+
+from zarr.abc.codec import ArrayArrayCodec, BytesBytesCodec
+from zarr.core.array_spec import ArraySpec
+from zarr.core.common import parse_named_configuration
+from zarr.registry import register_codec
+
+if TYPE_CHECKING:
+    from typing import Self
+
+    from zarr.core.buffer import Buffer, NDBuffer
+    from zarr.core.common import ChunkCoords
+
+ZSTD_DICTIONARY = "zarrs_python.zstd_dictionary"
+SQUEEZE = "zarrs.squeeze"
+
+
+@dataclass(frozen=True)
+class ZstdDictionaryCodec(BytesBytesCodec):
+    """A zstd codec which compresses every chunk with the same dictionary.
+
+    The dictionary (e.g. trained with `zarrs.train_zstd_dictionary`) is stored in the
+    array metadata, and chunks are only encoded and decoded by the `ZarrsCodecPipeline`.
+    """
+
+    is_fixed_size = False
+
+    dictionary: bytes
+    level: int = 3
+    checksum: bool = False
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Self:
+        _, configuration = parse_named_configuration(data, ZSTD_DICTIONARY)
+        return cls(
+            dictionary=base64.b64decode(configuration["dictionary"]),
+            level=configuration.get("level", 3),
+            checksum=configuration.get("checksum", False),
+        )
+
+    def to_dict(self) -> dict[str, Any]:
+        return {
+            "name": ZSTD_DICTIONARY,
+            "configuration": {
+                "level": self.level,
+                "checksum": self.checksum,
+                "dictionary": base64.b64encode(self.dictionary).decode(),
+            },
+        }
+
+    async def _decode_single(
+        self, chunk_bytes: Buffer, chunk_spec: ArraySpec
+    ) -> Buffer:
+        raise NotImplementedError(
+            f"{ZSTD_DICTIONARY} is only supported by the ZarrsCodecPipeline"
+        )
+
+    async def _encode_single(
+        self, chunk_bytes: Buffer, chunk_spec: ArraySpec
+    ) -> Buffer:
+        raise NotImplementedError(
+            f"{ZSTD_DICTIONARY} is only supported by the ZarrsCodecPipeline"
+        )
+
+    def compute_encoded_size(
+        self, input_byte_length: int, chunk_spec: ArraySpec
+    ) -> int:
+        raise NotImplementedError
+
+
+register_codec(ZSTD_DICTIONARY, ZstdDictionaryCodec)
+
+
+def squeezed_shape(shape: ChunkCoords) -> ChunkCoords:
+    return tuple(size for size in shape if size != 1)
+
+
+@dataclass(frozen=True)
+class SqueezeCodec(ArrayArrayCodec):
+    """The `zarrs.squeeze` codec of `zarrs`, which removes the dimensions of chunks with a size of 1.
+
+    Chunks are encoded with fewer dimensions, e.g. the `(1, 64, 1)` chunks of a
+    `(16, 64, 8)` array are encoded as `(64,)` arrays.
+    """
+
+    is_fixed_size = True
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Self:
+        parse_named_configuration(data, require_configuration=False)
+        return cls()
+
+    def to_dict(self) -> dict[str, Any]:
+        return {"name": SQUEEZE}
+
+    def resolve_metadata(self, chunk_spec: ArraySpec) -> ArraySpec:
+        return ArraySpec(
+            squeezed_shape(chunk_spec.shape),
+            chunk_spec.dtype,
+            chunk_spec.fill_value,
+            chunk_spec.config,
+            chunk_spec.prototype,
+        )
+
+    async def _decode_single(
+        self, chunk_array: NDBuffer, chunk_spec: ArraySpec
+    ) -> NDBuffer:
+        return chunk_array.reshape(chunk_spec.shape)
+
+    async def _encode_single(
+        self, chunk_array: NDBuffer, chunk_spec: ArraySpec
+    ) -> NDBuffer:
+        return chunk_array.reshape(squeezed_shape(chunk_spec.shape))
+
+    def compute_encoded_size(
+        self, input_byte_length: int, chunk_spec: ArraySpec
+    ) -> int:
+        return input_byte_length
+
+
+register_codec(SQUEEZE, SqueezeCodec)
+register_codec("squeeze", SqueezeCodec)
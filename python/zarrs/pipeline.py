@@ -3,11 +3,12 @@ from __future__ import annotations
 import asyncio
 import json
 import re
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from typing import TYPE_CHECKING, TypedDict
 
 import numpy as np
 from zarr.abc.codec import Codec, CodecPipeline
+from zarr.codecs import VLenBytesCodec, VLenUTF8Codec
 from zarr.core import BatchedCodecPipeline
 from zarr.core.config import config
 
@@ -27,6 +28,7 @@ from .utils import (
     CollapsedDimensionError,
     DiscontiguousArrayError,
     FillValueNoneError,
+    make_chunk_info_for_rust,
     make_chunk_info_for_rust_with_indices,
 )
 
@@ -39,10 +41,12 @@ class UnsupportedMetadataError(Exception):
     pass
 
 
-def get_codec_pipeline_impl(codec_metadata_json: str) -> CodecPipelineImpl | None:
+def get_codec_pipeline_impl(
+    codec_metadata: list[dict[str, Any]] | dict[str, Any],
+) -> CodecPipelineImpl | None:
     try:
         return CodecPipelineImpl(
-            codec_metadata_json,
+            codec_metadata,
             validate_checksums=config.get("codec_pipeline.validate_checksums", None),
             store_empty_chunks=config.get("array.write_empty_chunks", None),
             chunk_concurrent_minimum=config.get(
@@ -52,38 +56,153 @@ def get_codec_pipeline_impl(codec_metadata_json: str) -> CodecPipelineImpl | Non
                 "codec_pipeline.chunk_concurrent_maximum", None
             ),
             num_threads=config.get("threading.max_workers", None),
+            storage_options=config.get("codec_pipeline.storage_options", None),
+            cache_max_bytes=config.get("codec_pipeline.cache_max_bytes", None),
+            cache_max_entries=config.get("codec_pipeline.cache_max_entries", None),
+            storage_transformers=config.get(
+                "codec_pipeline.storage_transformers", None
+            ),
+            memory_map=config.get("codec_pipeline.memory_map", None),
+            direct_io=config.get("codec_pipeline.direct_io", None),
+            retry_max_attempts=config.get("codec_pipeline.retry_max_attempts", None),
+            retry_base_delay=config.get("codec_pipeline.retry_base_delay", None),
+            retry_jitter=config.get("codec_pipeline.retry_jitter", None),
+            store_timeout=config.get("codec_pipeline.store_timeout", None),
+            timeout=config.get("codec_pipeline.timeout", None),
+            read_only=config.get("codec_pipeline.read_only", False),
+            http_options=config.get("codec_pipeline.http_options", None),
+            disk_cache_directory=config.get(
+                "codec_pipeline.disk_cache_directory", None
+            ),
+            disk_cache_max_bytes=config.get(
+                "codec_pipeline.disk_cache_max_bytes", None
+            ),
+            key_transforms=config.get("codec_pipeline.key_transforms", None),
+            io_concurrent_limit=config.get("codec_pipeline.io_concurrent_limit", None),
+            multipart_part_size=config.get("codec_pipeline.multipart_part_size", None),
+            multipart_concurrency=config.get(
+                "codec_pipeline.multipart_concurrency", None
+            ),
+            write_buffer_max_bytes=config.get(
+                "codec_pipeline.write_buffer_max_bytes", None
+            ),
+            partial_encoding=config.get("codec_pipeline.partial_encoding", None),
+            partial_decoder_cache_max_entries=config.get(
+                "codec_pipeline.partial_decoder_cache_max_entries", None
+            ),
+            blosc_nthreads=config.get("codec_pipeline.blosc_nthreads", None),
+            blosc_blocksize=config.get("codec_pipeline.blosc_blocksize", None),
+            blosc_shuffle=config.get("codec_pipeline.blosc_shuffle", None),
+            unsafe_skip_write_checksums=config.get(
+                "codec_pipeline.unsafe_skip_write_checksums", None
+            ),
+            codec_concurrent_target=config.get(
+                "codec_pipeline.codec_concurrent_target", None
+            ),
+            experimental_codecs=config.get("codec_pipeline.experimental_codecs", None),
         )
     except TypeError as e:
-        if re.match(r"codec (delta|zlib) is not supported", str(e)):
+        # Fall back to zarr-python for unsupported (or disabled experimental) codecs
+        if re.match(
+            r"codec (zlib|(numcodecs\.)?adler32) is not supported"
+            r"|codec \S+ is experimental",
+            str(e),
+        ):
             return None
         else:
             raise e
 
 
+# numcodecs.zarr3 codecs that are converted from their zarr V2 configuration
+NUMCODECS_AS_V2 = {"numcodecs.zfpy"}
+# numcodecs.zarr3 codecs with the same configuration as a zarrs codec of another name
+NUMCODECS_RENAMED = {
+    "numcodecs.bitround": "bitround",
+    "numcodecs.fixedscaleoffset": "fixedscaleoffset",
+    "numcodecs.fletcher32": "fletcher32",
+    "numcodecs.pcodec": "pcodec",
+}
+# The data types of the elements of chunks encoded by variable length codecs
+VLEN_CODEC_DATA_TYPES = {
+    "vlen-utf8": "string",
+    "vlen-bytes": "bytes",
+    # Elements of zarr V2 `vlen-array` codecs are the bytes of arrays of its `dtype`
+    "vlen-array": "bytes",
+}
+
+
+def codec_metadata_v2(codecs: Iterable[Codec]) -> dict[str, Any] | None:
+    """The filters and compressor of a zarr V2 array, which has a single `V2Codec`."""
+    codecs = tuple(codecs)
+    if len(codecs) != 1 or codecs[0].__class__.__name__ != "V2Codec":
+        return None
+    codec_dict = codecs[0].to_dict()
+    filters = codec_dict.get("filters", None)
+    compressor = codec_dict.get("compressor", None)
+    return {
+        "filters": None
+        if filters is None
+        else [filter.get_config() for filter in filters],
+        "compressor": None if compressor is None else compressor.get_config(),
+    }
+
+
 def codecs_to_dict(codecs: Iterable[Codec]) -> Generator[dict[str, Any], None, None]:
     for codec in codecs:
         if codec.__class__.__name__ == "V2Codec":
-            codec_dict = codec.to_dict()
-            if codec_dict.get("filters", None) is not None:
-                filters = [
-                    json.dumps(filter.get_config())
-                    for filter in codec_dict.get("filters")
-                ]
-            else:
-                filters = None
-            if codec_dict.get("compressor", None) is not None:
-                compressor = json.dumps(codec_dict.get("compressor").get_config())
-            else:
-                compressor = None
-            codecs_v3 = codec_metadata_v2_to_v3(filters, compressor)
+            metadata_v2 = codec_metadata_v2([codec])
+            filters = metadata_v2["filters"]
+            compressor = metadata_v2["compressor"]
+            codecs_v3 = codec_metadata_v2_to_v3(
+                None if filters is None else [json.dumps(filter) for filter in filters],
+                None if compressor is None else json.dumps(compressor),
+            )
             for codec in codecs_v3:
                 yield json.loads(codec)
         else:
-            yield codec.to_dict()
+            codec_dict = codec.to_dict()
+            if codec_dict["name"] in NUMCODECS_AS_V2:
+                # numcodecs.zarr3 codecs have the configuration of the zarr V2 codec
+                config = {
+                    "id": codec_dict["name"].removeprefix("numcodecs."),
+                    **codec_dict.get("configuration", {}),
+                }
+                for codec_v3 in codec_metadata_v2_to_v3(None, json.dumps(config)):
+                    yield json.loads(codec_v3)
+            elif codec_dict["name"] in NUMCODECS_RENAMED:
+                yield {**codec_dict, "name": NUMCODECS_RENAMED[codec_dict["name"]]}
+            else:
+                yield codec_dict
+
+
+def vlen_array_elements(
+    vlen_codec: dict[str, Any], elements: list[bytes]
+) -> np.ndarray[Any, np.dtype[np.object_]]:
+    """A 1D object array of the arrays encoded as bytes by a `vlen-array` codec."""
+    dtype = np.dtype(vlen_codec["configuration"]["dtype"])
+    # Elements are assigned one by one, so arrays of the same length are not stacked
+    array = np.empty(len(elements), dtype=object)
+    for index, element in enumerate(elements):
+        array[index] = np.frombuffer(element, dtype=dtype)
+    return array
+
+
+def check_byte_order(dtype: np.dtype[Any]) -> None:
+    """Raise if `dtype` has a non-native byte order which must not be swapped.
+
+    Chunks are decoded and encoded with native byte order, so the elements of arrays
+    with a non-native byte order are only swapped if `codec_pipeline.swap_byte_order`
+    is true.
+    """
+    if not dtype.isnative and not config.get("codec_pipeline.swap_byte_order", False):
+        raise RuntimeError(
+            f"Non-native byte order of {dtype} not supported, "
+            "set `codec_pipeline.swap_byte_order` to swap the bytes of each element"
+        )
 
 
 class ZarrsCodecPipelineState(TypedDict):
-    codec_metadata_json: str
+    codec_metadata: list[dict[str, Any]] | dict[str, Any]
     codecs: tuple[Codec, ...]
 
 
@@ -91,36 +210,75 @@ class ZarrsCodecPipelineState(TypedDict):
 class ZarrsCodecPipeline(CodecPipeline):
     codecs: tuple[Codec, ...]
     impl: CodecPipelineImpl | None
-    codec_metadata_json: str
+    # The metadata of the codecs, or the filters and compressor of a zarr V2 array
+    codec_metadata: list[dict[str, Any]] | dict[str, Any]
     python_impl: BatchedCodecPipeline
+    # The codecs of a zarr V2 array until they are completed by the data type and order of its chunks
+    codec_metadata_v2: dict[str, Any] | None = None
+    # The chunk specs (shape, data type, and fill value) validated by `impl`
+    validated_chunk_specs: set[tuple[Any, ...]] = field(
+        default_factory=set, init=False, repr=False, compare=False
+    )
 
     def __getstate__(self) -> ZarrsCodecPipelineState:
-        return {"codec_metadata_json": self.codec_metadata_json, "codecs": self.codecs}
+        return {"codec_metadata": self.codec_metadata, "codecs": self.codecs}
 
     def __setstate__(self, state: ZarrsCodecPipelineState):
         self.codecs = state["codecs"]
-        self.codec_metadata_json = state["codec_metadata_json"]
-        self.impl = get_codec_pipeline_impl(self.codec_metadata_json)
+        self.codec_metadata = state["codec_metadata"]
+        self.impl = get_codec_pipeline_impl(self.codec_metadata)
         self.python_impl = BatchedCodecPipeline.from_codecs(self.codecs)
+        metadata = self.codec_metadata
+        self.codec_metadata_v2 = (
+            metadata if isinstance(metadata, dict) and "dtype" not in metadata else None
+        )
+        self.validated_chunk_specs = set()
 
     def evolve_from_array_spec(self, array_spec: ArraySpec) -> Self:
         raise NotImplementedError("evolve_from_array_spec")
 
     @classmethod
     def from_codecs(cls, codecs: Iterable[Codec]) -> Self:
-        codec_metadata = list(codecs_to_dict(codecs))
-        codec_metadata_json = json.dumps(codec_metadata)
+        codecs = tuple(codecs)
+        # The filters and compressor of zarr V2 arrays are converted to codecs in Rust
+        metadata_v2 = codec_metadata_v2(codecs)
+        codec_metadata = (
+            list(codecs_to_dict(codecs)) if metadata_v2 is None else metadata_v2
+        )
         # TODO: upstream zarr-python has not settled on how to deal with configs yet
         # Should they be checked when an array is created, or when an operation is performed?
         # https://github.com/zarr-developers/zarr-python/issues/2409
         # https://github.com/zarr-developers/zarr-python/pull/2429#issuecomment-2566976567
         return cls(
-            codec_metadata_json=codec_metadata_json,
+            codec_metadata=codec_metadata,
             codecs=tuple(codecs),
-            impl=get_codec_pipeline_impl(codec_metadata_json),
+            impl=get_codec_pipeline_impl(codec_metadata),
             python_impl=BatchedCodecPipeline.from_codecs(codecs),
+            codec_metadata_v2=metadata_v2,
         )
 
+    def _complete_codec_metadata_v2(self, chunk_spec: ArraySpec) -> None:
+        """Recreate the implementation of a zarr V2 array for its chunks.
+
+        The data type and order of the chunks determine the byte order of encoded
+        elements and whether chunks are transposed, but are not passed to `from_codecs`.
+        """
+        if self.codec_metadata_v2 is None:
+            return
+        metadata = {
+            **self.codec_metadata_v2,
+            "order": chunk_spec.order,
+            "shape": list(chunk_spec.shape),
+        }
+        # Only numeric data types have a byte order
+        if chunk_spec.dtype.kind in "biufcmM":
+            metadata["dtype"] = chunk_spec.dtype.str
+        self.flush()
+        self.codec_metadata = metadata
+        self.impl = get_codec_pipeline_impl(self.codec_metadata)
+        self.codec_metadata_v2 = None
+        self.validated_chunk_specs.clear()
+
     @property
     def supports_partial_decode(self) -> bool:
         return False
@@ -132,6 +290,11 @@ class ZarrsCodecPipeline(CodecPipeline):
     def __iter__(self) -> Iterator[Codec]:
         yield from self.codecs
 
+    def flush(self) -> None:
+        """Store the chunks with partial writes pending in the write buffer, see `codec_pipeline.write_buffer_max_bytes`."""
+        if self.impl is not None:
+            self.impl.flush()
+
     def validate(
         self, *, shape: ChunkCoords, dtype: np.dtype[Any], chunk_grid: ChunkGrid
     ) -> None:
@@ -158,15 +321,22 @@ class ZarrsCodecPipeline(CodecPipeline):
             tuple[ByteGetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
         ],
         out: NDBuffer,  # type: ignore
-        drop_axes: tuple[int, ...] = (),  # FIXME: unused
+        drop_axes: tuple[int, ...] = (),
     ) -> None:
         # FIXME: Error if array is not in host memory
-        if not out.dtype.isnative:
-            raise RuntimeError("Non-native byte order not supported")
+        check_byte_order(out.dtype)
+        batch_info = list(batch_info)
+        if batch_info:
+            self._complete_codec_metadata_v2(batch_info[0][1])
+        vlen_codec = self._vlen_codec
+        if self.impl is not None and vlen_codec is not None:
+            await self._read_vlen(batch_info, out, drop_axes, vlen_codec)
+            return None
         try:
             if self.impl is None:
                 raise UnsupportedMetadataError()
             self._raise_error_on_unsupported_batch_dtype(batch_info)
+            self._raise_error_on_unsupported_codecs(batch_info)
             chunks_desc = make_chunk_info_for_rust_with_indices(
                 batch_info, drop_axes, out.shape
             )
@@ -177,15 +347,29 @@ class ZarrsCodecPipeline(CodecPipeline):
             UnsupportedDataTypeError,
             FillValueNoneError,
         ):
+            # Chunks read by zarr-python must include the writes pending in the write buffer
+            await asyncio.to_thread(self.flush)
             await self.python_impl.read(batch_info, out, drop_axes)
             return None
         else:
-            out: NDArrayLike = out.as_ndarray_like()
-            await asyncio.to_thread(
-                self.impl.retrieve_chunks_and_apply_index,
-                chunks_desc,
-                out,
-            )
+            if not chunks_desc:
+                return None
+            out_np: NDArrayLike = out.as_ndarray_like()
+            if out_np.dtype.isnative:
+                await asyncio.to_thread(
+                    self.impl.retrieve_chunks_and_apply_index, chunks_desc, out_np
+                )
+                return None
+            # Chunks are decoded with native byte order into a native view of a non-native
+            # output, whose elements are swapped in place before and after, so it is not copied
+            out_native = out_np.view(out_np.dtype.newbyteorder("="))
+            out_np.byteswap(inplace=True)
+            try:
+                await asyncio.to_thread(
+                    self.impl.retrieve_chunks_and_apply_index, chunks_desc, out_native
+                )
+            finally:
+                out_np.byteswap(inplace=True)
             return None
 
     async def write(
@@ -196,10 +380,19 @@ class ZarrsCodecPipeline(CodecPipeline):
         value: NDBuffer,  # type: ignore
         drop_axes: tuple[int, ...] = (),
     ) -> None:
+        check_byte_order(value.dtype)
+        batch_info = list(batch_info)
+        if batch_info:
+            self._complete_codec_metadata_v2(batch_info[0][1])
+        vlen_codec = self._vlen_codec
+        if self.impl is not None and vlen_codec is not None:
+            await self._write_vlen(batch_info, value, drop_axes, vlen_codec)
+            return None
         try:
             if self.impl is None:
                 raise UnsupportedMetadataError()
             self._raise_error_on_unsupported_batch_dtype(batch_info)
+            self._raise_error_on_unsupported_codecs(batch_info)
             chunks_desc = make_chunk_info_for_rust_with_indices(
                 batch_info, drop_axes, value.shape
             )
@@ -210,32 +403,194 @@ class ZarrsCodecPipeline(CodecPipeline):
             UnsupportedDataTypeError,
             FillValueNoneError,
         ):
+            # Pending writes are stored first, so they do not overwrite the writes of zarr-python when flushed
+            await asyncio.to_thread(self.flush)
             await self.python_impl.write(batch_info, value, drop_axes)
             return None
         else:
+            if not chunks_desc:
+                return None
             # FIXME: Error if array is not in host memory
             value_np: NDArrayLike | np.ndarray = value.as_ndarray_like()
             if not value_np.dtype.isnative:
+                # The value must not be modified, so it is copied with native byte order
                 value_np = np.ascontiguousarray(
                     value_np, dtype=value_np.dtype.newbyteorder("=")
                 )
-            elif not value_np.flags.c_contiguous:
+            elif not (
+                value_np.flags.c_contiguous
+                or value_np.flags.f_contiguous
+                # A broadcast value (e.g. `np.broadcast_to`) is written as a constant without materializing it
+                or not any(value_np.strides)
+            ):
                 value_np = np.ascontiguousarray(value_np)
             await asyncio.to_thread(
                 self.impl.store_chunks_with_indices, chunks_desc, value_np
             )
             return None
 
+    @property
+    def _vlen_codec(self) -> dict[str, Any] | None:
+        """The variable length codec of the array with its `name` and configuration, if any.
+
+        The string and bytes data types are both object arrays in numpy, so the data
+        type of the elements is implied by the codec.
+        """
+        metadata_v2 = codec_metadata_v2(self.codecs)
+        if metadata_v2 is None:
+            codecs = [
+                codec.to_dict()
+                for codec in self.codecs
+                if isinstance(codec, VLenBytesCodec | VLenUTF8Codec)
+            ]
+        else:
+            # The object codec of a zarr V2 array is one of its filters
+            codecs = [
+                {"name": filter["id"], "configuration": filter}
+                for filter in metadata_v2["filters"] or []
+            ]
+        return next(
+            (codec for codec in codecs if codec["name"] in VLEN_CODEC_DATA_TYPES), None
+        )
+
+    async def _read_vlen(
+        self,
+        batch_info: Iterable[
+            tuple[ByteGetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
+        ],
+        out: NDBuffer,  # type: ignore
+        drop_axes: tuple[int, ...],
+        vlen_codec: dict[str, Any],
+    ) -> None:
+        # Variable length elements are exchanged with Rust as lists of Python objects for each whole chunk,
+        # which are indexed here like the chunks of zarr-python
+        batch_info = list(batch_info)
+        data_type = VLEN_CODEC_DATA_TYPES[vlen_codec["name"]]
+        chunks_elements = await asyncio.to_thread(
+            self.impl.retrieve_chunks_vlen,
+            [
+                make_chunk_info_for_rust(byte_getter, chunk_spec, data_type=data_type)
+                for byte_getter, chunk_spec, *_ in batch_info
+            ],
+        )
+        out_np: NDArrayLike = out.as_ndarray_like()
+        for (_, chunk_spec, chunk_selection, out_selection, _), chunk_elements in zip(
+            batch_info, chunks_elements, strict=True
+        ):
+            if vlen_codec["name"] == "vlen-array":
+                chunk_array = vlen_array_elements(vlen_codec, chunk_elements)
+            else:
+                chunk_array = np.array(chunk_elements, dtype=out_np.dtype)
+            chunk_array = chunk_array.reshape(chunk_spec.shape)
+            chunk_value = chunk_array[chunk_selection]
+            if drop_axes != ():
+                chunk_value = chunk_value.squeeze(axis=drop_axes)
+            out_np[out_selection] = chunk_value
+
+    async def _write_vlen(
+        self,
+        batch_info: Iterable[
+            tuple[ByteSetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
+        ],
+        value: NDBuffer,  # type: ignore
+        drop_axes: tuple[int, ...],
+        vlen_codec: dict[str, Any],
+    ) -> None:
+        batch_info = list(batch_info)
+        data_type = VLEN_CODEC_DATA_TYPES[vlen_codec["name"]]
+        chunks = [
+            make_chunk_info_for_rust(byte_setter, chunk_spec, data_type=data_type)
+            for byte_setter, chunk_spec, *_ in batch_info
+        ]
+        # Chunks that are not entirely overwritten are updated from their existing elements
+        partial = [
+            index
+            for index, (*_, is_complete_chunk) in enumerate(batch_info)
+            if not is_complete_chunk
+        ]
+        partial_elements = await asyncio.to_thread(
+            self.impl.retrieve_chunks_vlen, [chunks[index] for index in partial]
+        )
+        if vlen_codec["name"] == "vlen-array":
+            partial_elements = [
+                vlen_array_elements(vlen_codec, elements)
+                for elements in partial_elements
+            ]
+        existing = dict(zip(partial, partial_elements, strict=True))
+
+        value_np: NDArrayLike = value.as_ndarray_like()
+        chunks_elements = []
+        for index, (_, chunk_spec, chunk_selection, out_selection, _) in enumerate(
+            batch_info
+        ):
+            chunk_array = (
+                np.array(existing[index], dtype=object).reshape(chunk_spec.shape)
+                if index in existing
+                else np.empty(chunk_spec.shape, dtype=object)
+            )
+            if chunk_selection == () or value_np.shape == ():
+                chunk_value = value_np
+            else:
+                chunk_value = value_np[out_selection]
+                if drop_axes != ():
+                    chunk_value = chunk_value[
+                        tuple(
+                            None if axis in drop_axes else slice(None)
+                            for axis in range(chunk_spec.ndim)
+                        )
+                    ]
+            chunk_array[chunk_selection] = chunk_value
+            elements = chunk_array.ravel().tolist()
+            if vlen_codec["name"] == "vlen-array":
+                dtype = np.dtype(vlen_codec["configuration"]["dtype"])
+                elements = [
+                    np.asarray(element, dtype=dtype).tobytes() for element in elements
+                ]
+            chunks_elements.append((chunks[index], elements))
+        await asyncio.to_thread(self.impl.store_chunks_vlen, chunks_elements)
+
+    def _raise_error_on_unsupported_codecs(
+        self,
+        batch_info: list[
+            tuple[ByteSetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
+        ],
+    ) -> None:
+        """Fall back to zarr-python for an array if a codec does not support its chunks.
+
+        The codec chain is created without the data type of the array, so a codec
+        rejecting it (e.g. `bitround` with integers) is only detected with the chunks.
+        """
+        if not batch_info:
+            return
+        # The chunks of a batch have the same data type, and are validated once per spec
+        byte_getter, chunk_spec, *_ = batch_info[0]
+        key = (chunk_spec.shape, chunk_spec.dtype, repr(chunk_spec.fill_value))
+        if key in self.validated_chunk_specs:
+            return
+        try:
+            self.impl.validate(make_chunk_info_for_rust(byte_getter, chunk_spec))
+            self.validated_chunk_specs.add(key)
+        except TypeError as err:
+            # Chunks read by zarr-python must include the writes pending in the write buffer
+            self.flush()
+            self.impl = None
+            raise UnsupportedMetadataError(str(err)) from err
+
     def _raise_error_on_unsupported_batch_dtype(
         self,
         batch_info: Iterable[
             tuple[ByteSetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
         ],
     ):
-        # https://github.com/LDeakin/zarrs/blob/0532fe983b7b42b59dbf84e50a2fe5e6f7bad4ce/zarrs_metadata/src/v2_to_v3.rs#L289-L293 for VSUMm
+        # https://github.com/LDeakin/zarrs/blob/0532fe983b7b42b59dbf84e50a2fe5e6f7bad4ce/zarrs_metadata/src/v2_to_v3.rs#L289-L293 for SU
         # Further, our pipeline does not support variable-length objects due to limitations on decode_into, so object is also out
+        # Structured and void data types (V) are raw bits, which must have a non-zero size
+        # Fixed length unicode strings (U) are also raw bits, which are not byte swapped so must be native
+        # Datetimes and timedeltas (M, m) are int64
         if any(
-            info.dtype.kind in {"V", "S", "U", "M", "m", "O"}
+            info.dtype.kind in {"S", "O"}
+            or (info.dtype.kind in {"V", "U"} and info.dtype.itemsize == 0)
+            or (info.dtype.kind == "U" and not info.dtype.isnative)
             for (_, info, _, _, _) in batch_info
         ):
             raise UnsupportedDataTypeError()
@@ -0,0 +1,186 @@
+from __future__ import annotations
+
+from types import SimpleNamespace
+from typing import TYPE_CHECKING, Any
+
+import numpy as np
+from zarr.core.chunk_grids import RegularChunkGrid
+from zarr.core.indexing import is_integer, replace_ellipsis
+
+from .pipeline import ZarrsCodecPipeline
+from .utils import get_implicit_fill_value, get_shape_for_orthogonal_selection
+
+if TYPE_CHECKING:
+    import zarr
+    from zarr.core.indexing import Fields, OrthogonalSelection
+
+    from ._internal import CodecPipelineImpl
+
+
+def _get_chunk_key_encoding(array: zarr.Array) -> tuple[str, str] | None:
+    metadata = array.metadata
+    if metadata.zarr_format == 2:
+        prefix, separator = "", metadata.dimension_separator or "."
+    else:
+        encoding = metadata.chunk_key_encoding
+        separator = encoding.separator
+        prefix = f"c{separator}" if encoding.name == "default" else ""
+    if array.path:
+        prefix = f"{array.path}/{prefix}"
+    # Custom chunk key encodings are not supported
+    chunk_coords = tuple(range(array.ndim))
+    key = metadata.encode_chunk_key(chunk_coords)
+    if f"{array.path}/{key}".lstrip("/") != prefix + separator.join(
+        map(str, chunk_coords)
+    ):
+        return None
+    return prefix, separator
+
+
+def _get_impl(
+    array: zarr.Array,
+) -> tuple[CodecPipelineImpl, tuple[str, str]] | None:
+    codec_pipeline = array._async_array.codec_pipeline
+    if (
+        isinstance(codec_pipeline, ZarrsCodecPipeline)
+        and codec_pipeline.codec_metadata_v2 is not None
+    ):
+        codec_pipeline._complete_codec_metadata_v2(
+            SimpleNamespace(
+                dtype=array.dtype, order=array.order, shape=array.metadata.chunks
+            )
+        )
+    if (
+        not isinstance(codec_pipeline, ZarrsCodecPipeline)
+        or codec_pipeline.impl is None
+        or array.ndim == 0
+        or not isinstance(array.metadata.chunk_grid, RegularChunkGrid)
+        or not array.dtype.isnative
+        or array.dtype.kind in {"S", "O"}
+        or (array.dtype.kind in {"V", "U"} and array.dtype.itemsize == 0)
+    ):
+        return None
+    chunk_key_encoding = _get_chunk_key_encoding(array)
+    if chunk_key_encoding is None:
+        return None
+    return codec_pipeline.impl, chunk_key_encoding
+
+
+def _normalize_selection(
+    selection: OrthogonalSelection, shape: tuple[int, ...]
+) -> tuple[list[slice | np.ndarray], tuple[int, ...]] | None:
+    normalized: list[slice | np.ndarray] = []
+    drop_axes: list[int] = []
+    for axis, (dim_selection, length) in enumerate(
+        zip(replace_ellipsis(selection, shape), shape, strict=True)
+    ):
+        if is_integer(dim_selection):
+            index = int(dim_selection)
+            index = index + length if index < 0 else index
+            if not 0 <= index < length:
+                msg = f"index {dim_selection} is out of bounds for axis {axis} with length {length}"
+                raise IndexError(msg)
+            normalized.append(slice(index, index + 1))
+            drop_axes.append(axis)
+        elif isinstance(dim_selection, slice):
+            normalized.append(dim_selection)
+        else:
+            dim_selection = np.asarray(dim_selection)
+            if dim_selection.ndim != 1:
+                return None
+            if dim_selection.dtype == np.bool_:
+                normalized.append(np.ascontiguousarray(dim_selection))
+            elif np.issubdtype(dim_selection.dtype, np.integer):
+                dim_selection = np.where(
+                    dim_selection < 0, dim_selection + length, dim_selection
+                )
+                normalized.append(np.ascontiguousarray(dim_selection, dtype=np.int64))
+            else:
+                return None
+    return normalized, tuple(drop_axes)
+
+
+def _get_chunk_spec(array: zarr.Array) -> SimpleNamespace:
+    fill_value = np.array(
+        get_implicit_fill_value(array.dtype, array.fill_value), dtype=array.dtype
+    )
+    return SimpleNamespace(
+        shape=array.metadata.chunk_grid.chunk_shape,
+        dtype=array.dtype,
+        # A fixed length unicode string scalar is a `str`, so the padded array is passed
+        fill_value=fill_value if array.dtype.kind == "U" else fill_value[()],
+    )
+
+
+def get_orthogonal_selection(
+    array: zarr.Array,
+    selection: OrthogonalSelection,
+    *,
+    fields: Fields | None = None,
+) -> np.ndarray:
+    """Read an orthogonal selection of an array, like `array.oindex[selection]`.
+
+    The chunks intersected by the selection are computed in Rust rather than by zarr-python, which is much faster for selections spanning many chunks.
+    Arrays or selections that are not supported by the `ZarrsCodecPipeline` are read with `array.get_orthogonal_selection`.
+    Fields of arrays with a structured data type (`fields`) are always selected by zarr-python.
+    """
+    prepared = _get_impl(array) if fields is None else None
+    normalized = _normalize_selection(selection, array.shape) if prepared else None
+    if prepared is None or normalized is None:
+        return array.get_orthogonal_selection(selection, fields=fields)
+    impl, chunk_key_encoding = prepared
+    normalized_selection, drop_axes = normalized
+    out = np.empty(
+        get_shape_for_orthogonal_selection(normalized_selection, array.shape),
+        dtype=array.dtype,
+    )
+    if out.size:
+        impl.retrieve_selection(
+            array.store,
+            chunk_key_encoding,
+            _get_chunk_spec(array),
+            array.shape,
+            normalized_selection,
+            out,
+        )
+    return out.squeeze(axis=drop_axes)
+
+
+def set_orthogonal_selection(
+    array: zarr.Array,
+    selection: OrthogonalSelection,
+    value: Any,
+    *,
+    fields: Fields | None = None,
+) -> None:
+    """Write an orthogonal selection of an array, like `array.oindex[selection] = value`.
+
+    See `get_orthogonal_selection`.
+    """
+    prepared = _get_impl(array) if fields is None and not array.read_only else None
+    normalized = _normalize_selection(selection, array.shape) if prepared else None
+    if prepared is None or normalized is None:
+        array.set_orthogonal_selection(selection, value, fields=fields)
+        return
+    impl, chunk_key_encoding = prepared
+    normalized_selection, drop_axes = normalized
+    shape = get_shape_for_orthogonal_selection(normalized_selection, array.shape)
+    value = np.asarray(value, dtype=array.dtype)
+    value = np.broadcast_to(
+        value,
+        tuple(length for axis, length in enumerate(shape) if axis not in drop_axes),
+    )
+    value = np.expand_dims(value, drop_axes)
+    if not (
+        value.flags.c_contiguous or value.flags.f_contiguous or not any(value.strides)
+    ):
+        value = np.ascontiguousarray(value)
+    if value.size:
+        impl.store_selection(
+            array.store,
+            chunk_key_encoding,
+            _get_chunk_spec(array),
+            array.shape,
+            normalized_selection,
+            value,
+        )
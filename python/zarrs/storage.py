@@ -0,0 +1,174 @@
+from __future__ import annotations
+
+import os
+import sqlite3
+import threading
+from typing import TYPE_CHECKING
+
+from zarr.abc.store import (
+    OffsetByteRequest,
+    RangeByteRequest,
+    Store,
+    SuffixByteRequest,
+)
+
+if TYPE_CHECKING:
+    from collections.abc import AsyncIterator, Iterable
+    from typing import Any, Self
+
+    from zarr.abc.store import ByteRequest
+    from zarr.core.buffer import Buffer, BufferPrototype
+
+
+def _slice_bytes(value: bytes, byte_range: ByteRequest | None) -> bytes:
+    if byte_range is None:
+        return value
+    if isinstance(byte_range, RangeByteRequest):
+        return value[byte_range.start : byte_range.end]
+    if isinstance(byte_range, OffsetByteRequest):
+        return value[byte_range.offset :]
+    if isinstance(byte_range, SuffixByteRequest):
+        return value[-byte_range.suffix :] if byte_range.suffix > 0 else b""
+    raise TypeError(f"Unexpected byte_range, got {byte_range}")
+
+
+class SQLiteStore(Store):
+    """A store where each key is a row of a single SQLite database file.
+
+    Chunks are read/written by the `ZarrsCodecPipeline` directly, so arrays with millions of chunks are stored in a single file without going through Python.
+
+    Parameters
+    ----------
+    path
+        The path of the database, which is created if it does not exist.
+    read_only
+        Whether the store is read-only.
+    """
+
+    supports_writes: bool = True
+    supports_deletes: bool = True
+    supports_partial_writes: bool = False
+    supports_listing: bool = True
+
+    path: str
+
+    def __init__(self, path: str | os.PathLike[str], *, read_only: bool = False):
+        super().__init__(read_only=read_only)
+        self.path = os.fspath(path)
+        self._lock = threading.Lock()
+        self._connection: sqlite3.Connection | None = None
+
+    @classmethod
+    def from_url(cls, url: str, *, read_only: bool = False) -> Self:
+        """Create a store from a `sqlite://path.db` URL."""
+        if not url.startswith("sqlite://"):
+            raise ValueError(f"Expected a sqlite:// URL, got {url}")
+        return cls(url.removeprefix("sqlite://"), read_only=read_only)
+
+    def __getstate__(self) -> dict[str, Any]:
+        state = self.__dict__.copy()
+        del state["_lock"]
+        del state["_connection"]
+        return state
+
+    def __setstate__(self, state: dict[str, Any]) -> None:
+        self.__dict__.update(state)
+        self._lock = threading.Lock()
+        self._connection = None
+
+    def _execute(self, sql: str, parameters: tuple[Any, ...] = ()) -> list[Any]:
+        with self._lock:
+            if self._connection is None:
+                if self.read_only:
+                    self._connection = sqlite3.connect(
+                        f"file:{self.path}?mode=ro", uri=True, check_same_thread=False
+                    )
+                else:
+                    self._connection = sqlite3.connect(
+                        self.path, check_same_thread=False
+                    )
+                    self._connection.execute(
+                        "CREATE TABLE IF NOT EXISTS zarr (key TEXT PRIMARY KEY, value BLOB NOT NULL)"
+                    )
+            with self._connection:
+                return self._connection.execute(sql, parameters).fetchall()
+
+    def close(self) -> None:
+        with self._lock:
+            if self._connection is not None:
+                self._connection.close()
+                self._connection = None
+        super().close()
+
+    def __eq__(self, other: object) -> bool:
+        return (
+            isinstance(other, type(self))
+            and self.path == other.path
+            and self.read_only == other.read_only
+        )
+
+    def __str__(self) -> str:
+        return f"sqlite://{self.path}"
+
+    def __repr__(self) -> str:
+        return f"SQLiteStore('{self}')"
+
+    async def get(
+        self,
+        key: str,
+        prototype: BufferPrototype,
+        byte_range: ByteRequest | None = None,
+    ) -> Buffer | None:
+        rows = self._execute("SELECT value FROM zarr WHERE key = ?", (key,))
+        if not rows:
+            return None
+        return prototype.buffer.from_bytes(_slice_bytes(rows[0][0], byte_range))
+
+    async def get_partial_values(
+        self,
+        prototype: BufferPrototype,
+        key_ranges: Iterable[tuple[str, ByteRequest | None]],
+    ) -> list[Buffer | None]:
+        return [
+            await self.get(key, prototype, byte_range) for key, byte_range in key_ranges
+        ]
+
+    async def exists(self, key: str) -> bool:
+        return bool(self._execute("SELECT 1 FROM zarr WHERE key = ?", (key,)))
+
+    async def set(self, key: str, value: Buffer) -> None:
+        self._check_writable()
+        self._execute(
+            "INSERT OR REPLACE INTO zarr (key, value) VALUES (?, ?)",
+            (key, value.to_bytes()),
+        )
+
+    async def delete(self, key: str) -> None:
+        self._check_writable()
+        self._execute("DELETE FROM zarr WHERE key = ?", (key,))
+
+    async def set_partial_values(
+        self, key_start_values: Iterable[tuple[str, int, bytes]]
+    ) -> None:
+        raise NotImplementedError
+
+    async def list(self) -> AsyncIterator[str]:
+        for (key,) in self._execute("SELECT key FROM zarr ORDER BY key"):
+            yield key
+
+    async def list_prefix(self, prefix: str) -> AsyncIterator[str]:
+        for (key,) in self._execute(
+            "SELECT key FROM zarr WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+            (prefix,),
+        ):
+            yield key
+
+    async def list_dir(self, prefix: str) -> AsyncIterator[str]:
+        prefix = prefix.rstrip("/")
+        prefix = f"{prefix}/" if prefix else ""
+        seen = set()
+        async for key in self.list_prefix(prefix):
+            child = key.removeprefix(prefix).split("/", 1)[0]
+            if child not in seen:
+                seen.add(child)
+                yield child
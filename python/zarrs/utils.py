@@ -39,28 +39,39 @@ class FillValueNoneError(Exception):
 # This is a (mostly) copy of the function from zarr.core.indexing that fixes:
 #   DeprecationWarning: Conversion of an array with ndim > 0 to a scalar is deprecated
 # TODO: Upstream this fix
-def make_slice_selection(selection: tuple[np.ndarray | float]) -> list[slice]:
-    ls: list[slice] = []
+# Contiguous selections are returned as `(start, stop, step)` tuples, which are cheaper to build than slices
+def make_slice_selection(
+    selection: tuple[np.ndarray | float],
+) -> list[slice | tuple[int, int, int] | np.ndarray]:
+    ls: list[slice | tuple[int, int, int] | np.ndarray] = []
     for dim_selection in selection:
         if is_integer(dim_selection):
-            ls.append(slice(int(dim_selection), int(dim_selection) + 1, 1))
+            ls.append((int(dim_selection), int(dim_selection) + 1, 1))
+        elif isinstance(dim_selection, np.ndarray) and dim_selection.dtype == np.bool_:
+            # Boolean masks are converted to indices in Rust
+            ls.append(np.ascontiguousarray(dim_selection.ravel()))
         elif isinstance(dim_selection, np.ndarray):
+            # Orthogonal selections are expanded with `np.ix_`, so each array spans a single axis
+            if sum(length > 1 for length in dim_selection.shape) > 1:
+                raise CollapsedDimensionError(dim_selection.shape)
             dim_selection = dim_selection.ravel()
             if len(dim_selection) == 1:
-                ls.append(
-                    slice(int(dim_selection.item()), int(dim_selection.item()) + 1, 1)
-                )
+                ls.append((int(dim_selection.item()), int(dim_selection.item()) + 1, 1))
             else:
                 diff = np.diff(dim_selection)
-                if (diff != 1).any() and (diff != 0).any():
-                    raise DiscontiguousArrayError(diff)
-                ls.append(slice(dim_selection[0], dim_selection[-1] + 1, 1))
+                if (diff == 1).all():
+                    ls.append((int(dim_selection[0]), int(dim_selection[-1]) + 1, 1))
+                else:
+                    # Discontiguous indices are gathered and scattered in Rust
+                    ls.append(np.ascontiguousarray(dim_selection, dtype=np.int64))
         else:
             ls.append(dim_selection)
     return ls
 
 
-def selector_tuple_to_slice_selection(selector_tuple: SelectorTuple) -> list[slice]:
+def selector_tuple_to_slice_selection(
+    selector_tuple: SelectorTuple,
+) -> list[slice | tuple[int, int, int] | np.ndarray]:
     if isinstance(selector_tuple, slice):
         return [selector_tuple]
     if all(isinstance(s, slice) for s in selector_tuple):
@@ -93,13 +104,9 @@ def resulting_shape_from_index(
             # Integer index reduces dimension, so skip this dimension in array_shape
             basic_shape_index += 1
         elif isinstance(idx, slice):
-            if idx.step is not None and idx.step > 1:
-                raise DiscontiguousArrayError(
-                    "Step size greater than 1 is not supported"
-                )
             # Slice keeps dimension, adjust size accordingly
-            start, stop, _ = idx.indices(array_shape[basic_shape_index])
-            result_shape.append(stop - start)
+            start, stop, step = idx.indices(array_shape[basic_shape_index])
+            result_shape.append(len(range(start, stop, step)))
             basic_shape_index += 1
         elif idx is Ellipsis:
             # Calculate number of dimensions that Ellipsis should fill
@@ -139,12 +146,97 @@ def get_shape_for_selector(
     return resulting_shape_from_index(shape, selector_tuple, drop_axes, pad=pad)
 
 
+def get_shape_for_orthogonal_selection(
+    selection: list[slice | tuple[int, int, int] | np.ndarray], shape: tuple[int, ...]
+) -> tuple[int, ...]:
+    # Index arrays and masks select the cross product of their indices, like slices
+    return tuple(
+        len(range(*dim_selection.indices(length)))
+        if isinstance(dim_selection, slice)
+        # Tuples from `make_slice_selection` have bounds within the dimension
+        else len(range(*dim_selection))
+        if isinstance(dim_selection, tuple)
+        else np.count_nonzero(dim_selection)
+        if dim_selection.dtype == np.bool_
+        else len(dim_selection)
+        for dim_selection, length in zip(selection, shape)
+    )
+
+
+def is_coordinate_selection(selector_tuple: SelectorTuple) -> bool:
+    # zarr resolves `vindex` selections (including boolean masks) to the coordinates of each selected element
+    return (
+        isinstance(selector_tuple, tuple)
+        and len(selector_tuple) > 1
+        and all(
+            isinstance(dim_selection, np.ndarray)
+            and dim_selection.ndim == 1
+            and np.issubdtype(dim_selection.dtype, np.integer)
+            and len(dim_selection) == len(selector_tuple[0])
+            for dim_selection in selector_tuple
+        )
+    )
+
+
+def make_chunk_mask(
+    chunk_selection: tuple[np.ndarray, ...], chunk_shape: tuple[int, ...]
+) -> np.ndarray | None:
+    raveled = np.ravel_multi_index(chunk_selection, chunk_shape)
+    if (np.diff(raveled) <= 0).any():
+        return None
+    mask = np.zeros(chunk_shape, dtype=np.bool_)
+    mask[chunk_selection] = True
+    return mask
+
+
+def make_output_axes(
+    chunk_selection: SelectorTuple, drop_axes: tuple[int, ...], ndim: int
+) -> list[int | None]:
+    # Axes selected with an integer (or dropped after `np.ix_` expansion) have no axis in the output
+    selection = chunk_selection if isinstance(chunk_selection, tuple) else ()
+    dropped_axes = set(drop_axes) | {
+        axis
+        for axis, dim_selection in enumerate(selection)
+        if is_integer(dim_selection)
+    }
+    output_axes: list[int | None] = []
+    output_axis = 0
+    for axis in range(ndim):
+        if axis in dropped_axes:
+            output_axes.append(None)
+        else:
+            output_axes.append(output_axis)
+            output_axis += 1
+    return output_axes
+
+
 def get_implicit_fill_value(dtype: np.dtype, fill_value: Any) -> Any:
     if fill_value is None:
         fill_value = _default_fill_value(dtype)
     return fill_value
 
 
+def make_chunk_info_for_rust(
+    byte_getter: ByteGetter | ByteSetter,
+    chunk_spec: ArraySpec,
+    data_type: str | None = None,
+) -> Basic:
+    fill_value = get_implicit_fill_value(chunk_spec.dtype, chunk_spec.fill_value)
+    if chunk_spec.dtype.kind in {"V", "U"}:
+        # The fill value of a structured data type (e.g. a tuple) is passed as the bytes of a record
+        # and the fill value of a fixed length unicode string as its UTF-32 code units, padded to the itemsize
+        fill_value = np.array(fill_value, dtype=chunk_spec.dtype)
+    if fill_value is not chunk_spec.fill_value:
+        chunk_spec = ArraySpec(
+            chunk_spec.shape,
+            chunk_spec.dtype,
+            fill_value,
+            chunk_spec.config,
+            chunk_spec.prototype,
+        )
+    return Basic(byte_getter, chunk_spec, data_type=data_type)
+
+
 def make_chunk_info_for_rust_with_indices(
     batch_info: Iterable[
         tuple[ByteGetter | ByteSetter, ArraySpec, SelectorTuple, SelectorTuple, bool]
@@ -161,22 +253,33 @@ def make_chunk_info_for_rust_with_indices(
         out_selection,
         _,
     ) in batch_info:
-        if chunk_spec.fill_value is None:
-            chunk_spec = ArraySpec(
-                chunk_spec.shape,
-                chunk_spec.dtype,
-                get_implicit_fill_value(chunk_spec.dtype, chunk_spec.fill_value),
-                chunk_spec.config,
-                chunk_spec.prototype,
-            )
-        chunk_info = Basic(byte_getter, chunk_spec)
+        chunk_info = make_chunk_info_for_rust(byte_getter, chunk_spec)
         out_selection_as_slices = selector_tuple_to_slice_selection(out_selection)
+        if is_coordinate_selection(chunk_selection):
+            # Masks select unique coordinates in C order, otherwise the coordinates are passed as is
+            chunk_mask = make_chunk_mask(chunk_selection, chunk_spec.shape)
+            chunk_coordinates = (
+                None
+                if chunk_mask is not None
+                else [
+                    np.ascontiguousarray(dim_selection, dtype=np.int64)
+                    for dim_selection in chunk_selection
+                ]
+            )
+            chunk_info_with_indices.append(
+                WithSubset(
+                    chunk_info,
+                    chunk_subset=[],
+                    subset=out_selection_as_slices,
+                    shape=shape,
+                    chunk_mask=chunk_mask,
+                    chunk_coordinates=chunk_coordinates,
+                )
+            )
+            continue
         chunk_selection_as_slices = selector_tuple_to_slice_selection(chunk_selection)
-        shape_chunk_selection_slices = get_shape_for_selector(
-            tuple(chunk_selection_as_slices),
-            chunk_spec.shape,
-            pad=True,
-            drop_axes=drop_axes,
+        shape_chunk_selection_slices = get_shape_for_orthogonal_selection(
+            chunk_selection_as_slices, chunk_spec.shape
         )
         shape_chunk_selection = get_shape_for_selector(
             chunk_selection, chunk_spec.shape, pad=True, drop_axes=drop_axes
@@ -191,6 +294,9 @@ def make_chunk_info_for_rust_with_indices(
                 chunk_subset=chunk_selection_as_slices,
                 subset=out_selection_as_slices,
                 shape=shape,
+                output_axes=make_output_axes(
+                    chunk_selection, drop_axes, len(chunk_spec.shape)
+                ),
             )
         )
     return chunk_info_with_indices
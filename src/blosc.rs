@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use pyo3::{exceptions::PyValueError, PyErr, PyResult};
+use serde_json::Value;
+use zarrs::{
+    array::{
+        codec::{
+            BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes, RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+use crate::utils::map_codecs_metadata;
+
+/// The name of a `blosc` codec with a fixed number of internal threads, which is only used within the pipeline.
+const BLOSC_NTHREADS: &str = "zarrs_python.blosc";
+
+/// Overrides of the configuration of the `blosc` codecs of a pipeline.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BloscOptions {
+    nthreads: Option<usize>,
+    blocksize: Option<usize>,
+    shuffle: Option<String>,
+}
+
+impl BloscOptions {
+    pub(crate) fn new(
+        nthreads: Option<usize>,
+        blocksize: Option<usize>,
+        shuffle: Option<String>,
+    ) -> PyResult<Self> {
+        if nthreads == Some(0) {
+            return Err(PyErr::new::<PyValueError, _>(
+                "blosc_nthreads must be at least 1",
+            ));
+        }
+        if let Some(shuffle) = &shuffle {
+            if !matches!(shuffle.as_str(), "noshuffle" | "shuffle" | "bitshuffle") {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "blosc_shuffle must be noshuffle, shuffle, or bitshuffle, not {shuffle}"
+                )));
+            }
+        }
+        Ok(Self {
+            nthreads,
+            blocksize,
+            shuffle,
+        })
+    }
+
+    /// Apply the overrides to the `blosc` codecs of codec metadata, including the inner and index codecs of `sharding_indexed` codecs.
+    pub(crate) fn apply(&self, codecs: Vec<MetadataV3>) -> PyResult<Vec<MetadataV3>> {
+        if self.nthreads.is_none() && self.blocksize.is_none() && self.shuffle.is_none() {
+            return Ok(codecs);
+        }
+        map_codecs_metadata(codecs, |codec| {
+            if codec.get("name").and_then(Value::as_str) != Some("blosc") {
+                return;
+            }
+            let Some(configuration) = codec
+                .get_mut("configuration")
+                .and_then(Value::as_object_mut)
+            else {
+                return;
+            };
+            // The block size and shuffle mode are stored in the header of each chunk, so they only change how chunks are encoded
+            if let Some(blocksize) = self.blocksize {
+                configuration.insert("blocksize".to_string(), blocksize.into());
+            }
+            if let Some(shuffle) = &self.shuffle {
+                configuration.insert("shuffle".to_string(), shuffle.as_str().into());
+            }
+            if let Some(nthreads) = self.nthreads {
+                configuration.insert("nthreads".to_string(), nthreads.into());
+                codec.insert("name".to_string(), BLOSC_NTHREADS.into());
+            }
+        })
+    }
+}
+
+fn is_name_blosc_nthreads(name: &str) -> bool {
+    name == BLOSC_NTHREADS
+}
+
+fn create_codec_blosc_nthreads(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let mut configuration = metadata.configuration().cloned().unwrap_or_default();
+    let nthreads = configuration
+        .remove("nthreads")
+        .as_ref()
+        .and_then(Value::as_u64)
+        .and_then(|nthreads| usize::try_from(nthreads).ok())
+        .ok_or_else(|| {
+            PluginCreateError::Other("blosc nthreads must be a positive integer".to_string())
+        })?;
+    let Codec::BytesToBytes(codec) =
+        Codec::from_metadata(&MetadataV3::new_with_configuration("blosc", configuration))?
+    else {
+        return Err(PluginCreateError::Other(
+            "blosc is not a bytes to bytes codec".to_string(),
+        ));
+    };
+    Ok(Codec::BytesToBytes(Arc::new(BloscNthreadsCodec {
+        codec,
+        nthreads,
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new(BLOSC_NTHREADS, is_name_blosc_nthreads, create_codec_blosc_nthreads)
+}
+
+/// A `blosc` codec which encodes and decodes with a fixed number of internal threads.
+///
+/// The `blosc` codec of `zarrs` otherwise uses as many threads as the concurrent target of the codec options.
+#[derive(Debug)]
+struct BloscNthreadsCodec {
+    codec: Arc<dyn BytesToBytesCodecTraits>,
+    nthreads: usize,
+}
+
+impl BloscNthreadsCodec {
+    fn options(&self, options: &CodecOptions) -> CodecOptions {
+        options
+            .into_builder()
+            .concurrent_target(self.nthreads)
+            .build()
+    }
+}
+
+impl CodecTraits for BloscNthreadsCodec {
+    fn create_metadata_opt(&self, options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        self.codec.create_metadata_opt(options)
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        self.codec.partial_decoder_should_cache_input()
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        self.codec.partial_decoder_decodes_all()
+    }
+}
+
+impl BytesToBytesCodecTraits for BloscNthreadsCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(self.nthreads))
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        self.codec.encoded_representation(decoded_representation)
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.codec.encode(decoded_value, &self.options(options))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.codec.decode(
+            encoded_value,
+            decoded_representation,
+            &self.options(options),
+        )
+    }
+}
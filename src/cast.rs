@@ -0,0 +1,185 @@
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    PyErr, PyResult,
+};
+use zarrs::array::DataType;
+
+/// An element of a boolean, integer, or floating point data type, widened without loss.
+#[derive(Clone, Copy)]
+enum Element {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+macro_rules! read_elements {
+    ($bytes:expr, $type:ty, $variant:ident, $widened:ty) => {
+        $bytes
+            .chunks_exact(std::mem::size_of::<$type>())
+            .map(|element| {
+                Element::$variant(
+                    <$type>::from_ne_bytes(element.try_into().expect("chunks_exact")) as $widened,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+}
+
+macro_rules! write_elements {
+    ($elements:expr, $type:ty) => {
+        $elements
+            .iter()
+            .flat_map(|element| {
+                match *element {
+                    Element::Int(value) => value as $type,
+                    Element::UInt(value) => value as $type,
+                    Element::Float(value) => value as $type,
+                }
+                .to_ne_bytes()
+            })
+            .collect::<Vec<u8>>()
+    };
+}
+
+fn unsupported_cast_err(from: &DataType, to: &DataType) -> PyErr {
+    PyErr::new::<PyTypeError, _>(format!(
+        "cannot cast elements of data type {from} to {to}, only boolean, integer, float32, and float64 data types are supported"
+    ))
+}
+
+impl Element {
+    #[allow(clippy::cast_precision_loss)]
+    fn to_f64(self) -> f64 {
+        match self {
+            Element::Int(value) => value as f64,
+            Element::UInt(value) => value as f64,
+            Element::Float(value) => value,
+        }
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        clippy::cast_lossless,
+        clippy::unnecessary_cast
+    )]
+    fn read(bytes: &[u8], data_type: &DataType) -> Option<Vec<Self>> {
+        Some(match data_type {
+            DataType::Bool => read_elements!(bytes, u8, UInt, u64),
+            DataType::Int8 => read_elements!(bytes, i8, Int, i64),
+            DataType::Int16 => read_elements!(bytes, i16, Int, i64),
+            DataType::Int32 => read_elements!(bytes, i32, Int, i64),
+            DataType::Int64 => read_elements!(bytes, i64, Int, i64),
+            DataType::UInt8 => read_elements!(bytes, u8, UInt, u64),
+            DataType::UInt16 => read_elements!(bytes, u16, UInt, u64),
+            DataType::UInt32 => read_elements!(bytes, u32, UInt, u64),
+            DataType::UInt64 => read_elements!(bytes, u64, UInt, u64),
+            DataType::Float32 => read_elements!(bytes, f32, Float, f64),
+            DataType::Float64 => read_elements!(bytes, f64, Float, f64),
+            _ => return None,
+        })
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        clippy::cast_lossless,
+        clippy::unnecessary_cast
+    )]
+    fn write(elements: &[Self], data_type: &DataType) -> Option<Vec<u8>> {
+        Some(match data_type {
+            DataType::Bool => elements
+                .iter()
+                .map(|element| {
+                    u8::from(match *element {
+                        Element::Int(value) => value != 0,
+                        Element::UInt(value) => value != 0,
+                        Element::Float(value) => value != 0.0,
+                    })
+                })
+                .collect(),
+            DataType::Int8 => write_elements!(elements, i8),
+            DataType::Int16 => write_elements!(elements, i16),
+            DataType::Int32 => write_elements!(elements, i32),
+            DataType::Int64 => write_elements!(elements, i64),
+            DataType::UInt8 => write_elements!(elements, u8),
+            DataType::UInt16 => write_elements!(elements, u16),
+            DataType::UInt32 => write_elements!(elements, u32),
+            DataType::UInt64 => write_elements!(elements, u64),
+            DataType::Float32 => write_elements!(elements, f32),
+            DataType::Float64 => write_elements!(elements, f64),
+            _ => return None,
+        })
+    }
+}
+
+/// A linear transform of packed elements to their values, `value = packed * scale + offset` (e.g. the `scale_factor` and `add_offset` of NetCDF packed data).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScaleOffset {
+    scale: f64,
+    offset: f64,
+}
+
+impl ScaleOffset {
+    pub(crate) fn new((scale, offset): (f64, f64)) -> PyResult<Self> {
+        if scale == 0.0 || !scale.is_finite() || !offset.is_finite() {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "scale {scale} must be finite and nonzero, and offset {offset} must be finite"
+            )));
+        }
+        Ok(Self { scale, offset })
+    }
+}
+
+fn convert(
+    bytes: &[u8],
+    from: &DataType,
+    to: &DataType,
+    f: impl Fn(Element) -> Element,
+) -> PyResult<Vec<u8>> {
+    let elements = Element::read(bytes, from)
+        .ok_or_else(|| unsupported_cast_err(from, to))?
+        .into_iter()
+        .map(f)
+        .collect::<Vec<_>>();
+    Element::write(&elements, to).ok_or_else(|| unsupported_cast_err(from, to))
+}
+
+/// Cast decoded elements of data type `from` to data type `to`, with the semantics of `as` casts in Rust.
+///
+/// Integers wrap and floats saturate when they are out of the range of an integer data type (NaN is zero), and nonzero elements are `true` when cast to `bool`.
+pub(crate) fn cast(bytes: &[u8], from: &DataType, to: &DataType) -> PyResult<Vec<u8>> {
+    convert(bytes, from, to, |element| element)
+}
+
+/// Unpack decoded elements of data type `from` to their values with `scale_offset`, cast to data type `to` as in [`cast`].
+pub(crate) fn unpack(
+    bytes: &[u8],
+    from: &DataType,
+    to: &DataType,
+    scale_offset: ScaleOffset,
+) -> PyResult<Vec<u8>> {
+    convert(bytes, from, to, |element| {
+        Element::Float(element.to_f64() * scale_offset.scale + scale_offset.offset)
+    })
+}
+
+/// Pack values of data type `from` to elements of data type `to` with `scale_offset`, the inverse of [`unpack`].
+///
+/// Packed elements are rounded to the nearest integer if `to` is not a floating point data type.
+pub(crate) fn pack(
+    bytes: &[u8],
+    from: &DataType,
+    to: &DataType,
+    scale_offset: ScaleOffset,
+) -> PyResult<Vec<u8>> {
+    let round = !matches!(to, DataType::Float32 | DataType::Float64);
+    convert(bytes, from, to, |element| {
+        let packed = (element.to_f64() - scale_offset.offset) / scale_offset.scale;
+        Element::Float(if round { packed.round() } else { packed })
+    })
+}
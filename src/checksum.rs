@@ -0,0 +1,116 @@
+use std::{borrow::Cow, sync::Arc};
+
+use pyo3::PyResult;
+use serde_json::Value;
+use zarrs::{
+    array::{
+        codec::{
+            BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes, RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+use crate::utils::map_codecs_metadata;
+
+/// The name of a `crc32c` codec which writes zero checksums, which is only used within the pipeline.
+const CRC32C_UNCHECKED: &str = "zarrs_python.crc32c_unchecked";
+
+/// The size of a `crc32c` checksum.
+const CRC32C_SIZE: usize = 4;
+
+/// Replace the `crc32c` codecs of codec metadata (including those of shards and their index) with codecs which write zero checksums rather than computing them.
+pub(crate) fn skip_write_checksums(codecs: Vec<MetadataV3>) -> PyResult<Vec<MetadataV3>> {
+    map_codecs_metadata(codecs, |codec| {
+        if codec.get("name").and_then(Value::as_str) == Some("crc32c") {
+            codec.insert("name".to_string(), CRC32C_UNCHECKED.into());
+        }
+    })
+}
+
+fn is_name_crc32c_unchecked(name: &str) -> bool {
+    name == CRC32C_UNCHECKED
+}
+
+fn create_codec_crc32c_unchecked(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let crc32c = match metadata.configuration() {
+        Some(configuration) => MetadataV3::new_with_configuration("crc32c", configuration.clone()),
+        None => MetadataV3::new("crc32c"),
+    };
+    let Codec::BytesToBytes(codec) = Codec::from_metadata(&crc32c)? else {
+        return Err(PluginCreateError::Other(
+            "crc32c is not a bytes to bytes codec".to_string(),
+        ));
+    };
+    Ok(Codec::BytesToBytes(Arc::new(Crc32cUncheckedCodec {
+        codec,
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new(CRC32C_UNCHECKED, is_name_crc32c_unchecked, create_codec_crc32c_unchecked)
+}
+
+/// A `crc32c` codec which appends a zero checksum when encoding, and decodes like the `crc32c` codec.
+///
+/// Chunks encoded with it only decode with checksum validation disabled.
+#[derive(Debug)]
+struct Crc32cUncheckedCodec {
+    codec: Arc<dyn BytesToBytesCodecTraits>,
+}
+
+impl CodecTraits for Crc32cUncheckedCodec {
+    fn create_metadata_opt(&self, options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        self.codec.create_metadata_opt(options)
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        self.codec.partial_decoder_should_cache_input()
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        self.codec.partial_decoder_decodes_all()
+    }
+}
+
+impl BytesToBytesCodecTraits for Crc32cUncheckedCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        self.codec.recommended_concurrency(decoded_representation)
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        self.codec.encoded_representation(decoded_representation)
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let mut encoded_value = decoded_value.into_owned();
+        encoded_value.extend_from_slice(&[0; CRC32C_SIZE]);
+        Ok(Cow::Owned(encoded_value))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.codec
+            .decode(encoded_value, decoded_representation, options)
+    }
+}
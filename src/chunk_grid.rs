@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use pyo3::{exceptions::PyValueError, Bound, PyAny, PyErr, PyResult};
+use zarrs::{array::ChunkRepresentation, array_subset::ArraySubset, storage::StoreKey};
+
+use crate::{
+    chunk_item::{reject_newaxis, selection_to_array_subset, Basic, WithSubset},
+    indexing::cross_product,
+    store::StoreConfig,
+    utils::PyErrExt,
+};
+
+/// The keys of the chunks of an array, e.g. `array/c/0/1` with the prefix `array/c/` and the separator `/`.
+pub(crate) struct ChunkKeyEncoding<'a> {
+    pub prefix: &'a str,
+    pub separator: &'a str,
+}
+
+impl ChunkKeyEncoding<'_> {
+    fn key(&self, chunk_indices: &[u64]) -> PyResult<StoreKey> {
+        let chunk_indices: Vec<String> = chunk_indices.iter().map(u64::to_string).collect();
+        StoreKey::new(format!(
+            "{}{}",
+            self.prefix,
+            chunk_indices.join(self.separator)
+        ))
+        .map_py_err::<PyValueError>()
+    }
+}
+
+/// The elements selected along a dimension, either a range or indices in selection order.
+enum DimSelection {
+    Range(Range<u64>),
+    Indices(Vec<u64>),
+}
+
+impl DimSelection {
+    fn from_indices(indices: Vec<u64>) -> Self {
+        if indices.windows(2).all(|pair| pair[0] + 1 == pair[1]) {
+            let start = indices.first().copied().unwrap_or_default();
+            Self::Range(start..start + indices.len() as u64)
+        } else {
+            Self::Indices(indices)
+        }
+    }
+
+    /// The range that bounds the selected elements.
+    fn bounds(&self) -> Range<u64> {
+        match self {
+            Self::Range(range) => range.clone(),
+            Self::Indices(indices) => {
+                let min = indices.iter().min().copied().unwrap_or_default();
+                let max = indices.iter().max().copied().unwrap_or_default();
+                min..max + 1
+            }
+        }
+    }
+
+    fn indices(&self) -> Vec<u64> {
+        match self {
+            Self::Range(range) => range.clone().collect(),
+            Self::Indices(indices) => indices.clone(),
+        }
+    }
+}
+
+/// The elements selected from a chunk along a dimension, and their positions along the dimension of the output.
+struct DimChunkSelection {
+    chunk: u64,
+    chunk_selection: DimSelection,
+    output_selection: DimSelection,
+}
+
+/// Decompose the selection of a dimension into the selections of the chunks it intersects.
+///
+/// `indices` are the selected indices if they are not contiguous, otherwise every index of `range` is selected.
+fn decompose_dim(
+    range: Range<u64>,
+    indices: Option<&[u64]>,
+    chunk_size: u64,
+) -> Vec<DimChunkSelection> {
+    if let Some(indices) = indices {
+        // Elements are grouped by chunk, keeping their order within each chunk
+        let mut chunks: BTreeMap<u64, (Vec<u64>, Vec<u64>)> = BTreeMap::new();
+        for (position, &index) in (0..).zip(indices) {
+            let (chunk_indices, output_indices) = chunks.entry(index / chunk_size).or_default();
+            chunk_indices.push(index % chunk_size);
+            output_indices.push(position);
+        }
+        chunks
+            .into_iter()
+            .map(
+                |(chunk, (chunk_indices, output_indices))| DimChunkSelection {
+                    chunk,
+                    chunk_selection: DimSelection::from_indices(chunk_indices),
+                    output_selection: DimSelection::from_indices(output_indices),
+                },
+            )
+            .collect()
+    } else if range.is_empty() {
+        vec![]
+    } else {
+        (range.start / chunk_size..=(range.end - 1) / chunk_size)
+            .map(|chunk| {
+                let chunk_start = chunk * chunk_size;
+                let start = range.start.max(chunk_start);
+                let end = range.end.min(chunk_start + chunk_size);
+                DimChunkSelection {
+                    chunk,
+                    chunk_selection: DimSelection::Range(start - chunk_start..end - chunk_start),
+                    output_selection: DimSelection::Range(start - range.start..end - range.start),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Convert the selections of each dimension to a subset that bounds them, and the selected indices if any are not contiguous.
+fn dim_selections_to_array_subset(
+    selections: &[&DimSelection],
+) -> (ArraySubset, Option<Vec<Vec<u64>>>) {
+    let ranges: Vec<Range<u64>> = selections
+        .iter()
+        .map(|selection| selection.bounds())
+        .collect();
+    let indices = selections
+        .iter()
+        .any(|selection| matches!(selection, DimSelection::Indices(_)))
+        .then(|| {
+            selections
+                .iter()
+                .map(|selection| selection.indices())
+                .collect()
+        });
+    (ArraySubset::new_with_ranges(&ranges), indices)
+}
+
+/// Decompose an orthogonal selection of an array with a regular chunk grid into the selections of the chunks it intersects.
+///
+/// Returns the chunk descriptions and the shape of the output, which keeps a dimension for each dimension of the array.
+pub(crate) fn chunk_selections(
+    store: &StoreConfig,
+    chunk_key_encoding: &ChunkKeyEncoding,
+    representation: &ChunkRepresentation,
+    array_shape: &[u64],
+    selection: &[Bound<'_, PyAny>],
+) -> PyResult<(Vec<WithSubset>, Vec<u64>)> {
+    let chunk_shape = representation.shape_u64();
+    if chunk_shape.len() != array_shape.len() {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "chunk shape {chunk_shape:?} does not match the dimensionality of the array shape {array_shape:?}"
+        )));
+    }
+    reject_newaxis(selection)?;
+    let (subset, indices) = selection_to_array_subset(selection, array_shape)?;
+    let output_shape: Vec<u64> = match &indices {
+        Some(indices) => indices.iter().map(|indices| indices.len() as u64).collect(),
+        None => subset.shape().to_vec(),
+    };
+
+    let dim_chunks: Vec<Vec<DimChunkSelection>> = subset
+        .to_ranges()
+        .into_iter()
+        .zip(&chunk_shape)
+        .enumerate()
+        .map(|(axis, (range, &chunk_size))| {
+            decompose_dim(
+                range,
+                indices.as_ref().map(|indices| indices[axis].as_slice()),
+                chunk_size,
+            )
+        })
+        .collect();
+    let positions: Vec<Vec<u64>> = dim_chunks
+        .iter()
+        .map(|dim_chunks| (0..dim_chunks.len() as u64).collect())
+        .collect();
+
+    let chunk_descriptions = cross_product(&positions)
+        .into_iter()
+        .map(|positions| {
+            let dim_chunks: Vec<&DimChunkSelection> = positions
+                .iter()
+                .zip(&dim_chunks)
+                .map(|(&position, dim_chunks)| -> PyResult<_> {
+                    Ok(&dim_chunks[usize::try_from(position)?])
+                })
+                .collect::<PyResult<_>>()?;
+            let chunk_indices: Vec<u64> = dim_chunks.iter().map(|dim| dim.chunk).collect();
+            let (chunk_subset, chunk_indices_selected) = dim_selections_to_array_subset(
+                &dim_chunks
+                    .iter()
+                    .map(|dim| &dim.chunk_selection)
+                    .collect::<Vec<_>>(),
+            );
+            let (output_subset, output_indices_selected) = dim_selections_to_array_subset(
+                &dim_chunks
+                    .iter()
+                    .map(|dim| &dim.output_selection)
+                    .collect::<Vec<_>>(),
+            );
+            Ok(WithSubset {
+                item: Basic::from_parts(
+                    store.clone(),
+                    chunk_key_encoding.key(&chunk_indices)?,
+                    representation.clone(),
+                ),
+                chunk_subset,
+                chunk_indices: chunk_indices_selected,
+                chunk_points: None,
+                subset: output_subset,
+                subset_indices: output_indices_selected,
+                output_axes: Some((0..array_shape.len()).map(Some).collect()),
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok((chunk_descriptions, output_shape))
+}
@@ -1,10 +1,17 @@
-use std::num::NonZeroU64;
+use std::{collections::BTreeSet, num::NonZeroU64, ops::Range};
 
+use numpy::{
+    ndarray::Dimension as _, PyArrayDescrMethods, PyReadonlyArray1, PyReadonlyArrayDyn,
+    PyUntypedArray, PyUntypedArrayMethods,
+};
 use pyo3::{
-    exceptions::{PyRuntimeError, PyValueError},
+    exceptions::{PyIndexError, PyRuntimeError, PyTypeError, PyValueError},
     pyclass, pymethods,
-    types::{PyAnyMethods, PyBytes, PyBytesMethods, PyInt, PySlice, PySliceMethods as _},
-    Bound, PyAny, PyErr, PyResult,
+    types::{
+        PyAnyMethods, PyBytes, PyBytesMethods, PyEllipsis, PyInt, PySlice, PyString,
+        PyStringMethods, PyTuple,
+    },
+    Bound, PyAny, PyErr, PyResult, Python,
 };
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 use zarrs::{
@@ -14,7 +21,13 @@ use zarrs::{
     storage::StoreKey,
 };
 
-use crate::{store::StoreConfig, utils::PyErrExt};
+use crate::{
+    data_type::extension_data_type,
+    fill_value::fill_value_from_metadata,
+    metadata_v2::{data_type_v2_to_v3, is_data_type_v2},
+    store::StoreConfig,
+    utils::PyErrExt,
+};
 
 pub(crate) trait ChunksItem {
     fn store_config(&self) -> StoreConfig;
@@ -32,7 +45,7 @@ pub(crate) struct Basic {
 }
 
 fn fill_value_to_bytes(dtype: &str, fill_value: &Bound<'_, PyAny>) -> PyResult<Vec<u8>> {
-    if dtype == "string" {
+    if dtype == "string" || dtype == "bytes" {
         // Match zarr-python 2.x.x string fill value behaviour with a 0 fill value
         // See https://github.com/zarr-developers/zarr-python/issues/2792#issuecomment-2644362122
         if let Ok(fill_value_downcast) = fill_value.downcast::<PyInt>() {
@@ -48,6 +61,8 @@ fn fill_value_to_bytes(dtype: &str, fill_value: &Bound<'_, PyAny>) -> PyResult<V
 
     if let Ok(fill_value_downcast) = fill_value.downcast::<PyBytes>() {
         Ok(fill_value_downcast.as_bytes().to_vec())
+    } else if let Ok(fill_value_downcast) = fill_value.downcast::<PyString>() {
+        Ok(fill_value_downcast.to_str()?.as_bytes().to_vec())
     } else if fill_value.hasattr("tobytes")? {
         Ok(fill_value.call_method0("tobytes")?.extract()?)
     } else {
@@ -60,60 +75,236 @@ fn fill_value_to_bytes(dtype: &str, fill_value: &Bound<'_, PyAny>) -> PyResult<V
 #[gen_stub_pymethods]
 #[pymethods]
 impl Basic {
+    /// `data_type` is the zarr data type of the chunk if it is not implied by the numpy data type of `chunk_spec`, e.g. `bytes` for an object array.
     #[new]
-    fn new(byte_interface: &Bound<'_, PyAny>, chunk_spec: &Bound<'_, PyAny>) -> PyResult<Self> {
+    #[pyo3(signature = (byte_interface, chunk_spec, *, data_type=None))]
+    fn new(
+        byte_interface: &Bound<'_, PyAny>,
+        chunk_spec: &Bound<'_, PyAny>,
+        data_type: Option<String>,
+    ) -> PyResult<Self> {
         let store: StoreConfig = byte_interface.getattr("store")?.extract()?;
         let path: String = byte_interface.getattr("path")?.extract()?;
-
-        let chunk_shape = chunk_spec.getattr("shape")?.extract()?;
-        let mut dtype: String = chunk_spec
-            .getattr("dtype")?
-            .call_method0("__str__")?
-            .extract()?;
-        if dtype == "object" {
-            // zarrs doesn't understand `object` which is the output of `np.dtype("|O").__str__()`
-            // but maps it to "string" internally https://github.com/LDeakin/zarrs/blob/0532fe983b7b42b59dbf84e50a2fe5e6f7bad4ce/zarrs_metadata/src/v2_to_v3.rs#L288
-            dtype = String::from("string");
-        }
-        let fill_value: Bound<'_, PyAny> = chunk_spec.getattr("fill_value")?;
-        let fill_value_bytes = fill_value_to_bytes(&dtype, &fill_value)?;
         Ok(Self {
             store,
             key: StoreKey::new(path).map_py_err::<PyValueError>()?,
-            representation: get_chunk_representation(chunk_shape, &dtype, fill_value_bytes)?,
+            representation: chunk_spec_to_representation(chunk_spec, data_type)?,
         })
     }
 }
 
+impl Basic {
+    pub(crate) fn from_parts(
+        store: StoreConfig,
+        key: StoreKey,
+        representation: ChunkRepresentation,
+    ) -> Self {
+        Self {
+            store,
+            key,
+            representation,
+        }
+    }
+}
+
 #[derive(Clone)]
 #[gen_stub_pyclass]
 #[pyclass]
 pub(crate) struct WithSubset {
     pub item: Basic,
+    /// The subset of the chunk, which bounds the selected elements if they are not contiguous.
     pub chunk_subset: ArraySubset,
+    /// The indices of the elements selected from the chunk along each dimension, if they are not contiguous.
+    pub chunk_indices: Option<Vec<Vec<u64>>>,
+    /// The coordinates of each element selected from the chunk, in order, if the selection is pointwise (e.g. a mask).
+    pub chunk_points: Option<Vec<Vec<u64>>>,
+    /// The subset of the output, which bounds the selected elements if they are not contiguous.
     pub subset: ArraySubset,
+    /// The indices of the elements selected from the output along each dimension, if they are not contiguous.
+    pub subset_indices: Option<Vec<Vec<u64>>>,
+    /// The axis of the output selection of each axis of the chunk selection, or `None` if the axis is dropped (e.g. selected with an integer).
+    pub output_axes: Option<Vec<Option<usize>>>,
 }
 
 #[gen_stub_pymethods]
 #[pymethods]
 impl WithSubset {
     #[new]
+    #[pyo3(signature = (item, chunk_subset, subset, shape, *, chunk_mask=None, chunk_coordinates=None, output_axes=None))]
     #[allow(clippy::needless_pass_by_value)]
     fn new(
         item: Basic,
-        chunk_subset: Vec<Bound<'_, PySlice>>,
-        subset: Vec<Bound<'_, PySlice>>,
+        chunk_subset: Vec<Bound<'_, PyAny>>,
+        subset: Vec<Bound<'_, PyAny>>,
         shape: Vec<u64>,
+        chunk_mask: Option<PyReadonlyArrayDyn<'_, bool>>,
+        chunk_coordinates: Option<Vec<Bound<'_, PyAny>>>,
+        output_axes: Option<Vec<Option<usize>>>,
     ) -> PyResult<Self> {
-        let chunk_subset =
-            selection_to_array_subset(&chunk_subset, &item.representation.shape_u64())?;
-        let subset = selection_to_array_subset(&subset, &shape)?;
-        Ok(Self {
+        let key = item.key.clone();
+        Self::from_selections(
+            item,
+            &chunk_subset,
+            &subset,
+            &shape,
+            chunk_mask,
+            chunk_coordinates,
+            output_axes,
+        )
+        .map_err(|err| chunk_err(err, &key))
+    }
+}
+
+impl WithSubset {
+    #[allow(clippy::needless_pass_by_value)]
+    fn from_selections(
+        item: Basic,
+        chunk_subset: &[Bound<'_, PyAny>],
+        subset: &[Bound<'_, PyAny>],
+        shape: &[u64],
+        chunk_mask: Option<PyReadonlyArrayDyn<'_, bool>>,
+        chunk_coordinates: Option<Vec<Bound<'_, PyAny>>>,
+        output_axes: Option<Vec<Option<usize>>>,
+    ) -> PyResult<Self> {
+        let chunk_shape = item.representation.shape_u64();
+        let (chunk_subset, chunk_indices, chunk_points) = match (chunk_mask, chunk_coordinates) {
+            (None, None) => {
+                reject_newaxis(chunk_subset)?;
+                let (chunk_subset, chunk_indices) =
+                    selection_to_array_subset(chunk_subset, &chunk_shape)?;
+                (chunk_subset, chunk_indices, None)
+            }
+            (Some(chunk_mask), None) if chunk_subset.is_empty() => {
+                let chunk_points = mask_to_points(&chunk_mask, &chunk_shape)?;
+                (
+                    points_to_array_subset(&chunk_points, &chunk_shape),
+                    None,
+                    Some(chunk_points),
+                )
+            }
+            (None, Some(chunk_coordinates)) if chunk_subset.is_empty() => {
+                let chunk_points = coordinates_to_points(&chunk_coordinates, &chunk_shape)?;
+                (
+                    points_to_array_subset(&chunk_points, &chunk_shape),
+                    None,
+                    Some(chunk_points),
+                )
+            }
+            _ => {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "chunk_subset, chunk_mask, and chunk_coordinates are mutually exclusive"
+                        .to_string(),
+                ));
+            }
+        };
+        let (subset, subset_indices) = selection_to_array_subset(subset, shape)?;
+        let with_subset = Self {
             item,
             chunk_subset,
+            chunk_indices,
+            chunk_points,
             subset,
+            subset_indices,
+            output_axes,
+        };
+
+        // A single selected element of the output is broadcast to every selected element of the chunk
+        let chunk_selection_shape = with_subset.chunk_selection_shape();
+        let selection_shape = with_subset.selection_shape();
+        let num_selected = selection_shape.iter().product::<u64>();
+        if num_selected == 1 {
+            return Ok(with_subset);
+        }
+        if chunk_selection_shape.iter().product::<u64>() != num_selected {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "chunk selection {:?} of shape {chunk_selection_shape:?} does not match selection {:?} of shape {selection_shape:?} of an array with shape {shape:?}",
+                with_subset.chunk_subset.to_ranges(),
+                with_subset.subset.to_ranges(),
+            )));
+        }
+        if let Some(output_axes) = &with_subset.output_axes {
+            if with_subset.chunk_points.is_some() {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "output_axes are not supported with chunk_mask or chunk_coordinates"
+                        .to_string(),
+                ));
+            }
+            validate_output_axes(output_axes, &chunk_selection_shape, &selection_shape)
+                .map_err(|err| {
+                    PyErr::new::<PyValueError, _>(format!(
+                        "{err} (output axes {output_axes:?} of a chunk selection of shape {chunk_selection_shape:?} and a selection of shape {selection_shape:?})"
+                    ))
+                })?;
+        }
+        Ok(with_subset)
+    }
+
+    /// The pairs of the axes of the chunk selection and the output selection that correspond, if they are known.
+    pub(crate) fn axis_pairs(&self) -> Option<Vec<(usize, usize)>> {
+        self.output_axes.as_ref().map(|output_axes| {
+            output_axes
+                .iter()
+                .enumerate()
+                .filter_map(|(chunk_axis, output_axis)| {
+                    output_axis.map(|output_axis| (chunk_axis, output_axis))
+                })
+                .collect()
         })
     }
+
+    /// Returns true if the selected elements of the chunk or the output are not contiguous.
+    pub(crate) fn is_indexed(&self) -> bool {
+        self.chunk_indices.is_some() || self.chunk_points.is_some() || self.subset_indices.is_some()
+    }
+
+    /// Returns true if no elements of the chunk are selected.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.chunk_selection_shape().contains(&0)
+    }
+
+    /// Returns true if every element of the chunk is selected in C order.
+    pub(crate) fn is_whole_chunk(&self) -> bool {
+        self.chunk_indices.is_none()
+            && self.chunk_points.is_none()
+            && self.chunk_subset.start().iter().all(|&o| o == 0)
+            && self.chunk_subset.shape() == self.representation().shape_u64()
+    }
+
+    /// Returns true if every element of the chunk is selected in any order, so the chunk is entirely overwritten by a write.
+    pub(crate) fn covers_chunk(&self) -> bool {
+        match (&self.chunk_indices, &self.chunk_points) {
+            (_, Some(points)) => {
+                points.iter().collect::<BTreeSet<_>>().len() as u64
+                    == self.representation().num_elements()
+            }
+            (Some(indices), None) => {
+                indices
+                    .iter()
+                    .zip(self.representation().shape_u64())
+                    .all(|(indices, length)| {
+                        indices.iter().collect::<BTreeSet<_>>().len() as u64 == length
+                    })
+            }
+            (None, None) => self.is_whole_chunk(),
+        }
+    }
+
+    /// The number of elements selected from the chunk along each dimension, or the number of points of a pointwise selection.
+    pub(crate) fn chunk_selection_shape(&self) -> Vec<u64> {
+        match (&self.chunk_indices, &self.chunk_points) {
+            (_, Some(points)) => vec![points.len() as u64],
+            (Some(indices), None) => indices.iter().map(|indices| indices.len() as u64).collect(),
+            (None, None) => self.chunk_subset.shape().to_vec(),
+        }
+    }
+
+    /// The number of elements selected from the output along each dimension.
+    pub(crate) fn selection_shape(&self) -> Vec<u64> {
+        match &self.subset_indices {
+            Some(indices) => indices.iter().map(|indices| indices.len() as u64).collect(),
+            None => self.subset.shape().to_vec(),
+        }
+    }
 }
 
 impl ChunksItem for Basic {
@@ -140,56 +331,505 @@ impl ChunksItem for WithSubset {
     }
 }
 
-fn get_chunk_representation(
+/// Get the chunk representation from a zarr-python `ArraySpec` (or any object with its `shape`, `dtype`, and `fill_value`).
+pub(crate) fn chunk_spec_to_representation(
+    chunk_spec: &Bound<'_, PyAny>,
+    data_type: Option<String>,
+) -> PyResult<ChunkRepresentation> {
+    let chunk_shape = chunk_spec.getattr("shape")?.extract()?;
+    let mut dtype_py = chunk_spec.getattr("dtype")?;
+    if !dtype_py.getattr("isnative")?.extract::<bool>()? {
+        // The chunk is decoded to elements with native byte order, whatever the byte order of the array data type
+        dtype_py = dtype_py.call_method1("newbyteorder", ("=",))?;
+    }
+    let mut dtype: String = dtype_py.call_method0("__str__")?.extract()?;
+    let kind: String = dtype_py.getattr("kind")?.extract()?;
+    let fill_value: Bound<'_, PyAny> = chunk_spec.getattr("fill_value")?;
+    if data_type.is_none() {
+        if let Some((data_type, fill_value_bytes)) = extension_data_type(&dtype, &fill_value)? {
+            let fill_value_bytes = match fill_value_bytes {
+                Some(fill_value_bytes) => fill_value_bytes,
+                None => fill_value_to_bytes(&dtype, &fill_value)?,
+            };
+            return get_chunk_representation(chunk_shape, data_type, fill_value_bytes);
+        }
+    }
+    if let Some(data_type) = data_type {
+        dtype = data_type;
+    } else if dtype == "object" {
+        // zarrs doesn't understand `object` which is the output of `np.dtype("|O").__str__()`
+        // but maps it to "string" internally https://github.com/LDeakin/zarrs/blob/0532fe983b7b42b59dbf84e50a2fe5e6f7bad4ce/zarrs_metadata/src/v2_to_v3.rs#L288
+        dtype = String::from("string");
+    } else if kind == "T" {
+        // `np.dtypes.StringDType()`, the numpy data type of zarr-python `string` arrays with numpy >= 2
+        dtype = String::from("string");
+    } else if kind == "V" && dtype != "bfloat16" {
+        // Structured (compound) and void data types are raw bits, the fields of a record are copied as is
+        // `ml_dtypes.bfloat16` is also a void data type in numpy, but is the zarrs `bfloat16` data type
+        let itemsize: usize = dtype_py.getattr("itemsize")?.extract()?;
+        dtype = format!("r{}", itemsize * 8);
+    } else if kind == "U" {
+        // Fixed length unicode strings are UTF-32 code units, which are copied as is like raw bits
+        // Non-native byte order strings are not supported, since raw bits are not byte swapped
+        let itemsize: usize = dtype_py.getattr("itemsize")?.extract()?;
+        dtype = format!("r{}", itemsize * 8);
+    } else if kind == "M" || kind == "m" {
+        // `datetime64` and `timedelta64` are 64-bit integer counts of their unit, which is kept by the numpy data type
+        dtype = String::from("int64");
+    }
+    let data_type =
+        DataType::from_metadata(&data_type_metadata(&dtype)?).map_py_err::<PyRuntimeError>()?;
+    if let DataType::RawBits(size) = data_type {
+        // Elements are copied as is, so they must fill the elements of the numpy array exactly
+        let itemsize: usize = dtype_py.getattr("itemsize")?.extract()?;
+        if size != itemsize {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "raw bits data type {dtype} does not match the size of numpy data type {dtype_py} ({itemsize} bytes)"
+            )));
+        }
+    }
+    let fill_value_bytes = match fill_value_from_metadata(&data_type, &fill_value)? {
+        Some(fill_value) => fill_value.as_ne_bytes().to_vec(),
+        None => fill_value_to_bytes(&dtype, &fill_value)?,
+    };
+    get_chunk_representation(chunk_shape, data_type, fill_value_bytes)
+}
+
+/// The metadata of a zarr V3 data type name (e.g. `float64`), or of a zarr V2 data type string (e.g. `<f8`, `|u1`, or `>i4`).
+pub(crate) fn data_type_metadata(name: &str) -> PyResult<DataTypeMetadataV3> {
+    if is_data_type_v2(name) {
+        // Decoded elements have native byte order, so the endianness of a V2 data type only applies to the codecs
+        Ok(data_type_v2_to_v3(name)?.0)
+    } else {
+        Ok(DataTypeMetadataV3::from_metadata(&MetadataV3::new(name)))
+    }
+}
+
+pub(crate) fn get_chunk_representation(
     chunk_shape: Vec<u64>,
-    dtype: &str,
+    data_type: DataType,
     fill_value: Vec<u8>,
 ) -> PyResult<ChunkRepresentation> {
     // Get the chunk representation
-    let data_type =
-        DataType::from_metadata(&DataTypeMetadataV3::from_metadata(&MetadataV3::new(dtype)))
-            .map_py_err::<PyRuntimeError>()?;
     let chunk_shape = chunk_shape
-        .into_iter()
-        .map(|x| NonZeroU64::new(x).expect("chunk shapes should always be non-zero"))
-        .collect();
+        .iter()
+        .map(|&x| NonZeroU64::new(x))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            PyErr::new::<PyValueError, _>(format!(
+                "chunk shape {chunk_shape:?} must not have zero-size dimensions"
+            ))
+        })?;
     let chunk_representation =
         ChunkRepresentation::new(chunk_shape, data_type, FillValue::new(fill_value))
             .map_py_err::<PyValueError>()?;
     Ok(chunk_representation)
 }
 
-fn slice_to_range(slice: &Bound<'_, PySlice>, length: isize) -> PyResult<std::ops::Range<u64>> {
-    let indices = slice.indices(length)?;
-    if indices.start < 0 {
-        Err(PyErr::new::<PyValueError, _>(
-            "slice start must be greater than or equal to 0".to_string(),
-        ))
-    } else if indices.stop < 0 {
-        Err(PyErr::new::<PyValueError, _>(
-            "slice stop must be greater than or equal to 0".to_string(),
-        ))
-    } else if indices.step != 1 {
-        Err(PyErr::new::<PyValueError, _>(
-            "slice step must be equal to 1".to_string(),
-        ))
+/// The `(start, stop, step)` bounds of a slice.
+type SliceBounds = (Option<i128>, Option<i128>, Option<i128>);
+
+/// Get the bounds of a slice, or of a `(start, stop, step)` tuple of integers which is cheaper to build in Python.
+fn extract_slice_bounds(selection: &Bound<'_, PyAny>) -> PyResult<Option<SliceBounds>> {
+    if let Ok(slice) = selection.downcast::<PySlice>() {
+        Ok(Some((
+            slice.getattr("start")?.extract()?,
+            slice.getattr("stop")?.extract()?,
+            slice.getattr("step")?.extract()?,
+        )))
+    } else if selection.is_instance_of::<PyTuple>() {
+        Ok(Some(selection.extract()?))
     } else {
-        Ok(u64::try_from(indices.start)?..u64::try_from(indices.stop)?)
+        Ok(None)
+    }
+}
+
+/// Convert the bounds of a slice to the range that bounds its elements, and its step.
+///
+/// Negative bounds count from the end of the dimension, and bounds beyond either end are clamped, as in Python.
+/// Bounds are resolved with 128-bit integers rather than `isize` (as `slice.indices` does), so any dimension length is supported on any platform.
+fn slice_to_range((start, stop, step): SliceBounds, length: u64) -> PyResult<(Range<u64>, u64)> {
+    let step = step.unwrap_or(1);
+    if step < 1 {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "slice step must be greater than or equal to 1, not {step}"
+        )));
     }
+    // With a positive step, the bounds are clamped to 0..=length
+    let resolve = |index: Option<i128>, default: u64| -> PyResult<u64> {
+        let Some(index) = index else {
+            return Ok(default);
+        };
+        let length = i128::from(length);
+        let index = if index < 0 { index + length } else { index };
+        Ok(u64::try_from(index.clamp(0, length))?)
+    };
+    let start = resolve(start, 0)?;
+    let stop = resolve(stop, length)?;
+    // A step beyond the length of the dimension selects a single element
+    let step = u64::try_from(step).unwrap_or(u64::MAX);
+    // Stop just after the last selected element
+    let stop = if stop > start {
+        start + (stop - start - 1) / step * step + 1
+    } else {
+        start
+    };
+    Ok((start..stop, step))
+}
+
+/// Add the axis, the selection of the axis, and the shape of the array to an error converting a selection.
+fn selection_err(err: PyErr, axis: usize, selection: &Bound<'_, PyAny>, shape: &[u64]) -> PyErr {
+    let py = selection.py();
+    let selection = selection
+        .repr()
+        .map_or_else(|_| "?".to_string(), |repr| repr.to_string());
+    PyErr::from_type(
+        err.get_type(py),
+        format!(
+            "{} (axis {axis} with selection {selection} of an array with shape {shape:?})",
+            err.value(py)
+        ),
+    )
 }
 
-fn selection_to_array_subset(
-    selection: &[Bound<'_, PySlice>],
+/// Check that `output_axes` maps each axis of the chunk selection to an axis of the output selection with the same number of elements, in order.
+///
+/// Axes of the chunk selection that are dropped and axes of the output selection that are not mapped must select a single element.
+fn validate_output_axes(
+    output_axes: &[Option<usize>],
+    chunk_selection_shape: &[u64],
+    selection_shape: &[u64],
+) -> Result<(), String> {
+    if output_axes.len() != chunk_selection_shape.len() {
+        return Err(format!(
+            "expected an output axis for each of the {} axes of the chunk selection",
+            chunk_selection_shape.len()
+        ));
+    }
+    let mut mapped = vec![false; selection_shape.len()];
+    let mut previous_output_axis = None;
+    for (chunk_axis, (&output_axis, &length)) in
+        output_axes.iter().zip(chunk_selection_shape).enumerate()
+    {
+        match output_axis {
+            None if length != 1 => {
+                return Err(format!(
+                    "dropped axis {chunk_axis} of the chunk selection must select a single element, not {length}"
+                ));
+            }
+            None => {}
+            Some(output_axis) => {
+                if previous_output_axis.is_some_and(|previous| previous >= output_axis) {
+                    return Err("output axes must be increasing".to_string());
+                }
+                if selection_shape.get(output_axis) != Some(&length) {
+                    return Err(format!(
+                        "axis {chunk_axis} of the chunk selection does not match output axis {output_axis}"
+                    ));
+                }
+                mapped[output_axis] = true;
+                previous_output_axis = Some(output_axis);
+            }
+        }
+    }
+    if let Some(output_axis) = (0..selection_shape.len())
+        .find(|&output_axis| !mapped[output_axis] && selection_shape[output_axis] != 1)
+    {
+        return Err(format!(
+            "output axis {output_axis} is not mapped from an axis of the chunk selection"
+        ));
+    }
+    Ok(())
+}
+
+/// Add the key of a chunk to an error retrieving or storing the chunk.
+pub(crate) fn chunk_err(err: PyErr, key: &StoreKey) -> PyErr {
+    Python::with_gil(|py| {
+        PyErr::from_type(
+            err.get_type(py),
+            format!("{} (chunk {})", err.value(py), key.as_str()),
+        )
+    })
+}
+
+/// Convert the selection of a dimension to the range that bounds its elements, and the selected indices if they are not contiguous.
+///
+/// The selection is either a slice, a `(start, stop, step)` tuple, a 1-dimensional array of indices, or a 1-dimensional boolean mask.
+fn dim_selection_to_range(
+    selection: &Bound<'_, PyAny>,
+    length: u64,
+) -> PyResult<(Range<u64>, Option<Vec<u64>>)> {
+    if let Some(bounds) = extract_slice_bounds(selection)? {
+        let (range, step) = slice_to_range(bounds, length)?;
+        return Ok(if step > 1 {
+            let indices = range
+                .clone()
+                .step_by(usize::try_from(step).unwrap_or(usize::MAX))
+                .collect();
+            (range, Some(indices))
+        } else {
+            (range, None)
+        });
+    }
+
+    let indices: Vec<u64> = if let Ok(mask) = selection.extract::<PyReadonlyArray1<'_, bool>>() {
+        let mask = mask.as_array();
+        if mask.len() as u64 != length {
+            return Err(PyErr::new::<PyIndexError, _>(format!(
+                "boolean mask of length {} does not match a dimension of length {length}",
+                mask.len()
+            )));
+        }
+        (0..length)
+            .zip(mask)
+            .filter(|(_, &selected)| selected)
+            .map(|(index, _)| index)
+            .collect()
+    } else {
+        // Negative indices count back from the end of the dimension, as in numpy
+        let normalize_index = |index: i128| {
+            let wrapped = if index < 0 {
+                index + i128::from(length)
+            } else {
+                index
+            };
+            u64::try_from(wrapped)
+                .ok()
+                .filter(|&index| index < length)
+                .ok_or_else(|| {
+                    PyErr::new::<PyIndexError, _>(format!(
+                        "index {index} is out of bounds for a dimension of length {length}"
+                    ))
+                })
+        };
+        integer_array_to_indices(selection)?
+            .into_iter()
+            .map(normalize_index)
+            .collect::<PyResult<Vec<u64>>>()?
+    };
+    let range = match (indices.iter().min(), indices.iter().max()) {
+        (Some(&min), Some(&max)) => min..max + 1,
+        _ => 0..0,
+    };
+    let contiguous = indices.windows(2).all(|pair| pair[0] + 1 == pair[1]);
+    Ok((range, (!contiguous).then_some(indices)))
+}
+
+/// Reject `None` (`np.newaxis`) in a selection of the elements of a chunk (or array).
+///
+/// numpy inserts a new axis for `None`, which has no elements of the chunk to select, so it is only supported in selections of the output.
+pub(crate) fn reject_newaxis(selection: &[Bound<'_, PyAny>]) -> PyResult<()> {
+    match selection.iter().position(PyAnyMethods::is_none) {
+        Some(axis) => Err(PyErr::new::<PyIndexError, _>(format!(
+            "newaxis (None) at position {axis} is only supported in the output selection, not the selection of chunk elements"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Convert a selection to the array subset that bounds its elements, and the indices selected along each dimension if any are not contiguous.
+///
+/// `None` (`np.newaxis`) selects an axis of length 1 of an output which has the inserted axis.
+pub(crate) fn selection_to_array_subset(
+    selection: &[Bound<'_, PyAny>],
     shape: &[u64],
-) -> PyResult<ArraySubset> {
+) -> PyResult<(ArraySubset, Option<Vec<Vec<u64>>>)> {
     if selection.is_empty() {
-        Ok(ArraySubset::new_with_shape(vec![1; shape.len()]))
+        Ok((ArraySubset::new_with_shape(vec![1; shape.len()]), None))
     } else {
-        let chunk_ranges = selection
-            .iter()
+        let (ranges, indices): (Vec<_>, Vec<_>) = normalize_selection(selection, shape.len())?
+            .into_iter()
             .zip(shape)
-            .map(|(selection, &shape)| slice_to_range(selection, isize::try_from(shape)?))
-            .collect::<PyResult<Vec<_>>>()?;
-        Ok(ArraySubset::new_with_ranges(&chunk_ranges))
+            .enumerate()
+            .map(|(axis, (selection, &length))| match selection {
+                SelectionEntry::Dimension(selection) => dim_selection_to_range(selection, length)
+                    .map_err(|err| selection_err(err, axis, selection, shape)),
+                SelectionEntry::Full => Ok((0..length, None)),
+                SelectionEntry::NewAxis if length == 1 => Ok((0..1, None)),
+                SelectionEntry::NewAxis => Err(PyErr::new::<PyIndexError, _>(format!(
+                    "newaxis (None) must select an axis of length 1, not {length} (axis {axis} of an array with shape {shape:?})"
+                ))),
+            })
+            .collect::<PyResult<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        let indices = indices.iter().any(Option::is_some).then(|| {
+            indices
+                .into_iter()
+                .zip(&ranges)
+                .map(|(indices, range)| indices.unwrap_or_else(|| range.clone().collect()))
+                .collect()
+        });
+        Ok((ArraySubset::new_with_ranges(&ranges), indices))
     }
 }
+
+/// The selection of a dimension, after normalization.
+enum SelectionEntry<'a, 'py> {
+    /// A slice, index array, or mask.
+    Dimension(&'a Bound<'py, PyAny>),
+    /// Every element, e.g. from an `Ellipsis` or an omitted trailing dimension.
+    Full,
+    /// A singleton axis of an output inserted with `None` (`np.newaxis`).
+    NewAxis,
+}
+
+/// Normalize a selection to one entry per dimension.
+///
+/// An `Ellipsis` expands to full selections of as many dimensions as needed, and omitted trailing dimensions are selected in full.
+fn normalize_selection<'a, 'py>(
+    selection: &'a [Bound<'py, PyAny>],
+    dimensionality: usize,
+) -> PyResult<Vec<SelectionEntry<'a, 'py>>> {
+    let is_ellipsis = |entry: &Bound<'py, PyAny>| entry.is_instance_of::<PyEllipsis>();
+    let num_ellipsis = selection.iter().filter(|entry| is_ellipsis(entry)).count();
+    if num_ellipsis > 1 {
+        return Err(PyErr::new::<PyIndexError, _>(
+            "a selection can only have a single ellipsis (...)".to_string(),
+        ));
+    }
+    let num_explicit = selection.len() - num_ellipsis;
+    let Some(num_implicit) = dimensionality.checked_sub(num_explicit) else {
+        return Err(PyErr::new::<PyIndexError, _>(format!(
+            "too many indices: the selection has {num_explicit} but the shape has {dimensionality} dimensions"
+        )));
+    };
+
+    let mut entries = Vec::with_capacity(dimensionality);
+    for entry in selection {
+        if is_ellipsis(entry) {
+            entries.extend((0..num_implicit).map(|_| SelectionEntry::Full));
+        } else if entry.is_none() {
+            entries.push(SelectionEntry::NewAxis);
+        } else {
+            entries.push(SelectionEntry::Dimension(entry));
+        }
+    }
+    entries.resize_with(dimensionality, || SelectionEntry::Full);
+    Ok(entries)
+}
+
+/// Convert a boolean mask of a chunk to the coordinates of its selected elements in C order.
+fn mask_to_points(
+    mask: &PyReadonlyArrayDyn<'_, bool>,
+    chunk_shape: &[u64],
+) -> PyResult<Vec<Vec<u64>>> {
+    let mask = mask.as_array();
+    if !mask
+        .shape()
+        .iter()
+        .map(|&length| length as u64)
+        .eq(chunk_shape.iter().copied())
+    {
+        return Err(PyErr::new::<PyIndexError, _>(format!(
+            "boolean mask of shape {:?} does not match the chunk shape {chunk_shape:?}",
+            mask.shape()
+        )));
+    }
+    Ok(mask
+        .indexed_iter()
+        .filter(|(_, &selected)| selected)
+        .map(|(index, _)| {
+            index
+                .as_array_view()
+                .iter()
+                .map(|&index| index as u64)
+                .collect()
+        })
+        .collect())
+}
+
+/// The indices of a 1D array of any integer data type.
+fn integer_array_to_indices(array: &Bound<'_, PyAny>) -> PyResult<Vec<i128>> {
+    if let Ok(indices) = array.extract::<PyReadonlyArray1<'_, i64>>() {
+        Ok(indices
+            .as_array()
+            .iter()
+            .map(|&index| index.into())
+            .collect())
+    } else if let Ok(indices) = array.extract::<PyReadonlyArray1<'_, u64>>() {
+        Ok(indices
+            .as_array()
+            .iter()
+            .map(|&index| index.into())
+            .collect())
+    } else {
+        // Index arrays of any other integer data type (e.g. int32, or intp on other platforms) are cast to int64
+        let array = array.downcast::<PyUntypedArray>()?;
+        if !matches!(array.dtype().kind(), b'i' | b'u') {
+            return Err(PyErr::new::<PyTypeError, _>(format!(
+                "index arrays must have an integer or boolean data type, not {}",
+                array.dtype()
+            )));
+        }
+        let indices = array.call_method1("astype", ("int64",))?;
+        let indices: PyReadonlyArray1<'_, i64> = indices.extract()?;
+        Ok(indices
+            .as_array()
+            .iter()
+            .map(|&index| index.into())
+            .collect())
+    }
+}
+
+/// Convert the coordinates of each dimension of a chunk (arrays of any integer data type) to the coordinates of each selected element.
+fn coordinates_to_points(
+    coordinates: &[Bound<'_, PyAny>],
+    chunk_shape: &[u64],
+) -> PyResult<Vec<Vec<u64>>> {
+    if coordinates.len() != chunk_shape.len() {
+        return Err(PyErr::new::<PyIndexError, _>(format!(
+            "{} coordinate arrays do not match the chunk dimensionality {}",
+            coordinates.len(),
+            chunk_shape.len()
+        )));
+    }
+    let coordinates = coordinates
+        .iter()
+        .map(integer_array_to_indices)
+        .collect::<PyResult<Vec<_>>>()?;
+    let num_points = coordinates
+        .first()
+        .map_or(0, |coordinates| coordinates.len());
+    if coordinates
+        .iter()
+        .any(|coordinates| coordinates.len() != num_points)
+    {
+        return Err(PyErr::new::<PyIndexError, _>(
+            "coordinate arrays must have the same length".to_string(),
+        ));
+    }
+    (0..num_points)
+        .map(|point| {
+            coordinates
+                .iter()
+                .zip(chunk_shape)
+                .map(|(coordinates, &length)| {
+                    let index = coordinates[point];
+                    u64::try_from(index)
+                        .ok()
+                        .filter(|&index| index < length)
+                        .ok_or_else(|| {
+                            PyErr::new::<PyIndexError, _>(format!(
+                                "index {index} is out of bounds for a dimension of length {length}"
+                            ))
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The array subset that bounds a set of points.
+fn points_to_array_subset(points: &[Vec<u64>], chunk_shape: &[u64]) -> ArraySubset {
+    let ranges: Vec<Range<u64>> = (0..chunk_shape.len())
+        .map(|dimension| {
+            let indices = points.iter().map(|point| point[dimension]);
+            match (indices.clone().min(), indices.max()) {
+                (Some(min), Some(max)) => min..max + 1,
+                _ => 0..0,
+            }
+        })
+        .collect();
+    ArraySubset::new_with_ranges(&ranges)
+}
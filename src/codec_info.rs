@@ -0,0 +1,204 @@
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyModule},
+    Bound, PyAny, PyErr, PyResult, Python,
+};
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+use zarrs::{
+    array::{
+        codec::{ArrayCodecTraits, CodecError, CodecTraits},
+        ChunkRepresentation, CodecChain,
+    },
+    metadata::v3::MetadataV3,
+};
+
+use crate::utils::PyErrExt as _;
+
+/// A codec of the codec chain of a pipeline.
+#[derive(Debug, Clone)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct CodecInfo {
+    /// The name of the codec in the array metadata (e.g. `zstd`).
+    #[pyo3(get)]
+    pub name: String,
+    /// The kind of the codec, `array_to_array`, `array_to_bytes`, or `bytes_to_bytes`.
+    #[pyo3(get)]
+    pub kind: String,
+    /// Whether the codec can decode a subset of a chunk without decoding the whole chunk.
+    #[pyo3(get)]
+    pub partial_decoding: bool,
+    configuration: String,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl CodecInfo {
+    /// The configuration of the codec in the array metadata (a `dict`), or `None`.
+    #[getter]
+    fn configuration<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        PyModule::import(py, "json")?
+            .getattr("loads")?
+            .call1((&self.configuration,))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CodecInfo(name='{}', kind='{}', partial_decoding={}, configuration={})",
+            self.name,
+            self.kind,
+            if self.partial_decoding {
+                "True"
+            } else {
+                "False"
+            },
+            self.configuration
+        )
+    }
+}
+
+impl CodecInfo {
+    /// The information of a codec, if it has metadata (codecs without metadata are not in the array metadata).
+    fn new<C: CodecTraits + ?Sized>(codec: &C, kind: &str) -> PyResult<Option<Self>> {
+        let Some(metadata) = codec.create_metadata() else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            name: metadata.name().to_string(),
+            kind: kind.to_string(),
+            partial_decoding: !codec.partial_decoder_decodes_all(),
+            configuration: serde_json::to_string(&metadata.configuration())
+                .map_py_err::<PyValueError>()?,
+        }))
+    }
+}
+
+/// The information of the codecs of a codec chain, in the order they are applied when encoding.
+pub(crate) fn codec_chain_info(codec_chain: &CodecChain) -> PyResult<Vec<CodecInfo>> {
+    let array_to_array = codec_chain
+        .array_to_array_codecs()
+        .iter()
+        .map(|codec| CodecInfo::new(codec.as_ref(), "array_to_array"));
+    let array_to_bytes = std::iter::once(CodecInfo::new(
+        codec_chain.array_to_bytes_codec().as_ref(),
+        "array_to_bytes",
+    ));
+    let bytes_to_bytes = codec_chain
+        .bytes_to_bytes_codecs()
+        .iter()
+        .map(|codec| CodecInfo::new(codec.as_ref(), "bytes_to_bytes"));
+    array_to_array
+        .chain(array_to_bytes)
+        .chain(bytes_to_bytes)
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// The error of a codec which does not support chunks with a representation (e.g. their data type).
+fn unsupported_codec_err<C: CodecTraits + ?Sized>(
+    codec: &C,
+    representation: &ChunkRepresentation,
+    err: &CodecError,
+) -> PyErr {
+    let name = codec.create_metadata().map_or_else(
+        || "unknown".to_string(),
+        |metadata| metadata.name().to_string(),
+    );
+    PyErr::new::<PyTypeError, _>(format!(
+        "codec {name} does not support chunks with data type {} and shape {:?}: {err}",
+        representation.data_type(),
+        representation.shape_u64()
+    ))
+}
+
+/// Check that each codec of a codec chain supports the chunks it encodes, starting from chunks with a representation.
+///
+/// The representation of chunks is transformed by each codec (e.g. the data type by `astype` codecs, or the shape by `transpose` codecs),
+/// so an error names the codec which rejects its input rather than failing when a chunk is first encoded or decoded.
+pub(crate) fn validate_codec_chain(
+    codec_chain: &CodecChain,
+    representation: &ChunkRepresentation,
+) -> PyResult<()> {
+    let mut representation = representation.clone();
+    for codec in codec_chain.array_to_array_codecs() {
+        let codec = codec.as_ref();
+        codec
+            .recommended_concurrency(&representation)
+            .map_err(|err| unsupported_codec_err(codec, &representation, &err))?;
+        representation = codec
+            .encoded_representation(&representation)
+            .map_err(|err| unsupported_codec_err(codec, &representation, &err))?;
+    }
+    let codec = codec_chain.array_to_bytes_codec().as_ref();
+    codec
+        .recommended_concurrency(&representation)
+        .map_err(|err| unsupported_codec_err(codec, &representation, &err))?;
+    codec
+        .compute_encoded_size(&representation)
+        .map_err(|err| unsupported_codec_err(codec, &representation, &err))?;
+    Ok(())
+}
+
+/// The memory order of the elements of chunks as encoded, i.e. the dimensions of chunks from the slowest to the fastest varying.
+///
+/// The dimensions are permuted by `transpose` codecs, including those of the inner chunks of `sharding_indexed` codecs.
+pub(crate) fn codec_chain_memory_order(
+    codec_chain: &CodecChain,
+    ndim: usize,
+) -> PyResult<Vec<usize>> {
+    let codecs: Vec<MetadataV3> = codec_chain
+        .array_to_array_codecs()
+        .iter()
+        .map(|codec| codec.create_metadata())
+        .chain(std::iter::once(
+            codec_chain.array_to_bytes_codec().create_metadata(),
+        ))
+        .flatten()
+        .collect();
+    let mut order: Vec<usize> = (0..ndim).collect();
+    permute_memory_order(&codecs, &mut order)?;
+    Ok(order)
+}
+
+/// Permute the memory order of the dimensions of chunks by the `transpose` codecs of codec metadata.
+fn permute_memory_order(codecs: &[MetadataV3], order: &mut Vec<usize>) -> PyResult<()> {
+    for codec in codecs {
+        let configuration = codec.configuration();
+        match codec.name() {
+            "transpose" => {
+                let permutation = configuration
+                    .and_then(|configuration| configuration.get("order"))
+                    .and_then(|permutation| {
+                        serde_json::from_value::<Vec<usize>>(permutation.clone()).ok()
+                    })
+                    .filter(|permutation| {
+                        permutation.len() == order.len()
+                            && permutation.iter().all(|&dimension| dimension < order.len())
+                    })
+                    .ok_or_else(|| {
+                        PyErr::new::<PyValueError, _>(format!(
+                            "transpose codec has an invalid order for {} dimensions",
+                            order.len()
+                        ))
+                    })?;
+                *order = permutation
+                    .iter()
+                    .map(|&dimension| order[dimension])
+                    .collect();
+            }
+            "sharding_indexed" => {
+                if let Some(inner_codecs) =
+                    configuration.and_then(|configuration| configuration.get("codecs"))
+                {
+                    let inner_codecs: Vec<MetadataV3> =
+                        serde_json::from_value(inner_codecs.clone())
+                            .map_py_err::<PyValueError>()?;
+                    permute_memory_order(&inner_codecs, order)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
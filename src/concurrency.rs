@@ -1,10 +1,16 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
 use pyo3::{exceptions::PyRuntimeError, PyErr, PyResult};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use zarrs::array::{
     codec::CodecOptions, concurrency::calc_concurrency_outer_inner, ArrayCodecTraits,
     RecommendedConcurrency,
 };
 
-use crate::{chunk_item::ChunksItem, CodecPipelineImpl};
+use crate::{chunk_item::ChunksItem, codec_info, utils::PyErrExt, CodecPipelineImpl};
 
 pub trait ChunkConcurrentLimitAndCodecOptions {
     fn get_chunk_concurrent_limit_and_codec_options(
@@ -26,6 +32,7 @@ where
             return Ok(None);
         };
         let chunk_representation = chunk_descriptions0.representation();
+        codec_info::validate_codec_chain(&codec_pipeline_impl.codec_chain, chunk_representation)?;
 
         let codec_concurrency = codec_pipeline_impl
             .codec_chain
@@ -36,11 +43,19 @@ where
             std::cmp::min(codec_pipeline_impl.chunk_concurrent_minimum, num_chunks);
         let max_concurrent_chunks =
             std::cmp::max(codec_pipeline_impl.chunk_concurrent_maximum, num_chunks);
-        let (chunk_concurrent_limit, codec_concurrent_limit) = calc_concurrency_outer_inner(
-            codec_pipeline_impl.num_threads,
-            &RecommendedConcurrency::new(min_concurrent_chunks..max_concurrent_chunks),
-            &codec_concurrency,
-        );
+        let (chunk_concurrent_limit, codec_concurrent_limit) =
+            match codec_pipeline_impl.codec_concurrent_target {
+                // Each chunk is encoded/decoded by the target number of threads, and the remaining threads work on other chunks
+                Some(codec_concurrent_target) => (
+                    (codec_pipeline_impl.num_threads / codec_concurrent_target).max(1),
+                    codec_concurrent_target,
+                ),
+                None => calc_concurrency_outer_inner(
+                    codec_pipeline_impl.num_threads,
+                    &RecommendedConcurrency::new(min_concurrent_chunks..max_concurrent_chunks),
+                    &codec_concurrency,
+                ),
+            };
         let codec_options = codec_pipeline_impl
             .codec_options
             .into_builder()
@@ -49,3 +64,86 @@ where
         Ok(Some((chunk_concurrent_limit, codec_options)))
     }
 }
+
+/// Codec options of a single call, which override those of the codec pipeline.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CodecOptionsOverrides {
+    pub(crate) validate_checksums: Option<bool>,
+    pub(crate) store_empty_chunks: Option<bool>,
+    pub(crate) concurrent_target: Option<usize>,
+}
+
+impl CodecOptionsOverrides {
+    pub(crate) fn apply(self, codec_options: CodecOptions) -> CodecOptions {
+        let mut codec_options = codec_options.into_builder();
+        if let Some(validate_checksums) = self.validate_checksums {
+            codec_options = codec_options.validate_checksums(validate_checksums);
+        }
+        if let Some(store_empty_chunks) = self.store_empty_chunks {
+            codec_options = codec_options.store_empty_chunks(store_empty_chunks);
+        }
+        if let Some(concurrent_target) = self.concurrent_target {
+            codec_options = codec_options.concurrent_target(concurrent_target);
+        }
+        codec_options.build()
+    }
+}
+
+/// Thread pools shared by all codec pipelines, keyed by their name and number of threads.
+static THREAD_POOLS: OnceLock<Mutex<HashMap<(&'static str, usize), Arc<ThreadPool>>>> =
+    OnceLock::new();
+
+/// Get the shared thread pool with a name and number of threads, building it on first use.
+fn shared_thread_pool(name: &'static str, num_threads: usize) -> PyResult<Arc<ThreadPool>> {
+    let mut thread_pools = THREAD_POOLS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|_| PyErr::new::<PyRuntimeError, _>("thread pools lock poisoned"))?;
+    if let Some(thread_pool) = thread_pools.get(&(name, num_threads)) {
+        return Ok(thread_pool.clone());
+    }
+    let thread_pool = Arc::new(
+        ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(move |index| format!("zarrs-{name}-{index}"))
+            .build()
+            .map_py_err::<PyRuntimeError>()?,
+    );
+    thread_pools.insert((name, num_threads), thread_pool.clone());
+    Ok(thread_pool)
+}
+
+/// Separate thread pools for store I/O and codec work.
+///
+/// Chunks are retrieved/stored on the I/O thread pool, so many store requests can be in flight (e.g. for remote stores),
+/// while the codec work of each chunk runs on the codec thread pool, which is bounded by the number of threads.
+///
+/// The thread pools are shared by all codec pipelines with the same limits, so creating a codec pipeline does not spawn threads.
+/// The codec work cannot run on the global rayon thread pool, as work spawned from a thread of the I/O thread pool stays in that pool,
+/// so it runs on a shared codec thread pool instead.
+pub(crate) struct IoConcurrency {
+    io_thread_pool: Arc<ThreadPool>,
+    codec_thread_pool: Arc<ThreadPool>,
+}
+
+impl IoConcurrency {
+    pub(crate) fn new(io_concurrent_limit: usize, num_threads: usize) -> PyResult<Self> {
+        Ok(Self {
+            io_thread_pool: shared_thread_pool("io", io_concurrent_limit)?,
+            codec_thread_pool: shared_thread_pool("codec", num_threads)?,
+        })
+    }
+
+    /// The maximum number of chunks retrieved/stored concurrently.
+    pub(crate) fn io_concurrent_limit(&self) -> usize {
+        self.io_thread_pool.current_num_threads()
+    }
+
+    pub(crate) fn install_io<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        self.io_thread_pool.install(f)
+    }
+
+    pub(crate) fn install_codec<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        self.codec_thread_pool.install(f)
+    }
+}
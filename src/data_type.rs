@@ -0,0 +1,78 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    pyfunction,
+    types::{PyAnyMethods, PyBytes, PyBytesMethods},
+    Bound, Py, PyAny, PyErr, PyResult,
+};
+use zarrs::array::DataType;
+
+use crate::utils::PyErrExt;
+
+/// A fixed size data type registered from Python.
+struct ExtensionDataType {
+    size: usize,
+    fill_value_parser: Option<Py<PyAny>>,
+}
+
+static EXTENSION_DATA_TYPES: OnceLock<RwLock<HashMap<String, ExtensionDataType>>> = OnceLock::new();
+
+fn extension_data_types() -> &'static RwLock<HashMap<String, ExtensionDataType>> {
+    EXTENSION_DATA_TYPES.get_or_init(RwLock::default)
+}
+
+/// Register a fixed size data type, so arrays with a numpy data type named `name` (i.e. `str(dtype)`) use the codec pipeline.
+///
+/// The elements of the data type are `size` bytes, which are encoded as is (raw bits).
+/// `fill_value_parser` converts the fill value of an array to the `bytes` of an element, otherwise the fill value must be `bytes` or have a `tobytes` method (e.g. a numpy scalar).
+/// Registering a name again replaces its data type.
+#[pyfunction]
+#[pyo3(signature = (name, size, fill_value_parser=None))]
+pub fn register_data_type(
+    name: String,
+    size: usize,
+    fill_value_parser: Option<Py<PyAny>>,
+) -> PyResult<()> {
+    if size == 0 {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "data type {name} must have a size of at least 1 byte"
+        )));
+    }
+    extension_data_types()
+        .write()
+        .map_py_err::<PyRuntimeError>()?
+        .insert(
+            name,
+            ExtensionDataType {
+                size,
+                fill_value_parser,
+            },
+        );
+    Ok(())
+}
+
+/// The data type registered as `name` with [`register_data_type`], if any, and the fill value parsed by its fill value parser.
+pub(crate) fn extension_data_type(
+    name: &str,
+    fill_value: &Bound<'_, PyAny>,
+) -> PyResult<Option<(DataType, Option<Vec<u8>>)>> {
+    let data_types = extension_data_types()
+        .read()
+        .map_py_err::<PyRuntimeError>()?;
+    let Some(data_type) = data_types.get(name) else {
+        return Ok(None);
+    };
+    let fill_value = data_type
+        .fill_value_parser
+        .as_ref()
+        .map(|parser| -> PyResult<_> {
+            let parsed = parser.bind(fill_value.py()).call1((fill_value,))?;
+            Ok(parsed.downcast::<PyBytes>()?.as_bytes().to_vec())
+        })
+        .transpose()?;
+    Ok(Some((DataType::RawBits(data_type.size), fill_value)))
+}
@@ -0,0 +1,61 @@
+use pyo3::{exceptions::PyTypeError, pyfunction, PyErr, PyResult};
+use serde_json::Value;
+use zarrs::metadata::v3::MetadataV3;
+
+use crate::utils::map_codecs_metadata;
+
+/// The experimental codecs of `zarrs` compiled into the extension, and their other names in the array metadata.
+///
+/// Experimental codecs are not (yet) zarr extensions, so their metadata and encoding may change between `zarrs` releases.
+const EXPERIMENTAL_CODECS: [(&str, &[&str]); 6] = [
+    ("bitround", &["numcodecs.bitround"]),
+    ("fixedscaleoffset", &["numcodecs.fixedscaleoffset"]),
+    ("fletcher32", &["numcodecs.fletcher32"]),
+    ("pcodec", &["numcodecs.pcodec"]),
+    (
+        "zfp",
+        &[
+            "numcodecs.zfpy",
+            "https://codec.zarrs.dev/array_to_bytes/zfp",
+        ],
+    ),
+    (
+        "zarrs.squeeze",
+        &["squeeze", "https://codec.zarrs.dev/array_to_array/squeeze"],
+    ),
+];
+
+/// The names of the experimental codecs of `zarrs` compiled into the extension.
+///
+/// They are enabled unless a `CodecPipelineImpl` is created with `experimental_codecs=False`.
+/// Codecs behind `zarrs` features which are not compiled into the extension cannot be enabled at runtime.
+#[pyfunction]
+pub fn experimental_codecs() -> Vec<&'static str> {
+    EXPERIMENTAL_CODECS.iter().map(|(name, _)| *name).collect()
+}
+
+fn experimental_codec_name(name: &str) -> Option<&'static str> {
+    EXPERIMENTAL_CODECS
+        .iter()
+        .find(|(experimental_name, aliases)| *experimental_name == name || aliases.contains(&name))
+        .map(|(experimental_name, _)| *experimental_name)
+}
+
+/// Fail with a `TypeError` naming the first experimental codec of codec metadata (including those of shards and their index).
+pub(crate) fn reject_experimental_codecs(codecs: Vec<MetadataV3>) -> PyResult<Vec<MetadataV3>> {
+    let mut experimental = None;
+    let codecs = map_codecs_metadata(codecs, |codec| {
+        if experimental.is_none() {
+            experimental = codec
+                .get("name")
+                .and_then(Value::as_str)
+                .and_then(experimental_codec_name);
+        }
+    })?;
+    match experimental {
+        Some(name) => Err(PyErr::new::<PyTypeError, _>(format!(
+            "codec {name} is experimental, and experimental codecs are disabled"
+        ))),
+        None => Ok(codecs),
+    }
+}
@@ -0,0 +1,168 @@
+use pyo3::{
+    exceptions::PyValueError,
+    types::{
+        PyAnyMethods, PyBool, PyBytes, PyComplex, PyComplexMethods, PyFloat, PyInt, PyList,
+        PyString, PyStringMethods, PyTuple,
+    },
+    Bound, PyAny, PyErr, PyResult,
+};
+use serde_json::{Number, Value};
+use zarrs::{
+    array::{ArrayBytes, ChunkRepresentation, DataType, FillValue},
+    metadata::v3::array::fill_value::FillValueMetadataV3,
+};
+
+use crate::utils::PyErrExt;
+
+/// Whether the bits of two 16-bit floats are equal as numbers, given the masks of their exponent and mantissa.
+fn float16_eq(a: u16, b: u16, exponent_mask: u16, mantissa_mask: u16) -> bool {
+    let is_nan = |bits: u16| bits & exponent_mask == exponent_mask && bits & mantissa_mask != 0;
+    let is_zero = |bits: u16| bits & 0x7fff == 0;
+    a == b || (is_nan(a) && is_nan(b)) || (is_zero(a) && is_zero(b))
+}
+
+/// Whether two elements of a floating point or complex data type are equal as numbers.
+///
+/// Any NaN equals any NaN, whatever its bits (e.g. its sign or payload), and `-0.0` equals `0.0`, as in `np.array_equal(a, b, equal_nan=True)`.
+#[allow(clippy::float_cmp)]
+fn float_eq(data_type: &DataType, a: &[u8], b: &[u8]) -> bool {
+    match (data_type, a, b) {
+        (DataType::Float16, &[a0, a1], &[b0, b1]) => float16_eq(
+            u16::from_ne_bytes([a0, a1]),
+            u16::from_ne_bytes([b0, b1]),
+            0x7c00,
+            0x03ff,
+        ),
+        (DataType::BFloat16, &[a0, a1], &[b0, b1]) => float16_eq(
+            u16::from_ne_bytes([a0, a1]),
+            u16::from_ne_bytes([b0, b1]),
+            0x7f80,
+            0x007f,
+        ),
+        (DataType::Float32, a, b) => match (<[u8; 4]>::try_from(a), <[u8; 4]>::try_from(b)) {
+            (Ok(a), Ok(b)) => {
+                let (a, b) = (f32::from_ne_bytes(a), f32::from_ne_bytes(b));
+                a == b || (a.is_nan() && b.is_nan())
+            }
+            _ => false,
+        },
+        (DataType::Float64, a, b) => match (<[u8; 8]>::try_from(a), <[u8; 8]>::try_from(b)) {
+            (Ok(a), Ok(b)) => {
+                let (a, b) = (f64::from_ne_bytes(a), f64::from_ne_bytes(b));
+                a == b || (a.is_nan() && b.is_nan())
+            }
+            _ => false,
+        },
+        (DataType::Complex64, a, b) if a.len() == 8 && b.len() == 8 => {
+            float_eq(&DataType::Float32, &a[..4], &b[..4])
+                && float_eq(&DataType::Float32, &a[4..], &b[4..])
+        }
+        (DataType::Complex128, a, b) if a.len() == 16 && b.len() == 16 => {
+            float_eq(&DataType::Float64, &a[..8], &b[..8])
+                && float_eq(&DataType::Float64, &a[8..], &b[8..])
+        }
+        _ => false,
+    }
+}
+
+fn is_float(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Float16
+            | DataType::BFloat16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Complex64
+            | DataType::Complex128
+    )
+}
+
+/// Whether an element is the fill value.
+///
+/// Elements of floating point and complex data types are compared as numbers, see [`float_eq`].
+pub(crate) fn element_is_fill_value(
+    data_type: &DataType,
+    element: &[u8],
+    fill_value: &FillValue,
+) -> bool {
+    element == fill_value.as_ne_bytes() || float_eq(data_type, element, fill_value.as_ne_bytes())
+}
+
+/// Whether every element of a decoded chunk is the fill value, see [`element_is_fill_value`].
+pub(crate) fn is_fill_value(bytes: &ArrayBytes, representation: &ChunkRepresentation) -> bool {
+    let data_type = representation.data_type();
+    let fill_value = representation.fill_value();
+    if bytes.is_fill_value(fill_value) {
+        return true;
+    }
+    match bytes {
+        ArrayBytes::Fixed(bytes) if is_float(data_type) => bytes
+            .chunks_exact(data_type.fixed_size().unwrap_or(1))
+            .all(|element| float_eq(data_type, element, fill_value.as_ne_bytes())),
+        _ => false,
+    }
+}
+
+/// The JSON form of a float in array metadata, which has strings for non-finite values.
+fn float_to_json(value: f64) -> Value {
+    if value.is_nan() {
+        Value::String("NaN".to_string())
+    } else if value.is_infinite() {
+        Value::String(if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string())
+    } else {
+        Number::from_f64(value).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Convert a Python fill value to its JSON form in array metadata.
+fn fill_value_to_json(fill_value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if let Ok(fill_value) = fill_value.downcast::<PyBool>() {
+        Ok(Value::Bool(fill_value.extract()?))
+    } else if let Ok(fill_value) = fill_value.downcast::<PyInt>() {
+        if let Ok(fill_value) = fill_value.extract::<i64>() {
+            Ok(Value::from(fill_value))
+        } else {
+            Ok(Value::from(fill_value.extract::<u64>()?))
+        }
+    } else if let Ok(fill_value) = fill_value.downcast::<PyFloat>() {
+        Ok(float_to_json(fill_value.extract()?))
+    } else if let Ok(fill_value) = fill_value.downcast::<PyComplex>() {
+        Ok(Value::Array(vec![
+            float_to_json(fill_value.real()),
+            float_to_json(fill_value.imag()),
+        ]))
+    } else if let Ok(fill_value) = fill_value.downcast::<PyString>() {
+        Ok(Value::String(fill_value.to_str()?.to_string()))
+    } else if fill_value.is_instance_of::<PyList>() || fill_value.is_instance_of::<PyTuple>() {
+        fill_value
+            .try_iter()?
+            .map(|element| fill_value_to_json(&element?))
+            .collect::<PyResult<_>>()
+            .map(Value::Array)
+    } else {
+        Err(PyErr::new::<PyValueError, _>(format!(
+            "Unsupported fill value {fill_value:?}"
+        )))
+    }
+}
+
+/// Parse a fill value in its JSON form in array metadata (e.g. `"NaN"`, `"-Infinity"`, a hex string of the bits of a float such as `"0x7fc00001"`, or `[re, im]` for a complex number) for a data type.
+///
+/// Returns [`None`] if the fill value is already encoded, i.e. it is `bytes` or a numpy scalar, or the data type is variable length.
+pub(crate) fn fill_value_from_metadata(
+    data_type: &DataType,
+    fill_value: &Bound<'_, PyAny>,
+) -> PyResult<Option<FillValue>> {
+    if matches!(data_type, DataType::String | DataType::Bytes)
+        || fill_value.is_instance_of::<PyBytes>()
+        || fill_value.hasattr("tobytes")?
+    {
+        return Ok(None);
+    }
+    let metadata: FillValueMetadataV3 =
+        serde_json::from_value(fill_value_to_json(fill_value)?).map_py_err::<PyValueError>()?;
+    data_type
+        .fill_value_from_metadata(&metadata)
+        .map(Some)
+        .map_py_err::<PyValueError>()
+}
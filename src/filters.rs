@@ -0,0 +1,389 @@
+use std::{
+    borrow::Cow,
+    ops::{Add, Sub},
+    sync::Arc,
+};
+
+use serde_json::Value;
+use zarrs::{
+    array::{
+        codec::{
+            BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, DataType, Endianness, RawBytes,
+        RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+use crate::{cast, metadata_v2};
+
+// The `delta` and `shuffle` filters of numcodecs, which zarr V2 arrays may apply before their compressor (e.g. `[delta, shuffle, blosc]`).
+//
+// They are implemented as bytes to bytes codecs, so they apply to the encoded bytes of elements in C order as they do in zarr V2.
+
+fn configuration_value<'a>(
+    metadata: &'a MetadataV3,
+    field: &str,
+) -> Result<&'a Value, PluginCreateError> {
+    metadata
+        .configuration()
+        .and_then(|configuration| configuration.get(field))
+        .ok_or_else(|| {
+            PluginCreateError::Other(format!(
+                "codec {} requires the {field} configuration",
+                metadata.name()
+            ))
+        })
+}
+
+/// A zarr V2 data type string (e.g. `<i4`) of a filter configuration, and the endianness of its elements.
+fn configuration_data_type(
+    metadata: &MetadataV3,
+    field: &str,
+) -> Result<(DataType, Option<Endianness>), PluginCreateError> {
+    let invalid = |err: String| {
+        PluginCreateError::Other(format!(
+            "codec {} has an invalid {field} configuration: {err}",
+            metadata.name()
+        ))
+    };
+    let dtype = configuration_value(metadata, field)?
+        .as_str()
+        .ok_or_else(|| invalid("not a string".to_string()))?;
+    let (data_type, endianness) =
+        metadata_v2::data_type_v2_to_v3(dtype).map_err(|err| invalid(err.to_string()))?;
+    let data_type = DataType::from_metadata(&data_type).map_err(|err| invalid(err.to_string()))?;
+    if data_type.fixed_size().is_none() {
+        return Err(invalid(format!("{dtype} is not a fixed size data type")));
+    }
+    Ok((data_type, endianness))
+}
+
+fn is_name_shuffle(name: &str) -> bool {
+    matches!(name, "shuffle" | "numcodecs.shuffle")
+}
+
+fn create_codec_shuffle(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let element_size = configuration_value(metadata, "elementsize")?
+        .as_u64()
+        .and_then(|element_size| usize::try_from(element_size).ok())
+        .ok_or_else(|| {
+            PluginCreateError::Other("shuffle elementsize must be an integer".to_string())
+        })?;
+    Ok(Codec::BytesToBytes(Arc::new(ShuffleCodec {
+        metadata: metadata.clone(),
+        element_size,
+    })))
+}
+
+fn is_name_delta(name: &str) -> bool {
+    matches!(name, "delta" | "numcodecs.delta")
+}
+
+fn create_codec_delta(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let dtype = configuration_data_type(metadata, "dtype")?;
+    let astype = match metadata
+        .configuration()
+        .and_then(|configuration| configuration.get("astype"))
+    {
+        Some(Value::Null) | None => dtype.clone(),
+        Some(_) => configuration_data_type(metadata, "astype")?,
+    };
+    Ok(Codec::BytesToBytes(Arc::new(DeltaCodec {
+        metadata: metadata.clone(),
+        dtype,
+        astype,
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new("shuffle", is_name_shuffle, create_codec_shuffle)
+}
+
+inventory::submit! {
+    CodecPlugin::new("delta", is_name_delta, create_codec_delta)
+}
+
+/// The `shuffle` filter, which groups the first bytes of every element, then the second bytes, and so on.
+///
+/// Trailing bytes which do not form a whole element are left in place.
+#[derive(Debug)]
+struct ShuffleCodec {
+    metadata: MetadataV3,
+    element_size: usize,
+}
+
+impl ShuffleCodec {
+    fn shuffle(&self, bytes: &[u8], unshuffle: bool) -> Vec<u8> {
+        let element_size = self.element_size.max(1);
+        let count = bytes.len() / element_size;
+        let mut shuffled = bytes.to_vec();
+        for element in 0..count {
+            for byte in 0..element_size {
+                let (unshuffled_index, shuffled_index) =
+                    (element * element_size + byte, byte * count + element);
+                if unshuffle {
+                    shuffled[unshuffled_index] = bytes[shuffled_index];
+                } else {
+                    shuffled[shuffled_index] = bytes[unshuffled_index];
+                }
+            }
+        }
+        shuffled
+    }
+}
+
+/// The `delta` filter, which encodes the first element and the difference of every other element to the element before it.
+///
+/// Differences are computed in the data type `dtype` (wrapping integers), and are stored as the data type `astype`.
+#[derive(Debug)]
+struct DeltaCodec {
+    metadata: MetadataV3,
+    dtype: (DataType, Option<Endianness>),
+    astype: (DataType, Option<Endianness>),
+}
+
+/// The arithmetic of the elements of a delta filter.
+trait DeltaElement: Copy {
+    const SIZE: usize;
+    fn from_bytes(bytes: &[u8]) -> Self;
+    fn to_bytes(self) -> Vec<u8>;
+    fn delta_sub(self, other: Self) -> Self;
+    fn delta_add(self, other: Self) -> Self;
+}
+
+macro_rules! delta_element {
+    ($type:ty, $sub:ident, $add:ident) => {
+        impl DeltaElement for $type {
+            const SIZE: usize = std::mem::size_of::<$type>();
+            fn from_bytes(bytes: &[u8]) -> Self {
+                <$type>::from_ne_bytes(bytes.try_into().expect("chunks_exact"))
+            }
+            fn to_bytes(self) -> Vec<u8> {
+                self.to_ne_bytes().to_vec()
+            }
+            fn delta_sub(self, other: Self) -> Self {
+                self.$sub(other)
+            }
+            fn delta_add(self, other: Self) -> Self {
+                self.$add(other)
+            }
+        }
+    };
+}
+
+delta_element!(i8, wrapping_sub, wrapping_add);
+delta_element!(i16, wrapping_sub, wrapping_add);
+delta_element!(i32, wrapping_sub, wrapping_add);
+delta_element!(i64, wrapping_sub, wrapping_add);
+delta_element!(u8, wrapping_sub, wrapping_add);
+delta_element!(u16, wrapping_sub, wrapping_add);
+delta_element!(u32, wrapping_sub, wrapping_add);
+delta_element!(u64, wrapping_sub, wrapping_add);
+delta_element!(f32, sub, add);
+delta_element!(f64, sub, add);
+
+fn delta_encode<T: DeltaElement>(bytes: &[u8]) -> Vec<u8> {
+    let mut previous: Option<T> = None;
+    bytes
+        .chunks_exact(T::SIZE)
+        .flat_map(|element| {
+            let element = T::from_bytes(element);
+            let delta = previous.map_or(element, |previous| element.delta_sub(previous));
+            previous = Some(element);
+            delta.to_bytes()
+        })
+        .collect()
+}
+
+fn delta_decode<T: DeltaElement>(bytes: &[u8]) -> Vec<u8> {
+    let mut previous: Option<T> = None;
+    bytes
+        .chunks_exact(T::SIZE)
+        .flat_map(|delta| {
+            let delta = T::from_bytes(delta);
+            let element = previous.map_or(delta, |previous| previous.delta_add(delta));
+            previous = Some(element);
+            element.to_bytes()
+        })
+        .collect()
+}
+
+/// Swap the bytes of elements between native and `endianness` byte order.
+fn swap_endianness(
+    mut bytes: Vec<u8>,
+    element_size: usize,
+    endianness: Option<Endianness>,
+) -> Vec<u8> {
+    if endianness.is_some_and(|endianness| !endianness.is_native()) {
+        bytes
+            .chunks_exact_mut(element_size)
+            .for_each(<[u8]>::reverse);
+    }
+    bytes
+}
+
+impl DeltaCodec {
+    fn delta(&self, bytes: &[u8], decode: bool) -> Result<Vec<u8>, CodecError> {
+        let (data_type, endianness) = &self.dtype;
+        let (astype, astype_endianness) = &self.astype;
+        let size = |data_type: &DataType| data_type.fixed_size().unwrap_or(1);
+        let cast = |bytes: &[u8], from: &DataType, to: &DataType| {
+            if from == to {
+                Ok(bytes.to_vec())
+            } else {
+                cast::cast(bytes, from, to).map_err(|err| CodecError::Other(err.to_string()))
+            }
+        };
+        macro_rules! apply {
+            ($bytes:expr, $function:ident) => {
+                match data_type {
+                    DataType::Int8 => $function::<i8>($bytes),
+                    DataType::Int16 => $function::<i16>($bytes),
+                    DataType::Int32 => $function::<i32>($bytes),
+                    DataType::Int64 => $function::<i64>($bytes),
+                    DataType::UInt8 | DataType::Bool => $function::<u8>($bytes),
+                    DataType::UInt16 => $function::<u16>($bytes),
+                    DataType::UInt32 => $function::<u32>($bytes),
+                    DataType::UInt64 => $function::<u64>($bytes),
+                    DataType::Float32 => $function::<f32>($bytes),
+                    DataType::Float64 => $function::<f64>($bytes),
+                    _ => {
+                        return Err(CodecError::Other(format!(
+                            "delta is not supported for data type {data_type}"
+                        )))
+                    }
+                }
+            };
+        }
+        if decode {
+            let deltas = swap_endianness(bytes.to_vec(), size(astype), *astype_endianness);
+            let deltas = cast(&deltas, astype, data_type)?;
+            let elements = apply!(&deltas, delta_decode);
+            Ok(swap_endianness(elements, size(data_type), *endianness))
+        } else {
+            let elements = swap_endianness(bytes.to_vec(), size(data_type), *endianness);
+            let deltas = apply!(&elements, delta_encode);
+            let deltas = cast(&deltas, data_type, astype)?;
+            Ok(swap_endianness(deltas, size(astype), *astype_endianness))
+        }
+    }
+
+    fn encoded_size(&self, size: u64) -> u64 {
+        let dtype_size = self.dtype.0.fixed_size().unwrap_or(1) as u64;
+        let astype_size = self.astype.0.fixed_size().unwrap_or(1) as u64;
+        size / dtype_size * astype_size
+    }
+}
+
+impl CodecTraits for ShuffleCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        Some(self.metadata.clone())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl BytesToBytesCodecTraits for ShuffleCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        *decoded_representation
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(self.shuffle(&decoded_value, false)))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(self.shuffle(&encoded_value, true)))
+    }
+}
+
+impl CodecTraits for DeltaCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        Some(self.metadata.clone())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl BytesToBytesCodecTraits for DeltaCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        match *decoded_representation {
+            BytesRepresentation::FixedSize(size) => {
+                BytesRepresentation::FixedSize(self.encoded_size(size))
+            }
+            BytesRepresentation::BoundedSize(size) => {
+                BytesRepresentation::BoundedSize(self.encoded_size(size))
+            }
+            BytesRepresentation::UnboundedSize => BytesRepresentation::UnboundedSize,
+        }
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.delta(&decoded_value, false).map(Cow::Owned)
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.delta(&encoded_value, true).map(Cow::Owned)
+    }
+}
@@ -0,0 +1,436 @@
+use std::ops::Range;
+
+use pyo3::{exceptions::PyValueError, PyErr, PyResult};
+use zarrs::array_subset::ArraySubset;
+
+/// The layout of the elements of an array in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Layout {
+    /// The stride of each dimension in bytes, which may be negative.
+    strides: Vec<isize>,
+    /// The byte offset of the first element of the array.
+    offset: usize,
+}
+
+impl Layout {
+    /// Create a layout from the byte strides of each dimension and the byte offset of the first element.
+    pub(crate) fn new(strides: Vec<isize>, offset: usize) -> Self {
+        Self { strides, offset }
+    }
+
+    /// The layout of a C order array.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn c_order(shape: &[u64], element_size: usize) -> Self {
+        let mut strides = vec![element_size as isize; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1] as isize;
+        }
+        Self::new(strides, 0)
+    }
+
+    /// The layout of a Fortran (F) order array.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn f_order(shape: &[u64], element_size: usize) -> Self {
+        let mut strides = vec![element_size as isize; shape.len()];
+        for i in 1..shape.len() {
+            strides[i] = strides[i - 1] * shape[i - 1] as isize;
+        }
+        Self::new(strides, 0)
+    }
+}
+
+/// The byte range of each contiguous run of an indexed selection of an array with a layout, in C order.
+///
+/// The selection is the cross product of the indices selected along each dimension.
+struct Runs<'a> {
+    layout: &'a Layout,
+    indices: &'a [Vec<u64>],
+    /// The byte offset relative to the start of a row and the byte length of each run of the innermost dimension.
+    row_runs: Vec<(isize, usize)>,
+    /// The position in the indices of each outer dimension, or `None` if every run has been visited.
+    position: Option<Vec<usize>>,
+    /// The byte offset of the current row.
+    row: isize,
+    /// The index of the next run of the current row.
+    run: usize,
+}
+
+impl<'a> Runs<'a> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn new(layout: &'a Layout, indices: &'a [Vec<u64>], element_size: usize) -> Self {
+        let strides = &layout.strides;
+        let row_runs = match strides.len().checked_sub(1) {
+            // A 0-dimensional array has a single element
+            None => vec![(0, element_size)],
+            Some(last) => {
+                // Consecutive indices of a contiguous innermost dimension form a single run
+                let contiguous = strides[last] == element_size as isize;
+                let mut row_runs: Vec<(u64, isize, usize)> = Vec::new();
+                for &index in &indices[last] {
+                    match row_runs.last_mut() {
+                        Some((end, _, length)) if contiguous && *end == index => {
+                            *end += 1;
+                            *length += element_size;
+                        }
+                        _ => {
+                            row_runs.push((index + 1, index as isize * strides[last], element_size))
+                        }
+                    }
+                }
+                row_runs
+                    .into_iter()
+                    .map(|(_, start, length)| (start, length))
+                    .collect()
+            }
+        };
+        let position =
+            (!indices.iter().any(Vec::is_empty)).then(|| vec![0; strides.len().saturating_sub(1)]);
+        let mut runs = Self {
+            layout,
+            indices,
+            row_runs,
+            position,
+            row: 0,
+            run: 0,
+        };
+        runs.row = runs.row_offset();
+        runs
+    }
+
+    /// The byte offset of the row at the current position.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn row_offset(&self) -> isize {
+        self.layout.offset as isize
+            + self.position.as_ref().map_or(0, |position| {
+                position
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &position)| {
+                        self.indices[i][position] as isize * self.layout.strides[i]
+                    })
+                    .sum::<isize>()
+            })
+    }
+
+    /// Advance to the next row, returning false if every row has been visited.
+    fn next_row(&mut self) -> bool {
+        let Some(position) = &mut self.position else {
+            return false;
+        };
+        // Advance the position in the indices of the outer dimensions
+        for dimension in (0..position.len()).rev() {
+            position[dimension] += 1;
+            if position[dimension] < self.indices[dimension].len() {
+                self.row = self.row_offset();
+                return true;
+            }
+            position[dimension] = 0;
+        }
+        self.position = None;
+        false
+    }
+}
+
+impl Iterator for Runs<'_> {
+    type Item = Range<usize>;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn next(&mut self) -> Option<Range<usize>> {
+        self.position.as_ref()?;
+        let (start, length) = self.row_runs[self.run];
+        let offset = (self.row + start) as usize;
+        self.run += 1;
+        if self.run == self.row_runs.len() {
+            self.run = 0;
+            self.next_row();
+        }
+        Some(offset..offset + length)
+    }
+}
+
+fn out_of_bounds_err() -> PyErr {
+    PyErr::new::<PyValueError, _>("indexed selection is out of bounds of the array")
+}
+
+/// The indices of the elements of `subset` selected along each dimension, relative to `origin`.
+///
+/// `indices` are the selected indices if they are not contiguous, otherwise every index of `subset` is selected.
+pub(crate) fn selected_indices(
+    subset: &ArraySubset,
+    indices: Option<&[Vec<u64>]>,
+    origin: &[u64],
+) -> Vec<Vec<u64>> {
+    match indices {
+        Some(indices) => indices
+            .iter()
+            .zip(origin)
+            .map(|(indices, &origin)| indices.iter().map(|&index| index - origin).collect())
+            .collect(),
+        None => subset
+            .to_ranges()
+            .into_iter()
+            .zip(origin)
+            .map(|(range, &origin)| (range.start - origin..range.end - origin).collect())
+            .collect(),
+    }
+}
+
+/// Gather the elements of an indexed selection of an array with `layout` into a contiguous C order buffer.
+pub(crate) fn gather(
+    bytes: &[u8],
+    layout: &Layout,
+    indices: &[Vec<u64>],
+    element_size: usize,
+) -> PyResult<Vec<u8>> {
+    let mut gathered = Vec::new();
+    for run in Runs::new(layout, indices, element_size) {
+        gathered.extend_from_slice(bytes.get(run).ok_or_else(out_of_bounds_err)?);
+    }
+    Ok(gathered)
+}
+
+/// Scatter a contiguous C order buffer into the elements of an indexed selection of an array with `layout`.
+pub(crate) fn scatter(
+    bytes: &mut [u8],
+    layout: &Layout,
+    indices: &[Vec<u64>],
+    element_size: usize,
+    selected_bytes: &[u8],
+) -> PyResult<()> {
+    let mut offset = 0;
+    for run in Runs::new(layout, indices, element_size) {
+        let length = run.len();
+        match (
+            bytes.get_mut(run),
+            selected_bytes.get(offset..offset + length),
+        ) {
+            (Some(run), Some(selected_run)) => run.copy_from_slice(selected_run),
+            _ => return Err(out_of_bounds_err()),
+        }
+        offset += length;
+    }
+    if offset == selected_bytes.len() {
+        Ok(())
+    } else {
+        Err(PyErr::new::<PyValueError, _>(
+            "the number of selected bytes does not match the indexed selection",
+        ))
+    }
+}
+
+/// Copy the elements of an indexed selection of an array with `src_layout` to the elements of an indexed selection of an array with `dst_layout`, in C order.
+///
+/// The selections must have the same number of elements, but may have different shapes (e.g. where axes are dropped).
+/// This is equivalent to a [`gather`] followed by a [`scatter`], without an intermediate buffer.
+pub(crate) fn copy(
+    src: &[u8],
+    src_layout: &Layout,
+    src_indices: &[Vec<u64>],
+    dst: &mut [u8],
+    dst_layout: &Layout,
+    dst_indices: &[Vec<u64>],
+    element_size: usize,
+) -> PyResult<()> {
+    let mut src_runs = Runs::new(src_layout, src_indices, element_size);
+    let mut dst_runs = Runs::new(dst_layout, dst_indices, element_size);
+    let (mut src_run, mut dst_run) = (0..0, 0..0);
+    loop {
+        if src_run.is_empty() {
+            src_run = src_runs.next().unwrap_or_default();
+        }
+        if dst_run.is_empty() {
+            dst_run = dst_runs.next().unwrap_or_default();
+        }
+        // Runs are split where the runs of the source and the destination do not line up
+        let length = src_run.len().min(dst_run.len());
+        if length == 0 {
+            return if src_run.is_empty() && dst_run.is_empty() {
+                Ok(())
+            } else {
+                Err(PyErr::new::<PyValueError, _>(
+                    "the indexed selections do not have the same number of elements",
+                ))
+            };
+        }
+        dst.get_mut(dst_run.start..dst_run.start + length)
+            .zip(src.get(src_run.start..src_run.start + length))
+            .ok_or_else(out_of_bounds_err)
+            .map(|(dst, src)| dst.copy_from_slice(src))?;
+        src_run.start += length;
+        dst_run.start += length;
+    }
+}
+
+/// The byte offset of each point of a C order array.
+fn point_offsets<'a>(
+    array_shape: &'a [u64],
+    points: &'a [Vec<u64>],
+    origin: &'a [u64],
+    element_size: usize,
+) -> impl Iterator<Item = Option<usize>> + 'a {
+    points.iter().map(move |point| {
+        let mut offset = 0;
+        for ((&index, &origin), &length) in point.iter().zip(origin).zip(array_shape) {
+            let index = index.checked_sub(origin).filter(|&index| index < length)?;
+            offset = offset * length + index;
+        }
+        usize::try_from(offset)
+            .ok()
+            .map(|offset| offset * element_size)
+    })
+}
+
+/// Gather the elements at `points` (relative to `origin`) of a C order array into a contiguous buffer, in order.
+pub(crate) fn gather_points(
+    bytes: &[u8],
+    array_shape: &[u64],
+    points: &[Vec<u64>],
+    origin: &[u64],
+    element_size: usize,
+) -> PyResult<Vec<u8>> {
+    let mut gathered = Vec::with_capacity(points.len() * element_size);
+    for offset in point_offsets(array_shape, points, origin, element_size) {
+        let element = offset
+            .and_then(|offset| bytes.get(offset..offset + element_size))
+            .ok_or_else(out_of_bounds_err)?;
+        gathered.extend_from_slice(element);
+    }
+    Ok(gathered)
+}
+
+/// Scatter a contiguous buffer into the elements at `points` of a C order array, in order.
+pub(crate) fn scatter_points(
+    bytes: &mut [u8],
+    array_shape: &[u64],
+    points: &[Vec<u64>],
+    element_size: usize,
+    selected_bytes: &[u8],
+) -> PyResult<()> {
+    if selected_bytes.len() != points.len() * element_size {
+        return Err(PyErr::new::<PyValueError, _>(
+            "the number of selected bytes does not match the number of points",
+        ));
+    }
+    let origin = vec![0; array_shape.len()];
+    for (offset, selected) in point_offsets(array_shape, points, &origin, element_size)
+        .zip(selected_bytes.chunks_exact(element_size))
+    {
+        offset
+            .and_then(|offset| bytes.get_mut(offset..offset + element_size))
+            .ok_or_else(out_of_bounds_err)?
+            .copy_from_slice(selected);
+    }
+    Ok(())
+}
+
+/// The position of each element of an orthogonal selection along each dimension, in C order.
+pub(crate) fn cross_product(indices: &[Vec<u64>]) -> Vec<Vec<u64>> {
+    indices.iter().fold(vec![vec![]], |products, indices| {
+        products
+            .iter()
+            .flat_map(|product| {
+                indices.iter().map(move |&index| {
+                    let mut product = product.clone();
+                    product.push(index);
+                    product
+                })
+            })
+            .collect()
+    })
+}
+
+/// A stable ordering of `keys`, which is `None` if they are already in order.
+fn stable_order<T: Ord>(keys: &[T]) -> Option<Vec<usize>> {
+    if keys.windows(2).all(|pair| pair[0] <= pair[1]) {
+        return None;
+    }
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    Some(order)
+}
+
+/// Order an orthogonal selection of a chunk by the positions of its elements in the output, so that where elements are duplicated in the chunk, the last in the output is written last.
+///
+/// Returns the reordered indices of each dimension of the chunk and the reordered selected bytes (in C order of the reordered selection).
+/// `axis_pairs` are the corresponding dimensions of the chunk and the output, if known.
+/// Otherwise, dimensions of the chunk and the output selecting a single element are ignored, since they cannot duplicate elements (e.g. dropped axes).
+pub(crate) fn order_by_output(
+    chunk_indices: &[Vec<u64>],
+    output_indices: &[Vec<u64>],
+    axis_pairs: Option<Vec<(usize, usize)>>,
+    selected_bytes: &[u8],
+    element_size: usize,
+) -> PyResult<Option<(Vec<Vec<u64>>, Vec<u8>)>> {
+    let axis_pairs = match axis_pairs {
+        Some(axis_pairs) => axis_pairs,
+        None => {
+            let chunk_dims = (0..chunk_indices.len()).filter(|&i| chunk_indices[i].len() > 1);
+            let output_dims = (0..output_indices.len()).filter(|&i| output_indices[i].len() > 1);
+            if chunk_dims.clone().count() != output_dims.clone().count() {
+                return Ok(None);
+            }
+            chunk_dims.zip(output_dims).collect()
+        }
+    };
+
+    let mut positions: Vec<Vec<u64>> = chunk_indices
+        .iter()
+        .map(|indices| (0..indices.len() as u64).collect())
+        .collect();
+    let mut reordered = false;
+    for (chunk_dim, output_dim) in axis_pairs {
+        if let Some(order) = stable_order(&output_indices[output_dim]) {
+            positions[chunk_dim] = order.iter().map(|&i| i as u64).collect();
+            reordered = true;
+        }
+    }
+    if !reordered {
+        return Ok(None);
+    }
+
+    let selection_shape: Vec<u64> = chunk_indices
+        .iter()
+        .map(|indices| indices.len() as u64)
+        .collect();
+    let selected_bytes = gather(
+        selected_bytes,
+        &Layout::c_order(&selection_shape, element_size),
+        &positions,
+        element_size,
+    )?;
+    #[allow(clippy::cast_possible_truncation)]
+    let chunk_indices = chunk_indices
+        .iter()
+        .zip(&positions)
+        .map(|(indices, positions)| {
+            positions
+                .iter()
+                .map(|&position| indices[position as usize])
+                .collect()
+        })
+        .collect();
+    Ok(Some((chunk_indices, selected_bytes)))
+}
+
+/// Order the points of a pointwise selection of a chunk by their positions in the output, so that where points are duplicated, the last in the output is written last.
+///
+/// Returns the reordered points and selected bytes.
+pub(crate) fn order_points_by_output(
+    points: &[Vec<u64>],
+    output_indices: &[Vec<u64>],
+    selected_bytes: &[u8],
+    element_size: usize,
+) -> Option<(Vec<Vec<u64>>, Vec<u8>)> {
+    let output_positions = cross_product(output_indices);
+    if output_positions.len() != points.len() {
+        return None;
+    }
+    let order = stable_order(&output_positions)?;
+    let points = order.iter().map(|&i| points[i].clone()).collect();
+    let selected_bytes = order
+        .iter()
+        .flat_map(|&i| &selected_bytes[i * element_size..(i + 1) * element_size])
+        .copied()
+        .collect();
+    Some((points, selected_bytes))
+}
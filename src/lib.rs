@@ -2,39 +2,78 @@
 #![allow(clippy::module_name_repetitions)]
 
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use numpy::npyffi::PyArrayObject;
 use numpy::{PyArrayDescrMethods, PyUntypedArray, PyUntypedArrayMethods};
-use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::exceptions::{PyRuntimeError, PyTimeoutError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 use pyo3_stub_gen::define_stub_info_gatherer;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rayon_iter_concurrent_limit::iter_concurrent_limit;
 use unsafe_cell_slice::UnsafeCellSlice;
-use zarrs::array::codec::{ArrayToBytesCodecTraits, CodecOptions, CodecOptionsBuilder};
+use zarrs::array::codec::{
+    ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, CodecError, CodecOptions,
+    CodecOptionsBuilder,
+};
 use zarrs::array::{
-    copy_fill_value_into, update_array_bytes, ArrayBytes, ArraySize, CodecChain, FillValue,
+    copy_fill_value_into, update_array_bytes, ArrayBytes, ArraySize, BytesRepresentation,
+    CodecChain, DataType, FillValue,
 };
 use zarrs::array_subset::ArraySubset;
 use zarrs::metadata::v3::MetadataV3;
+use zarrs::storage::StorePrefix;
 
+mod blosc;
+mod cast;
+mod checksum;
+mod chunk_grid;
 mod chunk_item;
+mod codec_info;
 mod concurrency;
+mod data_type;
+mod experimental;
+mod fill_value;
+mod filters;
+mod indexing;
 mod metadata_v2;
+mod python_codec;
 mod runtime;
+mod squeeze;
 mod store;
 #[cfg(test)]
 mod tests;
 mod utils;
+mod verify;
+mod vlen;
+mod write_buffer;
+mod zstd_dictionary;
 
+use crate::blosc::BloscOptions;
+use crate::cast::ScaleOffset;
 use crate::chunk_item::ChunksItem;
-use crate::concurrency::ChunkConcurrentLimitAndCodecOptions;
+use crate::codec_info::CodecInfo;
+use crate::concurrency::{
+    ChunkConcurrentLimitAndCodecOptions, CodecOptionsOverrides, IoConcurrency,
+};
 use crate::metadata_v2::codec_metadata_v2_to_v3;
-use crate::store::StoreManager;
-use crate::utils::{PyErrExt as _, PyUntypedArrayExt as _};
+use crate::store::{
+    http_client, storage_err_to_py_err, storage_options_to_strings, CacheConfig, DiskCache,
+    FilesystemOptions, KeyTransform, MultipartConfig, OpendalStoreConfig, PerformanceMetrics,
+    RetryConfig, StorageTransformer, StorageTransformers, StoreConfig, StoreManager,
+    StoreManagerConfig, StoreStats, MULTIPART_PART_SIZE_MINIMUM,
+};
+use crate::utils::{py_to_json_metadata, PyErrExt as _, PyUntypedArrayExt as _};
+use crate::verify::{ChunkStatus, ChunkVerification};
+use crate::write_buffer::{BufferedChunk, WriteBuffer};
 
 // TODO: Use a OnceLock for store with get_or_try_init when stabilised?
 #[gen_stub_pyclass]
@@ -46,9 +85,80 @@ pub struct CodecPipelineImpl {
     pub(crate) chunk_concurrent_minimum: usize,
     pub(crate) chunk_concurrent_maximum: usize,
     pub(crate) num_threads: usize,
+    pub(crate) codec_concurrent_target: Option<usize>,
+    pub(crate) io_concurrency: Option<IoConcurrency>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) read_only: bool,
+    pub(crate) write_buffer: Option<WriteBuffer>,
+    pub(crate) scale_offset: Option<ScaleOffset>,
+    pub(crate) blosc: BloscOptions,
+    pub(crate) skip_write_checksums: bool,
+    pub(crate) experimental_codecs: bool,
 }
 
 impl CodecPipelineImpl {
+    /// Create a codec chain from the metadata of its codecs, or from the `filters` and `compressor` of a zarr V2 array.
+    ///
+    /// The metadata is a JSON `str`, or the `list` (or `dict`) it is serialized from.
+    /// The codecs are configured with the `blosc` overrides, and `crc32c` codecs write zero checksums if `skip_write_checksums` is true.
+    /// Experimental codecs are rejected unless `experimental_codecs` is true.
+    fn codec_chain_from_metadata(
+        metadata: &Bound<'_, PyAny>,
+        blosc: &BloscOptions,
+        skip_write_checksums: bool,
+        experimental_codecs: bool,
+    ) -> PyResult<CodecChain> {
+        let metadata: Vec<MetadataV3> = match py_to_json_metadata(metadata)? {
+            // The filters and compressor of a zarr V2 array
+            serde_json::Value::Object(metadata) => {
+                metadata_v2::array_metadata_v2_to_codecs(&metadata)?
+            }
+            metadata => serde_json::from_value(metadata).map_py_err::<PyTypeError>()?,
+        };
+        let mut metadata = blosc.apply(metadata)?;
+        if !experimental_codecs {
+            metadata = experimental::reject_experimental_codecs(metadata)?;
+        }
+        if skip_write_checksums {
+            metadata = checksum::skip_write_checksums(metadata)?;
+        }
+        CodecChain::from_metadata(&metadata).map_py_err::<PyTypeError>()
+    }
+
+    /// Fail with a `TimeoutError` if the deadline of a batched call has passed.
+    fn check_deadline(deadline: Option<Instant>) -> PyResult<()> {
+        if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            return Err(PyTimeoutError::new_err("codec pipeline call timed out"));
+        }
+        Ok(())
+    }
+
+    /// Convert a prefix to a store prefix, appending a trailing `/` if it is missing.
+    fn str_to_store_prefix(prefix: &str) -> PyResult<StorePrefix> {
+        let prefix = if prefix.is_empty() || prefix.ends_with('/') {
+            prefix.to_string()
+        } else {
+            format!("{prefix}/")
+        };
+        StorePrefix::new(prefix).map_py_err::<PyValueError>()
+    }
+
+    /// Run the store I/O of a batched call, on the I/O thread pool if store I/O is separated from codec work.
+    fn install_io<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        match &self.io_concurrency {
+            Some(io_concurrency) => io_concurrency.install_io(f),
+            None => f(),
+        }
+    }
+
+    /// Run the codec work of a chunk, on the codec thread pool if store I/O is separated from codec work.
+    fn install_codec<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        match &self.io_concurrency {
+            Some(io_concurrency) => io_concurrency.install_codec(f),
+            None => f(),
+        }
+    }
+
     fn retrieve_chunk_bytes<'a, I: ChunksItem>(
         &self,
         item: &I,
@@ -57,10 +167,24 @@ impl CodecPipelineImpl {
     ) -> PyResult<ArrayBytes<'a>> {
         let value_encoded = self.stores.get(item)?;
         let value_decoded = if let Some(value_encoded) = value_encoded {
-            let value_encoded: Vec<u8> = value_encoded.into(); // zero-copy in this case
-            codec_chain
-                .decode(value_encoded.into(), item.representation(), codec_options)
-                .map_py_err::<PyRuntimeError>()?
+            let representation = item.representation();
+            self.install_codec(|| match value_encoded.try_into_mut() {
+                // Bytes in a heap allocation of their own are decoded without a copy
+                Ok(value_encoded) => codec_chain.decode(
+                    Vec::from(value_encoded).into(),
+                    representation,
+                    codec_options,
+                ),
+                // Shared bytes (e.g. of a memory mapped file) are borrowed, so only decoded bytes that still borrow them are copied
+                Err(value_encoded) => codec_chain
+                    .decode(
+                        Cow::Borrowed(&value_encoded[..]),
+                        representation,
+                        codec_options,
+                    )
+                    .map(ArrayBytes::into_owned),
+            })
+            .map_py_err::<PyRuntimeError>()?
         } else {
             let array_size = ArraySize::new(
                 item.representation().data_type().size(),
@@ -85,13 +209,22 @@ impl CodecPipelineImpl {
             )
             .map_py_err::<PyValueError>()?;
 
-        if value_decoded.is_fill_value(item.representation().fill_value()) {
+        if !codec_options.store_empty_chunks()
+            && fill_value::is_fill_value(&value_decoded, item.representation())
+        {
             self.stores.erase(item)
         } else {
-            let value_encoded = codec_chain
-                .encode(value_decoded, item.representation(), codec_options)
-                .map(Cow::into_owned)
+            let representation = item.representation();
+            let bytes_decoded = value_decoded.size();
+            let value_encoded = self
+                .install_codec(|| {
+                    codec_chain
+                        .encode(value_decoded, representation, codec_options)
+                        .map(Cow::into_owned)
+                })
                 .map_py_err::<PyRuntimeError>()?;
+            self.stores
+                .record_encode(bytes_decoded, value_encoded.len());
 
             // Store the encoded chunk
             self.stores.set(item, value_encoded.into())
@@ -123,6 +256,38 @@ impl CodecPipelineImpl {
                 .validate(chunk_subset.num_elements(), data_type_size)
                 .map_py_err::<PyValueError>()?;
 
+            // Fast path if chunks are stored as is, only the bytes of the subset are written in place
+            if let (ArrayBytes::Fixed(subset_bytes), Some(element_size)) = (
+                &chunk_subset_bytes,
+                item.representation().data_type().fixed_size(),
+            ) {
+                let chunk_size = array_shape.iter().product::<u64>() * element_size as u64;
+                if Self::is_native_bytes_codec_chain(codec_chain)
+                    && self.stores.writes_partial_values(item)
+                    // A chunk the write may fill with the fill value is updated entirely, so it can be erased
+                    && (codec_options.store_empty_chunks()
+                        || !fill_value::is_fill_value(&chunk_subset_bytes, item.representation()))
+                    // A stored chunk of another size (e.g. truncated) is rewritten entirely
+                    && self.stores.size(item)? == Some(chunk_size)
+                {
+                    let byte_ranges = chunk_subset
+                        .byte_ranges(&array_shape, element_size)
+                        .map_py_err::<PyValueError>()?;
+                    let mut subset_offset = 0;
+                    let offset_values = byte_ranges
+                        .iter()
+                        .map(|byte_range| {
+                            let length = usize::try_from(byte_range.length(chunk_size))
+                                .map_py_err::<PyValueError>()?;
+                            let value = &subset_bytes[subset_offset..subset_offset + length];
+                            subset_offset += length;
+                            Ok((byte_range.start(chunk_size), value))
+                        })
+                        .collect::<PyResult<Vec<(u64, &[u8])>>>()?;
+                    return self.stores.set_partial_values(item, &offset_values);
+                }
+            }
+
             // Retrieve the chunk
             let chunk_bytes_old = self.retrieve_chunk_bytes(item, codec_chain, codec_options)?;
 
@@ -147,6 +312,445 @@ impl CodecPipelineImpl {
         }
     }
 
+    /// Whether a codec chain is just the `bytes` codec with native endianness, so the elements of chunks are stored as is.
+    fn is_native_bytes_codec_chain(codec_chain: &CodecChain) -> bool {
+        let native_endian = if cfg!(target_endian = "little") {
+            "little"
+        } else {
+            "big"
+        };
+        codec_chain.array_to_array_codecs().is_empty()
+            && codec_chain.bytes_to_bytes_codecs().is_empty()
+            && codec_chain
+                .array_to_bytes_codec()
+                .create_metadata()
+                .is_some_and(|metadata| {
+                    let endian = metadata
+                        .configuration()
+                        .and_then(|configuration| configuration.get("endian"))
+                        .and_then(serde_json::Value::as_str);
+                    metadata.name() == "bytes"
+                        && (endian.is_none() || endian == Some(native_endian))
+                })
+    }
+
+    /// Store the elements of a subset of a chunk with a partial encoder.
+    ///
+    /// For the `sharding_indexed` codec, only the inner chunks intersecting the subset and the shard index are encoded and written, rather than the whole shard.
+    fn partial_encode_chunk_subset_bytes(
+        &self,
+        item: &chunk_item::WithSubset,
+        chunk_subset_bytes: ArrayBytes,
+        codec_options: &CodecOptions,
+    ) -> PyResult<()> {
+        let array_shape = item.representation().shape_u64();
+        if !item.chunk_subset.inbounds(&array_shape) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "chunk subset ({}) is out of bounds for array shape ({array_shape:?})",
+                item.chunk_subset
+            )));
+        }
+        chunk_subset_bytes
+            .validate(
+                item.chunk_subset.num_elements(),
+                item.representation().data_type().size(),
+            )
+            .map_py_err::<PyValueError>()?;
+
+        let input_handle = Arc::new(self.stores.decoder(item)?);
+        self.stores.partial_encode(item, |output_handle| {
+            let partial_encoder = self
+                .codec_chain
+                .clone()
+                .partial_encoder(
+                    input_handle,
+                    Arc::new(output_handle),
+                    item.representation(),
+                    codec_options,
+                )
+                .map_py_err::<PyValueError>()?;
+            partial_encoder
+                .partial_encode(&[(&item.chunk_subset, chunk_subset_bytes)], codec_options)
+                .map_py_err::<PyRuntimeError>()
+        })
+    }
+
+    /// Scatter the selected elements of an item into the bytes of its chunk.
+    ///
+    /// Where the selection duplicates elements of the chunk, the element last in the output is written last, so it wins.
+    fn scatter_into_chunk(
+        item: &chunk_item::WithSubset,
+        chunk_bytes: &mut [u8],
+        element_size: usize,
+        selected_bytes: &[u8],
+    ) -> PyResult<()> {
+        let array_shape = item.representation().shape_u64();
+        let output_indices = item.subset_indices.as_ref().map(|_| {
+            indexing::selected_indices(
+                &item.subset,
+                item.subset_indices.as_deref(),
+                &vec![0; item.subset.dimensionality()],
+            )
+        });
+        if let Some(chunk_points) = &item.chunk_points {
+            let ordered = output_indices.and_then(|output_indices| {
+                indexing::order_points_by_output(
+                    chunk_points,
+                    &output_indices,
+                    selected_bytes,
+                    element_size,
+                )
+            });
+            let (chunk_points, selected_bytes) = match &ordered {
+                Some((chunk_points, selected_bytes)) => (chunk_points, selected_bytes.as_slice()),
+                None => (chunk_points, selected_bytes),
+            };
+            return indexing::scatter_points(
+                chunk_bytes,
+                &array_shape,
+                chunk_points,
+                element_size,
+                selected_bytes,
+            );
+        }
+
+        let chunk_indices = indexing::selected_indices(
+            &item.chunk_subset,
+            item.chunk_indices.as_deref(),
+            &vec![0; array_shape.len()],
+        );
+        let ordered = match (&item.chunk_indices, output_indices) {
+            (Some(_), Some(output_indices)) => indexing::order_by_output(
+                &chunk_indices,
+                &output_indices,
+                item.axis_pairs(),
+                selected_bytes,
+                element_size,
+            )?,
+            _ => None,
+        };
+        let (chunk_indices, selected_bytes) = match &ordered {
+            Some((chunk_indices, selected_bytes)) => (chunk_indices, selected_bytes.as_slice()),
+            None => (&chunk_indices, selected_bytes),
+        };
+        indexing::scatter(
+            chunk_bytes,
+            &indexing::Layout::c_order(&array_shape, element_size),
+            chunk_indices,
+            element_size,
+            selected_bytes,
+        )
+    }
+
+    /// Retrieve the bytes of a chunk to be updated, or the fill value if every element of the chunk is `overwritten`.
+    fn retrieve_chunk_bytes_to_update(
+        &self,
+        item: &chunk_item::WithSubset,
+        overwritten: bool,
+        codec_options: &CodecOptions,
+    ) -> PyResult<Vec<u8>> {
+        let chunk_bytes = if overwritten {
+            // No read is required
+            ArrayBytes::new_fill_value(
+                ArraySize::new(
+                    item.representation().data_type().size(),
+                    item.representation().num_elements(),
+                ),
+                item.representation().fill_value(),
+            )
+        } else {
+            self.retrieve_chunk_bytes(item, &self.codec_chain, codec_options)?
+        };
+        Ok(chunk_bytes
+            .into_fixed()
+            .map_py_err::<PyValueError>()?
+            .into_owned())
+    }
+
+    /// Update the decoded bytes of a chunk with the elements of several of its subsets in order.
+    ///
+    /// The chunk is retrieved if `chunk_bytes` is `None`, unless it is entirely overwritten.
+    /// Later subsets overwrite any elements they share with earlier subsets.
+    fn update_chunk_subsets_bytes(
+        &self,
+        items: &[chunk_item::WithSubset],
+        items_bytes: Vec<ArrayBytes>,
+        chunk_bytes: Option<Vec<u8>>,
+        codec_options: &CodecOptions,
+    ) -> PyResult<Vec<u8>> {
+        let Some(first) = items.first() else {
+            return Ok(chunk_bytes.unwrap_or_default());
+        };
+        let array_shape = first.representation().shape_u64();
+        let element_size = Self::fixed_element_size(first)?;
+
+        // Retrieve the chunk, unless it is entirely overwritten
+        let mut chunk_bytes = match chunk_bytes {
+            Some(chunk_bytes) => chunk_bytes,
+            None => self.retrieve_chunk_bytes_to_update(
+                first,
+                items.iter().any(chunk_item::WithSubset::covers_chunk),
+                codec_options,
+            )?,
+        };
+
+        // Update the chunk with each subset
+        for (item, chunk_subset_bytes) in items.iter().zip(items_bytes) {
+            if !item.chunk_subset.inbounds(&array_shape) {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "chunk subset ({}) is out of bounds for array shape ({array_shape:?})",
+                    item.chunk_subset
+                )));
+            }
+            let chunk_subset_bytes = chunk_subset_bytes
+                .into_fixed()
+                .map_py_err::<PyValueError>()?;
+            Self::scatter_into_chunk(item, &mut chunk_bytes, element_size, &chunk_subset_bytes)?;
+        }
+        Ok(chunk_bytes)
+    }
+
+    /// Store the elements of several subsets of the same chunk in order, with a single read and write of the chunk.
+    ///
+    /// Later subsets overwrite any elements they share with earlier subsets.
+    fn store_coalesced_chunk_subsets_bytes(
+        &self,
+        items: &[chunk_item::WithSubset],
+        items_bytes: Vec<ArrayBytes>,
+        codec_options: &CodecOptions,
+    ) -> PyResult<()> {
+        let Some(first) = items.first() else {
+            return Ok(());
+        };
+        let chunk_bytes =
+            self.update_chunk_subsets_bytes(items, items_bytes, None, codec_options)?;
+        self.store_chunk_bytes(
+            first,
+            &self.codec_chain,
+            ArrayBytes::new_flen(chunk_bytes),
+            codec_options,
+        )
+    }
+
+    /// Update the decoded bytes of a chunk in the write buffer with the elements of several of its subsets in order.
+    ///
+    /// Returns true if the write buffer should be flushed.
+    fn buffer_chunk_subsets_bytes(
+        &self,
+        write_buffer: &WriteBuffer,
+        items: &[chunk_item::WithSubset],
+        items_bytes: Vec<ArrayBytes>,
+        codec_options: &CodecOptions,
+    ) -> PyResult<bool> {
+        let Some(first) = items.first() else {
+            return Ok(false);
+        };
+        let chunk_bytes = self.update_chunk_subsets_bytes(
+            items,
+            items_bytes,
+            write_buffer.take(first)?,
+            codec_options,
+        )?;
+        write_buffer.insert(
+            first.item.clone(),
+            chunk_bytes,
+            codec_options.into_builder().build(),
+        )
+    }
+
+    /// Store the chunks in the write buffer, if it is enabled.
+    ///
+    /// Each chunk is stored with the codec options of the write that last updated it.
+    /// Chunks which fail to be stored are put back in the buffer, and the first error is returned.
+    fn flush_write_buffer(&self) -> PyResult<()> {
+        let Some(write_buffer) = &self.write_buffer else {
+            return Ok(());
+        };
+        let chunks = write_buffer.drain()?;
+        let items: Vec<chunk_item::Basic> = chunks.iter().map(|chunk| chunk.item.clone()).collect();
+        let Some((chunk_concurrent_limit, codec_options)) =
+            items.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(());
+        };
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+
+        let store_chunk = |chunk: BufferedChunk| {
+            let chunk_codec_options = chunk
+                .codec_options
+                .into_builder()
+                .concurrent_target(codec_options.concurrent_target())
+                .build();
+            match self.store_chunk_bytes(
+                &chunk.item,
+                &self.codec_chain,
+                ArrayBytes::new_flen(chunk.bytes.as_slice()),
+                &chunk_codec_options,
+            ) {
+                Ok(()) => Ok(()),
+                Err(err) => Err((chunk_item::chunk_err(err, chunk.item.key()), chunk)),
+            }
+        };
+        let results: Vec<Result<(), (PyErr, BufferedChunk)>> = self.install_io(|| {
+            iter_concurrent_limit!(chunk_concurrent_limit, chunks, map, store_chunk).collect()
+        });
+
+        let mut first_err = None;
+        let mut unstored = Vec::new();
+        for (err, chunk) in results.into_iter().filter_map(Result::err) {
+            first_err.get_or_insert(err);
+            unstored.push(chunk);
+        }
+        write_buffer.restore(unstored)?;
+        first_err.map_or(Ok(()), Err)
+    }
+
+    /// The size of an element of a chunk, which must have a fixed size data type.
+    fn fixed_element_size(item: &chunk_item::WithSubset) -> PyResult<usize> {
+        item.representation()
+            .data_type()
+            .fixed_size()
+            .ok_or_else(|| {
+                PyErr::new::<PyValueError, _>(
+                    "indexed selections are only supported for fixed size data types",
+                )
+            })
+    }
+
+    /// Retrieve the indexed elements of a chunk into the indexed elements of the output.
+    ///
+    /// The subset that bounds the selected elements is decoded, and the selected elements are copied to the output with `output_layout`.
+    fn retrieve_indexed_chunk_subset_into(
+        &self,
+        item: &chunk_item::WithSubset,
+        output_data_type: Option<&DataType>,
+        scale_offset: Option<ScaleOffset>,
+        output: &UnsafeCellSlice<u8>,
+        output_layout: &indexing::Layout,
+        codec_options: &CodecOptions,
+    ) -> PyResult<()> {
+        let element_size = Self::fixed_element_size(item)?;
+        let bounding_shape = item.chunk_subset.shape();
+        let mut bounding_bytes = vec![0; item.chunk_subset.num_elements_usize() * element_size];
+        let partial_decoder = self.stores.partial_decoder(item, |input_handle| {
+            self.codec_chain
+                .clone()
+                .partial_decoder(Arc::new(input_handle), item.representation(), codec_options)
+                .map_py_err::<PyValueError>()
+        })?;
+        unsafe {
+            // SAFETY:
+            // - bounding_bytes has the elements of item.chunk_subset of the item.representation data type,
+            // - the output subset spans bounding_shape.
+            partial_decoder.partial_decode_into(
+                &item.chunk_subset,
+                &UnsafeCellSlice::new(&mut bounding_bytes),
+                bounding_shape,
+                &ArraySubset::new_with_shape(bounding_shape.to_vec()),
+                codec_options,
+            )
+        }
+        .map_err(|err| match err {
+            CodecError::StorageError(err) => storage_err_to_py_err::<PyValueError>(err),
+            err => PyValueError::new_err(err.to_string()),
+        })?;
+
+        // The decoded elements are cast to the data type of the output if it differs from the data type of the chunk, and unpacked with the scale and offset
+        let (bounding_bytes, element_size) = if output_data_type.is_some() || scale_offset.is_some()
+        {
+            let data_type = item.representation().data_type();
+            let output_data_type = output_data_type.unwrap_or(data_type);
+            let output_bytes = match scale_offset {
+                Some(scale_offset) => {
+                    cast::unpack(&bounding_bytes, data_type, output_data_type, scale_offset)?
+                }
+                None => cast::cast(&bounding_bytes, data_type, output_data_type)?,
+            };
+            (
+                output_bytes,
+                output_data_type.fixed_size().unwrap_or(element_size),
+            )
+        } else {
+            (bounding_bytes, element_size)
+        };
+
+        let output_indices = indexing::selected_indices(
+            &item.subset,
+            item.subset_indices.as_deref(),
+            &vec![0; item.subset.dimensionality()],
+        );
+        let output = unsafe {
+            // SAFETY: chunks are written to disjoint elements of the output
+            output.get()
+        };
+        match &item.chunk_points {
+            Some(chunk_points) => indexing::scatter(
+                output,
+                output_layout,
+                &output_indices,
+                element_size,
+                &indexing::gather_points(
+                    &bounding_bytes,
+                    bounding_shape,
+                    chunk_points,
+                    item.chunk_subset.start(),
+                    element_size,
+                )?,
+            ),
+            None => indexing::copy(
+                &bounding_bytes,
+                &indexing::Layout::c_order(bounding_shape, element_size),
+                &indexing::selected_indices(
+                    &item.chunk_subset,
+                    item.chunk_indices.as_deref(),
+                    item.chunk_subset.start(),
+                ),
+                output,
+                output_layout,
+                &output_indices,
+                element_size,
+            ),
+        }
+    }
+
+    /// Store the indexed elements of a chunk, updating the rest of the chunk.
+    fn store_indexed_chunk_subset_bytes(
+        &self,
+        item: &chunk_item::WithSubset,
+        chunk_subset_bytes: ArrayBytes,
+        codec_options: &CodecOptions,
+    ) -> PyResult<()> {
+        let array_shape = item.representation().shape_u64();
+        if !item.chunk_subset.inbounds(&array_shape) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "chunk subset ({}) is out of bounds for array shape ({array_shape:?})",
+                item.chunk_subset
+            )));
+        }
+        let element_size = Self::fixed_element_size(item)?;
+        let chunk_subset_bytes = chunk_subset_bytes
+            .into_fixed()
+            .map_py_err::<PyValueError>()?;
+
+        // Retrieve the chunk, unless it is entirely overwritten
+        let mut chunk_bytes =
+            self.retrieve_chunk_bytes_to_update(item, item.covers_chunk(), codec_options)?;
+
+        // Update the chunk
+        Self::scatter_into_chunk(item, &mut chunk_bytes, element_size, &chunk_subset_bytes)?;
+
+        // Store the updated chunk
+        self.store_chunk_bytes(
+            item,
+            &self.codec_chain,
+            ArrayBytes::new_flen(chunk_bytes),
+            codec_options,
+        )
+    }
+
     fn py_untyped_array_to_array_object<'a>(
         value: &'a Bound<'_, PyUntypedArray>,
     ) -> &'a PyArrayObject {
@@ -161,10 +765,106 @@ impl CodecPipelineImpl {
         array_object
     }
 
+    /// Fail if the elements of an array cannot be copied as is, i.e. they are (or contain) Python objects or have a non-native byte order.
+    fn check_element_data_type(value: &Bound<'_, PyUntypedArray>) -> PyResult<()> {
+        if value.dtype().has_object() {
+            // The elements are pointers to Python objects, copying them would read or write garbage
+            return Err(PyErr::new::<PyTypeError, _>(format!(
+                "array data type {} holds Python objects, which cannot be copied to or from chunks: \
+                only fixed size (numeric, boolean, complex, datetime, structured, and void) data types are supported, \
+                arrays of `str` or `bytes` are read and written with `retrieve_chunks_vlen` and `store_chunks_vlen`",
+                value.dtype()
+            )));
+        }
+        if value.dtype().is_native_byteorder() == Some(false) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "array data type {} has a non-native byte order, convert it with `arr.astype(arr.dtype.newbyteorder(\"=\"))`",
+                value.dtype()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write whether the elements selected from a chunk are stored to the elements of a `bool` mask with the shape of the output.
+    fn write_mask(
+        item: &chunk_item::WithSubset,
+        stored: bool,
+        mask: &UnsafeCellSlice<u8>,
+        mask_layout: &indexing::Layout,
+    ) -> PyResult<()> {
+        let indices = indexing::selected_indices(
+            &item.subset,
+            item.subset_indices.as_deref(),
+            &vec![0; item.subset.dimensionality()],
+        );
+        let num_elements = indices.iter().map(Vec::len).product();
+        let mask = unsafe {
+            // SAFETY: chunks are written to disjoint elements of the mask
+            mask.get()
+        };
+        indexing::scatter(
+            mask,
+            mask_layout,
+            &indices,
+            1,
+            &vec![u8::from(stored); num_elements],
+        )
+    }
+
+    /// The data type named `astype` of the elements of an input or output array.
+    fn element_data_type(astype: &str, value: &Bound<'_, PyUntypedArray>) -> PyResult<DataType> {
+        let data_type = DataType::from_metadata(&chunk_item::data_type_metadata(astype)?)
+            .map_py_err::<PyValueError>()?;
+        if data_type.fixed_size() != Some(value.dtype().itemsize()) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "data type {astype} does not match the size of the elements of the array with data type {}",
+                value.dtype()
+            )));
+        }
+        Ok(data_type)
+    }
+
+    /// The layout of a C or F contiguous array, with elements of `element_size` bytes.
+    fn nparray_layout(
+        value: &Bound<'_, PyUntypedArray>,
+        element_size: usize,
+    ) -> PyResult<indexing::Layout> {
+        let shape: Vec<u64> = value.shape_zarr()?;
+        if value.is_c_contiguous() {
+            Ok(indexing::Layout::c_order(&shape, element_size))
+        } else if value.is_fortran_contiguous() {
+            Ok(indexing::Layout::f_order(&shape, element_size))
+        } else {
+            Err(PyErr::new::<PyValueError, _>(
+                "array must be a C or F contiguous array".to_string(),
+            ))
+        }
+    }
+
+    /// The bytes of the single value broadcast to every element of an array (e.g. a scalar or the output of `np.broadcast_to`), if any.
+    fn nparray_constant(value: &Bound<'_, PyUntypedArray>) -> Option<Vec<u8>> {
+        let broadcast = value
+            .shape()
+            .iter()
+            .zip(value.strides())
+            .all(|(&length, &stride)| length <= 1 || stride == 0);
+        if value.len() == 0 || !broadcast {
+            return None;
+        }
+        let array_object: &PyArrayObject = Self::py_untyped_array_to_array_object(value);
+        let array_data = array_object.data.cast::<u8>();
+        let element = unsafe {
+            // SAFETY: array_data is a valid pointer to the first element of the array
+            debug_assert!(!array_data.is_null());
+            std::slice::from_raw_parts(array_data, value.dtype().itemsize())
+        };
+        Some(element.to_vec())
+    }
+
     fn nparray_to_slice<'a>(value: &'a Bound<'_, PyUntypedArray>) -> Result<&'a [u8], PyErr> {
-        if !value.is_c_contiguous() {
+        if !value.is_contiguous() {
             return Err(PyErr::new::<PyValueError, _>(
-                "input array must be a C contiguous array".to_string(),
+                "input array must be a C or F contiguous array".to_string(),
             ));
         }
         let array_object: &PyArrayObject = Self::py_untyped_array_to_array_object(value);
@@ -178,23 +878,47 @@ impl CodecPipelineImpl {
         Ok(slice)
     }
 
+    /// The bytes spanned by an output array, and the layout of its elements within them.
+    ///
+    /// The output may be any strided view (e.g. a subset of a larger array), not only a contiguous array.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
     fn nparray_to_unsafe_cell_slice<'a>(
         value: &'a Bound<'_, PyUntypedArray>,
-    ) -> Result<UnsafeCellSlice<'a, u8>, PyErr> {
-        if !value.is_c_contiguous() {
-            return Err(PyErr::new::<PyValueError, _>(
-                "input array must be a C contiguous array".to_string(),
-            ));
-        }
+    ) -> Result<(UnsafeCellSlice<'a, u8>, indexing::Layout), PyErr> {
+        Self::check_element_data_type(value)?;
+        let element_size = value.dtype().itemsize();
         let array_object: &PyArrayObject = Self::py_untyped_array_to_array_object(value);
         let array_data = array_object.data.cast::<u8>();
-        let array_len = value.len() * value.dtype().itemsize();
+        debug_assert!(!array_data.is_null());
+        if value.is_c_contiguous() {
+            let array_len = value.len() * element_size;
+            let output = unsafe {
+                // SAFETY: array_data is a valid pointer to a u8 array of length array_len
+                std::slice::from_raw_parts_mut(array_data, array_len)
+            };
+            let layout = indexing::Layout::c_order(&value.shape_zarr()?, element_size);
+            return Ok((UnsafeCellSlice::new(output), layout));
+        }
+
+        // The elements of a strided view span the bytes from its lowest to its highest element, which precedes array_data with negative strides
+        let (mut start, mut end) = (0, element_size as isize);
+        for (&length, &stride) in value.shape().iter().zip(value.strides()) {
+            let extent = (length as isize - 1) * stride;
+            if extent < 0 {
+                start += extent;
+            } else {
+                end += extent;
+            }
+        }
+        if value.len() == 0 {
+            (start, end) = (0, 0);
+        }
         let output = unsafe {
-            // SAFETY: array_data is a valid pointer to a u8 array of length array_len
-            debug_assert!(!array_data.is_null());
-            std::slice::from_raw_parts_mut(array_data, array_len)
+            // SAFETY: the elements of the array are within the bytes from array_data + start to array_data + end
+            std::slice::from_raw_parts_mut(array_data.offset(start), (end - start) as usize)
         };
-        Ok(UnsafeCellSlice::new(output))
+        let layout = indexing::Layout::new(value.strides().to_vec(), (-start) as usize);
+        Ok((UnsafeCellSlice::new(output), layout))
     }
 }
 
@@ -209,20 +933,90 @@ impl CodecPipelineImpl {
         chunk_concurrent_minimum=None,
         chunk_concurrent_maximum=None,
         num_threads=None,
+        storage_options=None,
+        cache_max_bytes=None,
+        cache_max_entries=None,
+        storage_transformers=None,
+        memory_map=None,
+        direct_io=None,
+        retry_max_attempts=None,
+        retry_base_delay=None,
+        retry_jitter=None,
+        store_timeout=None,
+        timeout=None,
+        read_only=false,
+        http_options=None,
+        disk_cache_directory=None,
+        disk_cache_max_bytes=None,
+        key_transforms=None,
+        io_concurrent_limit=None,
+        multipart_part_size=None,
+        multipart_concurrency=None,
+        write_buffer_max_bytes=None,
+        scale_offset=None,
+        partial_encoding=None,
+        partial_decoder_cache_max_entries=None,
+        blosc_nthreads=None,
+        blosc_blocksize=None,
+        blosc_shuffle=None,
+        unsafe_skip_write_checksums=None,
+        codec_concurrent_target=None,
+        experimental_codecs=None,
     ))]
     #[new]
+    #[allow(clippy::too_many_arguments)]
     fn new(
-        metadata: &str,
+        metadata: &Bound<'_, PyAny>,
         validate_checksums: Option<bool>,
         store_empty_chunks: Option<bool>,
         chunk_concurrent_minimum: Option<usize>,
         chunk_concurrent_maximum: Option<usize>,
         num_threads: Option<usize>,
+        storage_options: Option<HashMap<String, Bound<'_, PyAny>>>,
+        cache_max_bytes: Option<usize>,
+        cache_max_entries: Option<usize>,
+        storage_transformers: Option<Vec<String>>,
+        memory_map: Option<bool>,
+        direct_io: Option<bool>,
+        retry_max_attempts: Option<usize>,
+        retry_base_delay: Option<f64>,
+        retry_jitter: Option<f64>,
+        store_timeout: Option<f64>,
+        timeout: Option<f64>,
+        read_only: bool,
+        http_options: Option<HashMap<String, Bound<'_, PyAny>>>,
+        disk_cache_directory: Option<PathBuf>,
+        disk_cache_max_bytes: Option<u64>,
+        key_transforms: Option<Vec<String>>,
+        io_concurrent_limit: Option<usize>,
+        multipart_part_size: Option<usize>,
+        multipart_concurrency: Option<usize>,
+        write_buffer_max_bytes: Option<usize>,
+        scale_offset: Option<(f64, f64)>,
+        partial_encoding: Option<bool>,
+        partial_decoder_cache_max_entries: Option<usize>,
+        blosc_nthreads: Option<usize>,
+        blosc_blocksize: Option<usize>,
+        blosc_shuffle: Option<String>,
+        unsafe_skip_write_checksums: Option<bool>,
+        codec_concurrent_target: Option<usize>,
+        experimental_codecs: Option<bool>,
     ) -> PyResult<Self> {
-        let metadata: Vec<MetadataV3> =
-            serde_json::from_str(metadata).map_py_err::<PyTypeError>()?;
-        let codec_chain =
-            Arc::new(CodecChain::from_metadata(&metadata).map_py_err::<PyTypeError>()?);
+        let blosc = BloscOptions::new(blosc_nthreads, blosc_blocksize, blosc_shuffle)?;
+        let skip_write_checksums = unsafe_skip_write_checksums.unwrap_or(false);
+        // Chunks written without checksums would fail the validation of reads (and read-modify-writes) of the pipeline
+        if skip_write_checksums && validate_checksums != Some(false) {
+            return Err(PyErr::new::<PyValueError, _>(
+                "unsafe_skip_write_checksums requires validate_checksums to be false",
+            ));
+        }
+        let experimental_codecs = experimental_codecs.unwrap_or(true);
+        let codec_chain = Arc::new(Self::codec_chain_from_metadata(
+            metadata,
+            &blosc,
+            skip_write_checksums,
+            experimental_codecs,
+        )?);
         let mut codec_options = CodecOptionsBuilder::new();
         if let Some(validate_checksums) = validate_checksums {
             codec_options = codec_options.validate_checksums(validate_checksums);
@@ -230,6 +1024,9 @@ impl CodecPipelineImpl {
         if let Some(store_empty_chunks) = store_empty_chunks {
             codec_options = codec_options.store_empty_chunks(store_empty_chunks);
         }
+        if let Some(partial_encoding) = partial_encoding {
+            codec_options = codec_options.experimental_partial_encoding(partial_encoding);
+        }
         let codec_options = codec_options.build();
 
         let chunk_concurrent_minimum = chunk_concurrent_minimum
@@ -237,26 +1034,290 @@ impl CodecPipelineImpl {
         let chunk_concurrent_maximum =
             chunk_concurrent_maximum.unwrap_or(rayon::current_num_threads());
         let num_threads = num_threads.unwrap_or(rayon::current_num_threads());
+        if codec_concurrent_target == Some(0) {
+            return Err(PyErr::new::<PyValueError, _>(
+                "codec_concurrent_target must be at least 1",
+            ));
+        }
+        let io_concurrency = io_concurrent_limit
+            .map(|io_concurrent_limit| IoConcurrency::new(io_concurrent_limit, num_threads))
+            .transpose()?;
+
+        let storage_options = storage_options
+            .as_ref()
+            .map(storage_options_to_strings)
+            .transpose()?
+            .unwrap_or_default();
+        // Storage options with a scheme select an OpenDAL store, otherwise they configure the store of each chunk
+        let (store_config_override, storage_options) = if storage_options.contains_key("scheme") {
+            let config = OpendalStoreConfig::new(storage_options)?;
+            (Some(StoreConfig::Opendal(config)), BTreeMap::new())
+        } else {
+            (None, storage_options)
+        };
+        let cache =
+            (cache_max_bytes.is_some() || cache_max_entries.is_some()).then_some(CacheConfig {
+                max_bytes: cache_max_bytes,
+                max_entries: cache_max_entries,
+            });
+        let store_timeout = store_timeout
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_py_err::<PyValueError>()?;
+        let timeout = timeout
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_py_err::<PyValueError>()?;
+        let retry = retry_max_attempts
+            .filter(|&max_attempts| max_attempts > 1)
+            .map(|max_attempts| -> PyResult<_> {
+                Ok(RetryConfig {
+                    max_attempts,
+                    base_delay: Duration::try_from_secs_f64(retry_base_delay.unwrap_or(0.1))
+                        .map_py_err::<PyValueError>()?,
+                    jitter: retry_jitter.unwrap_or(0.5),
+                })
+            })
+            .transpose()?;
+        let http_client = http_options.as_ref().map(http_client).transpose()?;
+        if multipart_concurrency.is_some() && multipart_part_size.is_none() {
+            return Err(PyErr::new::<PyValueError, _>(
+                "multipart_concurrency requires multipart_part_size",
+            ));
+        }
+        if multipart_part_size.is_some_and(|part_size| part_size < MULTIPART_PART_SIZE_MINIMUM) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "multipart_part_size must be at least {MULTIPART_PART_SIZE_MINIMUM} bytes (5 MiB)"
+            )));
+        }
+        let multipart = multipart_part_size.map(|part_size| MultipartConfig {
+            part_size,
+            concurrency: multipart_concurrency.unwrap_or(4),
+        });
+        let disk_cache = disk_cache_directory
+            .map(|directory| Arc::new(DiskCache::new(directory, disk_cache_max_bytes)));
+        let storage_transformers = storage_transformers
+            .unwrap_or_default()
+            .iter()
+            .map(|name| name.parse::<StorageTransformer>())
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let key_transforms = key_transforms
+            .unwrap_or_default()
+            .iter()
+            .map(|transform| transform.parse::<KeyTransform>())
+            .collect::<PyResult<Vec<_>>>()?;
 
         Ok(Self {
-            stores: StoreManager::default(),
+            stores: StoreManager::new(StoreManagerConfig {
+                store_config_override,
+                storage_options,
+                http_client,
+                multipart,
+                disk_cache,
+                key_transforms,
+                read_only,
+                timeout: store_timeout,
+                retry,
+                cache,
+                storage_transformers: StorageTransformers::new(storage_transformers),
+                filesystem: FilesystemOptions {
+                    memory_map: memory_map.unwrap_or(false),
+                    direct_io: direct_io.unwrap_or(false),
+                },
+                partial_decoder_cache_max_entries: partial_decoder_cache_max_entries
+                    .and_then(NonZeroUsize::new),
+            }),
             codec_chain,
             codec_options,
             chunk_concurrent_minimum,
             chunk_concurrent_maximum,
             num_threads,
+            codec_concurrent_target,
+            io_concurrency,
+            timeout,
+            read_only,
+            write_buffer: write_buffer_max_bytes.map(WriteBuffer::new),
+            scale_offset: scale_offset.map(ScaleOffset::new).transpose()?,
+            blosc,
+            skip_write_checksums,
+            experimental_codecs,
+        })
+    }
+
+    /// The codecs of the codec chain of the pipeline, in the order they are applied when encoding.
+    fn codecs(&self) -> PyResult<Vec<CodecInfo>> {
+        codec_info::codec_chain_info(&self.codec_chain)
+    }
+
+    /// Check that the codecs support the chunks of an array (e.g. its data type), otherwise raise a `TypeError` naming the codec which does not.
+    ///
+    /// Batched methods check their chunks the same way, this allows a caller to fall back to another codec pipeline for an array before reading or writing it.
+    fn validate(&self, chunk_description: chunk_item::Basic) -> PyResult<()> {
+        codec_info::validate_codec_chain(&self.codec_chain, chunk_description.representation())
+    }
+
+    /// The memory order of the elements of encoded chunks with `ndim` dimensions, as the dimensions from the slowest to the fastest varying (e.g. `[0, 1]` for C order, `[1, 0]` for F order).
+    ///
+    /// The dimensions are permuted by `transpose` codecs (including those within shards), decoded chunks are always in C order.
+    fn memory_order(&self, ndim: usize) -> PyResult<Vec<usize>> {
+        codec_info::codec_chain_memory_order(&self.codec_chain, ndim)
+    }
+
+    /// The size in bytes of an encoded chunk with shape `chunk_shape` and data type `dtype` (e.g. `float32` or `<f4`), or `None` if it is unbounded.
+    ///
+    /// The size is exact if the codecs encode chunks to a fixed size (e.g. without compression), otherwise it is an upper bound (e.g. the worst case of a compressor).
+    fn encoded_size_estimate(&self, chunk_shape: Vec<u64>, dtype: &str) -> PyResult<Option<u64>> {
+        let data_type = DataType::from_metadata(&chunk_item::data_type_metadata(dtype)?)
+            .map_py_err::<PyValueError>()?;
+        let fill_value = vec![0; data_type.fixed_size().unwrap_or(0)];
+        let representation =
+            chunk_item::get_chunk_representation(chunk_shape, data_type, fill_value)?;
+        match self
+            .codec_chain
+            .compute_encoded_size(&representation)
+            .map_py_err::<PyValueError>()?
+        {
+            BytesRepresentation::FixedSize(size) | BytesRepresentation::BoundedSize(size) => {
+                Ok(Some(size))
+            }
+            BytesRepresentation::UnboundedSize => Ok(None),
+        }
+    }
+
+    /// The store access counts of the pipeline, if the `performance_metrics` storage transformer is enabled.
+    fn performance_metrics(&self) -> Option<PerformanceMetrics> {
+        self.stores.performance_metrics()
+    }
+
+    /// The store I/O and encoding statistics of the pipeline since it was created or the statistics were reset.
+    fn stats(&self) -> StoreStats {
+        self.stores.stats()
+    }
+
+    /// Reset the store I/O statistics of the pipeline.
+    fn reset_stats(&self) {
+        self.stores.reset_stats();
+    }
+
+    /// List the keys of a zarr-python store through the store of the pipeline.
+    fn list(&self, py: Python, store: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+        let store_config: StoreConfig = store.extract()?;
+        let keys = py.allow_threads(|| {
+            self.flush_write_buffer()?;
+            self.stores.list(store_config)
+        })?;
+        Ok(keys.iter().map(ToString::to_string).collect())
+    }
+
+    /// List the keys under a prefix (e.g. `group/array/`) of a zarr-python store through the store of the pipeline.
+    fn list_prefix(
+        &self,
+        py: Python,
+        store: &Bound<'_, PyAny>,
+        prefix: &str,
+    ) -> PyResult<Vec<String>> {
+        let store_config: StoreConfig = store.extract()?;
+        let prefix = Self::str_to_store_prefix(prefix)?;
+        let keys = py.allow_threads(|| {
+            self.flush_write_buffer()?;
+            self.stores.list_prefix(store_config, &prefix)
+        })?;
+        Ok(keys.iter().map(ToString::to_string).collect())
+    }
+
+    /// List the keys and prefixes immediately under a prefix of a zarr-python store through the store of the pipeline.
+    fn list_dir(
+        &self,
+        py: Python,
+        store: &Bound<'_, PyAny>,
+        prefix: &str,
+    ) -> PyResult<(Vec<String>, Vec<String>)> {
+        let store_config: StoreConfig = store.extract()?;
+        let prefix = Self::str_to_store_prefix(prefix)?;
+        let keys_prefixes = py.allow_threads(|| {
+            self.flush_write_buffer()?;
+            self.stores.list_dir(store_config, &prefix)
+        })?;
+        Ok((
+            keys_prefixes
+                .keys()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            keys_prefixes
+                .prefixes()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        ))
+    }
+
+    /// Erase every key under a prefix (e.g. the chunks of an array, `group/array/c/`) of a zarr-python store, in parallel.
+    fn erase_prefix(&self, py: Python, store: &Bound<'_, PyAny>, prefix: &str) -> PyResult<()> {
+        if self.read_only {
+            return Err(PyValueError::new_err(
+                "cannot erase keys with a read-only codec pipeline",
+            ));
+        }
+        let store_config: StoreConfig = store.extract()?;
+        let prefix = Self::str_to_store_prefix(prefix)?;
+        py.allow_threads(|| {
+            self.flush_write_buffer()?;
+            self.install_io(|| self.stores.erase_prefix(store_config, &prefix))
         })
     }
 
+    /// Retrieve the selected elements of chunks into `value`.
+    ///
+    /// `astype` is the data type of the elements of `value` (e.g. `float32`) if it differs from the data type of the chunks, which are cast while they are copied into `value`.
+    /// `scale_offset` is the `(scale, offset)` of packed elements, which are unpacked to `element * scale + offset` (overriding the `scale_offset` of the pipeline).
+    /// `mask` is a `bool` array with the shape of `value`, whose selected elements are set to whether they are stored rather than the fill value of a missing chunk.
+    /// `validate_checksums` and `concurrent_target` override the codec options of the pipeline for this call.
+    #[pyo3(signature = (
+        chunk_descriptions,
+        value,
+        *,
+        astype=None,
+        scale_offset=None,
+        mask=None,
+        validate_checksums=None,
+        concurrent_target=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn retrieve_chunks_and_apply_index(
         &self,
         py: Python,
         chunk_descriptions: Vec<chunk_item::WithSubset>, // FIXME: Ref / iterable?
         value: &Bound<'_, PyUntypedArray>,
+        astype: Option<String>,
+        scale_offset: Option<(f64, f64)>,
+        mask: Option<&Bound<'_, PyUntypedArray>>,
+        validate_checksums: Option<bool>,
+        concurrent_target: Option<usize>,
     ) -> PyResult<()> {
         // Get input array
-        let output = Self::nparray_to_unsafe_cell_slice(value)?;
+        let (output, output_layout) = Self::nparray_to_unsafe_cell_slice(value)?;
+        let mask = mask
+            .map(|mask| -> PyResult<_> {
+                if mask.dtype().kind() != b'b' || mask.shape() != value.shape() {
+                    return Err(PyErr::new::<PyValueError, _>(format!(
+                        "mask must be a bool array with shape {:?}",
+                        value.shape()
+                    )));
+                }
+                Self::nparray_to_unsafe_cell_slice(mask)
+            })
+            .transpose()?;
+        let output_data_type = astype
+            .map(|astype| Self::element_data_type(&astype, value))
+            .transpose()?;
+        let scale_offset = scale_offset
+            .map(ScaleOffset::new)
+            .transpose()?
+            .or(self.scale_offset);
         let output_shape: Vec<u64> = value.shape_zarr()?;
+        let output_is_c_order = value.is_c_contiguous();
 
         // Adjust the concurrency based on the codec chain and the first chunk description
         let Some((chunk_concurrent_limit, codec_options)) =
@@ -264,33 +1325,76 @@ impl CodecPipelineImpl {
         else {
             return Ok(());
         };
+        let codec_options = CodecOptionsOverrides {
+            validate_checksums,
+            concurrent_target,
+            ..Default::default()
+        }
+        .apply(codec_options);
 
+        // With separate I/O and codec thread pools, the number of chunks in flight is limited by the I/O thread pool
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
         py.allow_threads(move || {
+            // Reads see the partial writes in the write buffer
+            self.flush_write_buffer()?;
+
             // FIXME: the `decode_into` methods only support fixed length data types.
             // For variable length data types, need a codepath with non `_into` methods.
             // Collect all the subsets and copy into value on the Python side?
-            let update_chunk_subset = |item: chunk_item::WithSubset| {
+            let retrieve_chunk_subset = |item: &chunk_item::WithSubset| {
+                Self::check_deadline(deadline)?;
+                if item.is_empty() {
+                    // Nothing is selected, so the chunk is not retrieved
+                    return Ok(());
+                }
+                if let Some((mask, mask_layout)) = &mask {
+                    Self::write_mask(item, self.stores.exists(item)?, mask, mask_layout)?;
+                }
+                // Outputs that are not C order (e.g. F order or strided views) are written through their strides
+                // Elements cast to another data type or unpacked are decoded into an intermediate buffer
+                let output_data_type = output_data_type
+                    .as_ref()
+                    .filter(|&data_type| data_type != item.representation().data_type());
+                if item.is_indexed()
+                    || !output_is_c_order
+                    || output_data_type.is_some()
+                    || scale_offset.is_some()
+                {
+                    return self.retrieve_indexed_chunk_subset_into(
+                        item,
+                        output_data_type,
+                        scale_offset,
+                        &output,
+                        &output_layout,
+                        &codec_options,
+                    );
+                }
                 // See zarrs::array::Array::retrieve_chunk_subset_into
                 if item.chunk_subset.start().iter().all(|&o| o == 0)
                     && item.chunk_subset.shape() == item.representation().shape_u64()
                 {
                     // See zarrs::array::Array::retrieve_chunk_into
-                    if let Some(chunk_encoded) = self.stores.get(&item)? {
+                    if let Some(chunk_encoded) = self.stores.get(item)? {
                         // Decode the encoded data into the output buffer
-                        let chunk_encoded: Vec<u8> = chunk_encoded.into();
-                        unsafe {
+                        let representation = item.representation();
+                        let subset = &item.subset;
+                        self.install_codec(|| unsafe {
                             // SAFETY:
                             // - output is an array with output_shape elements of the item.representation data type,
                             // - item.subset is within the bounds of output_shape.
                             self.codec_chain.decode_into(
-                                Cow::Owned(chunk_encoded),
-                                item.representation(),
+                                Cow::Borrowed(&chunk_encoded[..]),
+                                representation,
                                 &output,
                                 &output_shape,
-                                &item.subset,
+                                subset,
                                 &codec_options,
                             )
-                        }
+                        })
                     } else {
                         // The chunk is missing, write the fill value
                         unsafe {
@@ -308,12 +1412,17 @@ impl CodecPipelineImpl {
                         }
                     }
                 } else {
-                    let input_handle = Arc::new(self.stores.decoder(&item)?);
-                    let partial_decoder = self
-                        .codec_chain
-                        .clone()
-                        .partial_decoder(input_handle, item.representation(), &codec_options)
-                        .map_py_err::<PyValueError>()?;
+                    // Partial decoding interleaves store I/O and codec work, so it runs entirely on the I/O thread pool
+                    let partial_decoder = self.stores.partial_decoder(item, |input_handle| {
+                        self.codec_chain
+                            .clone()
+                            .partial_decoder(
+                                Arc::new(input_handle),
+                                item.representation(),
+                                &codec_options,
+                            )
+                            .map_py_err::<PyValueError>()
+                    })?;
                     unsafe {
                         // SAFETY:
                         // - output is an array with output_shape elements of the item.representation data type,
@@ -328,40 +1437,122 @@ impl CodecPipelineImpl {
                         )
                     }
                 }
-                .map_py_err::<PyValueError>()
+                .map_err(|err| match err {
+                    CodecError::StorageError(err) => storage_err_to_py_err::<PyValueError>(err),
+                    err => PyValueError::new_err(err.to_string()),
+                })
+            };
+            let update_chunk_subset = |item: chunk_item::WithSubset| {
+                retrieve_chunk_subset(&item).map_err(|err| chunk_item::chunk_err(err, item.key()))
             };
 
-            iter_concurrent_limit!(
-                chunk_concurrent_limit,
-                chunk_descriptions,
-                try_for_each,
-                update_chunk_subset
-            )?;
+            self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    chunk_descriptions,
+                    try_for_each,
+                    update_chunk_subset
+                )
+            })?;
 
             Ok(())
         })
     }
 
+    /// Store the selected elements of `value` to chunks.
+    ///
+    /// `astype` is the data type of the elements of `value` (e.g. `float32`) if it differs from the data type of the chunks, which are cast while they are copied from `value`.
+    /// `scale_offset` is the `(scale, offset)` of packed elements, which are packed to `(element - offset) / scale` (overriding the `scale_offset` of the pipeline).
+    /// `validate_checksums`, `store_empty_chunks`, and `concurrent_target` override the codec options of the pipeline for this call.
+    /// Chunks held in the write buffer are stored with the codec options of the pipeline when it is flushed.
+    #[pyo3(signature = (
+        chunk_descriptions,
+        value,
+        *,
+        astype=None,
+        scale_offset=None,
+        validate_checksums=None,
+        store_empty_chunks=None,
+        concurrent_target=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn store_chunks_with_indices(
         &self,
         py: Python,
         chunk_descriptions: Vec<chunk_item::WithSubset>,
         value: &Bound<'_, PyUntypedArray>,
+        astype: Option<String>,
+        scale_offset: Option<(f64, f64)>,
+        validate_checksums: Option<bool>,
+        store_empty_chunks: Option<bool>,
+        concurrent_target: Option<usize>,
     ) -> PyResult<()> {
         enum InputValue<'a> {
-            Array(ArrayBytes<'a>),
+            Array {
+                bytes: ArrayBytes<'a>,
+                slice: &'a [u8],
+                layout: indexing::Layout,
+            },
             Constant(FillValue),
         }
 
-        // Get input array
-        let input_slice = Self::nparray_to_slice(value)?;
-        let input = if value.ndim() > 0 {
-            // FIXME: Handle variable length data types, convert value to bytes and offsets
-            InputValue::Array(ArrayBytes::new_flen(Cow::Borrowed(input_slice)))
+        if self.read_only {
+            return Err(PyValueError::new_err(
+                "cannot store chunks with a read-only codec pipeline",
+            ));
+        }
+
+        // Get input array, or the constant broadcast to every selected element
+        Self::check_element_data_type(value)?;
+        let input_data_type = astype
+            .map(|astype| Self::element_data_type(&astype, value))
+            .transpose()?;
+        let scale_offset = scale_offset
+            .map(ScaleOffset::new)
+            .transpose()?
+            .or(self.scale_offset);
+        // Elements of another data type or packed elements are converted to the data type of the chunks up front
+        let chunk_data_type = chunk_descriptions
+            .first()
+            .map(|item| item.representation().data_type().clone());
+        let convert = |bytes: &[u8]| -> PyResult<Option<Vec<u8>>> {
+            let Some(data_type) = &chunk_data_type else {
+                return Ok(None);
+            };
+            let input_data_type = input_data_type.as_ref().unwrap_or(data_type);
+            match scale_offset {
+                Some(scale_offset) => {
+                    cast::pack(bytes, input_data_type, data_type, scale_offset).map(Some)
+                }
+                None if input_data_type != data_type => {
+                    cast::cast(bytes, input_data_type, data_type).map(Some)
+                }
+                None => Ok(None),
+            }
+        };
+        let converted_input: Vec<u8>;
+        let input = if let Some(constant) = Self::nparray_constant(value) {
+            InputValue::Constant(FillValue::new(convert(&constant)?.unwrap_or(constant)))
         } else {
-            InputValue::Constant(FillValue::new(input_slice.to_vec()))
+            let mut input_slice = Self::nparray_to_slice(value)?;
+            let mut element_size = value.dtype().itemsize();
+            if let Some(converted) = convert(input_slice)? {
+                element_size = chunk_data_type
+                    .as_ref()
+                    .and_then(DataType::fixed_size)
+                    .unwrap_or(element_size);
+                converted_input = converted;
+                input_slice = &converted_input;
+            }
+            // FIXME: Handle variable length data types, convert value to bytes and offsets
+            InputValue::Array {
+                bytes: ArrayBytes::new_flen(Cow::Borrowed(input_slice)),
+                slice: input_slice,
+                layout: Self::nparray_layout(value, element_size)?,
+            }
         };
         let input_shape: Vec<u64> = value.shape_zarr()?;
+        let input_is_c_order = value.is_c_contiguous();
 
         // Adjust the concurrency based on the codec chain and the first chunk description
         let Some((chunk_concurrent_limit, codec_options)) =
@@ -369,36 +1560,106 @@ impl CodecPipelineImpl {
         else {
             return Ok(());
         };
+        let codec_options = CodecOptionsOverrides {
+            validate_checksums,
+            store_empty_chunks,
+            concurrent_target,
+        }
+        .apply(codec_options);
 
+        // With separate I/O and codec thread pools, the number of chunks in flight is limited by the I/O thread pool
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
         py.allow_threads(move || {
-            let store_chunk = |item: chunk_item::WithSubset| match &input {
-                InputValue::Array(input) => {
-                    let chunk_subset_bytes = input
+            let item_bytes = |item: &chunk_item::WithSubset| {
+                Ok(match (&input, &item.subset_indices) {
+                    (InputValue::Array { slice, layout, .. }, subset_indices)
+                        if subset_indices.is_some() || !input_is_c_order =>
+                    {
+                        ArrayBytes::new_flen(indexing::gather(
+                            slice,
+                            layout,
+                            &indexing::selected_indices(
+                                &item.subset,
+                                subset_indices.as_deref(),
+                                &vec![0; item.subset.dimensionality()],
+                            ),
+                            Self::fixed_element_size(item)?,
+                        )?)
+                    }
+                    (InputValue::Array { bytes, .. }, _) => bytes
                         .extract_array_subset(
                             &item.subset,
                             &input_shape,
                             item.item.representation().data_type(),
                         )
-                        .map_py_err::<PyRuntimeError>()?;
-                    self.store_chunk_subset_bytes(
-                        &item,
-                        &self.codec_chain,
-                        chunk_subset_bytes,
-                        &item.chunk_subset,
-                        &codec_options,
-                    )
-                }
-                InputValue::Constant(constant_value) => {
-                    let chunk_subset_bytes = ArrayBytes::new_fill_value(
+                        .map_py_err::<PyRuntimeError>()?,
+                    (InputValue::Constant(constant_value), _) => ArrayBytes::new_fill_value(
                         ArraySize::new(
                             item.representation().data_type().size(),
-                            item.chunk_subset.num_elements(),
+                            item.chunk_selection_shape().iter().product(),
                         ),
                         constant_value,
-                    );
+                    ),
+                })
+            };
 
+            let flush = AtomicBool::new(false);
+            let store_chunk_subsets = |items: &[chunk_item::WithSubset]| {
+                Self::check_deadline(deadline)?;
+                if let (Some(write_buffer), [first, ..]) = (&self.write_buffer, items) {
+                    // Fixed size chunks are updated in the write buffer, and stored when it is flushed
+                    if first.representation().data_type().fixed_size().is_some() {
+                        let items_bytes =
+                            items.iter().map(item_bytes).collect::<PyResult<Vec<_>>>()?;
+                        if self.buffer_chunk_subsets_bytes(
+                            write_buffer,
+                            items,
+                            items_bytes,
+                            &codec_options,
+                        )? {
+                            flush.store(true, Ordering::Relaxed);
+                        }
+                        return Ok(());
+                    }
+                }
+                let item = match items {
+                    [] => return Ok(()),
+                    [item] => item,
+                    _ => {
+                        let items_bytes =
+                            items.iter().map(item_bytes).collect::<PyResult<Vec<_>>>()?;
+                        return self.store_coalesced_chunk_subsets_bytes(
+                            items,
+                            items_bytes,
+                            &codec_options,
+                        );
+                    }
+                };
+                if let InputValue::Constant(constant_value) = &input {
+                    // Fast path if the fill value is written to the entire chunk, no chunk is materialized
+                    if item.is_whole_chunk()
+                        && fill_value::element_is_fill_value(
+                            item.representation().data_type(),
+                            constant_value.as_ne_bytes(),
+                            item.representation().fill_value(),
+                        )
+                    {
+                        return self.stores.erase(item);
+                    }
+                }
+                let chunk_subset_bytes = item_bytes(item)?;
+                if item.chunk_indices.is_some() || item.chunk_points.is_some() {
+                    self.store_indexed_chunk_subset_bytes(item, chunk_subset_bytes, &codec_options)
+                } else if codec_options.experimental_partial_encoding() && !item.is_whole_chunk() {
+                    // Partial encoding interleaves store I/O and codec work, so it runs entirely on the I/O thread pool
+                    self.partial_encode_chunk_subset_bytes(item, chunk_subset_bytes, &codec_options)
+                } else {
                     self.store_chunk_subset_bytes(
-                        &item,
+                        item,
                         &self.codec_chain,
                         chunk_subset_bytes,
                         &item.chunk_subset,
@@ -406,17 +1667,510 @@ impl CodecPipelineImpl {
                     )
                 }
             };
+            let store_chunk = |items: Vec<chunk_item::WithSubset>| {
+                // Items selecting nothing leave the chunk untouched
+                let items: Vec<_> = items.into_iter().filter(|item| !item.is_empty()).collect();
+                let Some(first) = items.first() else {
+                    return Ok(());
+                };
+                store_chunk_subsets(&items).map_err(|err| chunk_item::chunk_err(err, first.key()))
+            };
+
+            // Items targeting the same chunk are stored together, so each chunk is read and written at most once
+            let mut chunk_groups: Vec<Vec<chunk_item::WithSubset>> = Vec::new();
+            let mut chunk_group_indices: BTreeMap<(StoreConfig, String), usize> = BTreeMap::new();
+            for item in chunk_descriptions {
+                let chunk = (item.store_config(), item.key().as_str().to_string());
+                match chunk_group_indices.get(&chunk) {
+                    Some(&index) => chunk_groups[index].push(item),
+                    None => {
+                        chunk_group_indices.insert(chunk, chunk_groups.len());
+                        chunk_groups.push(vec![item]);
+                    }
+                }
+            }
 
-            iter_concurrent_limit!(
-                chunk_concurrent_limit,
-                chunk_descriptions,
-                try_for_each,
-                store_chunk
-            )?;
+            self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    chunk_groups,
+                    try_for_each,
+                    store_chunk
+                )
+            })?;
 
+            if flush.load(Ordering::Relaxed) {
+                self.flush_write_buffer()?;
+            }
             Ok(())
         })
     }
+
+    /// Retrieve the encoded bytes of chunks as they are stored, without decoding them (e.g. to copy chunks without recompressing them).
+    ///
+    /// Missing chunks are `None`.
+    fn retrieve_chunks_encoded<'py>(
+        &self,
+        py: Python<'py>,
+        chunk_descriptions: Vec<chunk_item::Basic>,
+    ) -> PyResult<Vec<Option<Bound<'py, PyBytes>>>> {
+        let chunk_concurrent_limit = self.io_concurrency.as_ref().map_or(
+            self.chunk_concurrent_maximum,
+            IoConcurrency::io_concurrent_limit,
+        );
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        let chunks_encoded = py.allow_threads(|| {
+            // Reads see the partial writes in the write buffer
+            self.flush_write_buffer()?;
+            let retrieve_chunk = |item: &chunk_item::Basic| -> PyResult<_> {
+                Self::check_deadline(deadline)?;
+                self.stores
+                    .get(item)
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))
+            };
+            self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    &chunk_descriptions,
+                    map,
+                    retrieve_chunk
+                )
+                .collect::<PyResult<Vec<_>>>()
+            })
+        })?;
+
+        // Python objects are created with the GIL held
+        Ok(chunks_encoded
+            .into_iter()
+            .map(|bytes| bytes.map(|bytes| PyBytes::new(py, &bytes)))
+            .collect())
+    }
+
+    /// Retrieve every element of chunks with a variable length data type (e.g. `string`), in C order.
+    ///
+    /// Missing chunks are filled with the fill value.
+    fn retrieve_chunks_vlen(
+        &self,
+        py: Python,
+        chunk_descriptions: Vec<chunk_item::Basic>,
+    ) -> PyResult<Vec<Vec<PyObject>>> {
+        let Some((chunk_concurrent_limit, codec_options)) =
+            chunk_descriptions.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(vec![]);
+        };
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        let chunks_bytes = py.allow_threads(|| {
+            self.flush_write_buffer()?;
+            let retrieve_chunk = |item: &chunk_item::Basic| -> PyResult<_> {
+                Self::check_deadline(deadline)?;
+                let (bytes, offsets) = self
+                    .retrieve_chunk_bytes(item, &self.codec_chain, &codec_options)?
+                    .into_variable()
+                    .map_py_err::<PyValueError>()?;
+                Ok((bytes.into_owned(), offsets.into_owned()))
+            };
+            let retrieve_chunk = |item: &chunk_item::Basic| {
+                retrieve_chunk(item).map_err(|err| chunk_item::chunk_err(err, item.key()))
+            };
+            self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    &chunk_descriptions,
+                    map,
+                    retrieve_chunk
+                )
+                .collect::<PyResult<Vec<_>>>()
+            })
+        })?;
+
+        // Python objects are created with the GIL held
+        chunk_descriptions
+            .iter()
+            .zip(chunks_bytes)
+            .map(|(item, (bytes, offsets))| {
+                vlen::elements_to_py(py, item.representation().data_type(), &bytes, &offsets)
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))
+            })
+            .collect()
+    }
+
+    /// Store the encoded bytes of chunks as they are, without encoding them (e.g. chunks retrieved with `retrieve_chunks_encoded`).
+    ///
+    /// If `validate` is true, every chunk is decoded first and no chunk is stored if any of them cannot be decoded.
+    #[pyo3(signature = (chunks, *, validate=false))]
+    fn store_chunks_encoded(
+        &self,
+        py: Python,
+        chunks: Vec<(chunk_item::Basic, Bound<'_, PyBytes>)>,
+        validate: bool,
+    ) -> PyResult<()> {
+        if self.read_only {
+            return Err(PyValueError::new_err(
+                "cannot store chunks with a read-only codec pipeline",
+            ));
+        }
+
+        // Python objects are read with the GIL held
+        let chunks: Vec<(chunk_item::Basic, Vec<u8>)> = chunks
+            .into_iter()
+            .map(|(item, bytes)| (item, bytes.as_bytes().to_vec()))
+            .collect();
+
+        let items: Vec<chunk_item::Basic> = chunks.iter().map(|(item, _)| item.clone()).collect();
+        let Some((chunk_concurrent_limit, codec_options)) =
+            items.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(());
+        };
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        py.allow_threads(|| {
+            // Pending partial writes are stored first, so they do not overwrite the stored chunks
+            self.flush_write_buffer()?;
+            if validate {
+                let decode_chunk = |(item, bytes): &(chunk_item::Basic, Vec<u8>)| {
+                    self.install_codec(|| {
+                        self.codec_chain.decode(
+                            Cow::Borrowed(bytes.as_slice()),
+                            item.representation(),
+                            &codec_options,
+                        )
+                    })
+                    .map(|_| ())
+                    .map_py_err::<PyValueError>()
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))
+                };
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    &chunks,
+                    try_for_each,
+                    decode_chunk
+                )?;
+            }
+            let store_chunk = |(item, bytes): (chunk_item::Basic, Vec<u8>)| {
+                Self::check_deadline(deadline)?;
+                self.stores
+                    .set(&item, bytes.into())
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))
+            };
+            self.install_io(|| {
+                iter_concurrent_limit!(chunk_concurrent_limit, chunks, try_for_each, store_chunk)
+            })
+        })
+    }
+
+    /// Store every element of chunks with a variable length data type (e.g. `string`), in C order.
+    fn store_chunks_vlen(
+        &self,
+        py: Python,
+        chunks: Vec<(chunk_item::Basic, Vec<Bound<'_, PyAny>>)>,
+    ) -> PyResult<()> {
+        if self.read_only {
+            return Err(PyValueError::new_err(
+                "cannot store chunks with a read-only codec pipeline",
+            ));
+        }
+
+        // Python objects are read with the GIL held
+        let chunks = chunks
+            .into_iter()
+            .map(|(item, elements)| {
+                let elements = vlen::elements_from_py(item.representation().data_type(), &elements)
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))?;
+                Ok((item, elements))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let items: Vec<chunk_item::Basic> = chunks.iter().map(|(item, _)| item.clone()).collect();
+        let Some((chunk_concurrent_limit, codec_options)) =
+            items.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(());
+        };
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        py.allow_threads(|| {
+            let store_chunk =
+                |(item, (bytes, offsets)): (chunk_item::Basic, (Vec<u8>, Vec<usize>))| {
+                    Self::check_deadline(deadline)?;
+                    self.store_chunk_bytes(
+                        &item,
+                        &self.codec_chain,
+                        ArrayBytes::new_vlen(bytes, offsets),
+                        &codec_options,
+                    )
+                    .map_err(|err| chunk_item::chunk_err(err, item.key()))
+                };
+            self.install_io(|| {
+                iter_concurrent_limit!(chunk_concurrent_limit, chunks, try_for_each, store_chunk)
+            })
+        })
+    }
+
+    /// Decode every chunk with checksum validation, and report the chunks which are missing, corrupt (with an invalid checksum), or cannot be retrieved or decoded, rather than raising on the first failure.
+    fn verify_chunks(
+        &self,
+        py: Python,
+        chunk_descriptions: Vec<chunk_item::Basic>,
+    ) -> PyResult<ChunkVerification> {
+        let Some((chunk_concurrent_limit, codec_options)) =
+            chunk_descriptions.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(ChunkVerification::default());
+        };
+        let codec_options = CodecOptionsOverrides {
+            validate_checksums: Some(true),
+            ..Default::default()
+        }
+        .apply(codec_options);
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+
+        let statuses = py.allow_threads(|| {
+            // Pending partial writes are stored first, so they are verified
+            self.flush_write_buffer()?;
+            let verify_chunk = |item: &chunk_item::Basic| match self.stores.get(item) {
+                Ok(Some(value_encoded)) => ChunkStatus::from(self.install_codec(|| {
+                    self.codec_chain
+                        .decode(
+                            Cow::Borrowed(&value_encoded[..]),
+                            item.representation(),
+                            &codec_options,
+                        )
+                        .map(|_| ())
+                })),
+                Ok(None) => ChunkStatus::Missing,
+                Err(err) => ChunkStatus::Undecodable(err.to_string()),
+            };
+            Ok::<_, PyErr>(self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    &chunk_descriptions,
+                    map,
+                    verify_chunk
+                )
+                .collect::<Vec<_>>()
+            }))
+        })?;
+        Ok(chunk_descriptions
+            .iter()
+            .map(|item| item.key().as_str().to_string())
+            .zip(statuses)
+            .collect())
+    }
+
+    /// Decode chunks with the codecs of the pipeline and encode them with the codecs of `dst_codec_metadata` (e.g. to change the compression of an array).
+    ///
+    /// `dst_codec_metadata` is the metadata of the codecs, like the metadata of the pipeline.
+    /// Chunks are transcoded in place, or to `dst_descriptions` (e.g. the chunks of another array with the same chunk shape and data type) if it is given.
+    /// Chunks are transcoded in parallel and independently, so only the chunks in flight are held in memory.
+    /// The array metadata is not updated, so the array must be reopened with the new codecs once its chunks are transcoded in place.
+    #[pyo3(signature = (src_descriptions, dst_codec_metadata, *, dst_descriptions=None))]
+    fn transcode(
+        &self,
+        py: Python,
+        src_descriptions: Vec<chunk_item::Basic>,
+        dst_codec_metadata: &Bound<'_, PyAny>,
+        dst_descriptions: Option<Vec<chunk_item::Basic>>,
+    ) -> PyResult<()> {
+        if self.read_only {
+            return Err(PyValueError::new_err(
+                "cannot transcode chunks with a read-only codec pipeline",
+            ));
+        }
+        let dst_codec_chain = Self::codec_chain_from_metadata(
+            dst_codec_metadata,
+            &self.blosc,
+            self.skip_write_checksums,
+            self.experimental_codecs,
+        )?;
+        let dst_descriptions = dst_descriptions.unwrap_or_else(|| src_descriptions.clone());
+        if dst_descriptions.len() != src_descriptions.len() {
+            return Err(PyValueError::new_err(format!(
+                "the number of destination chunks ({}) does not match the number of source chunks ({})",
+                dst_descriptions.len(),
+                src_descriptions.len()
+            )));
+        }
+
+        let Some((chunk_concurrent_limit, codec_options)) =
+            src_descriptions.get_chunk_concurrent_limit_and_codec_options(self)?
+        else {
+            return Ok(());
+        };
+        let chunk_concurrent_limit = self
+            .io_concurrency
+            .as_ref()
+            .map_or(chunk_concurrent_limit, IoConcurrency::io_concurrent_limit);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        py.allow_threads(|| {
+            // Pending partial writes are stored first, so they are transcoded
+            self.flush_write_buffer()?;
+            let transcode_chunk = |(src, dst): (&chunk_item::Basic, &chunk_item::Basic)| {
+                Self::check_deadline(deadline)?;
+                let Some(value_encoded) = self.stores.get(src)? else {
+                    // Missing chunks stay missing
+                    return self.stores.erase(dst);
+                };
+                let value_decoded = self
+                    .install_codec(|| {
+                        self.codec_chain.decode(
+                            Cow::Borrowed(&value_encoded[..]),
+                            src.representation(),
+                            &codec_options,
+                        )
+                    })
+                    .map_py_err::<PyRuntimeError>()?;
+                self.store_chunk_bytes(dst, &dst_codec_chain, value_decoded, &codec_options)
+            };
+            let transcode_chunk = |(src, dst): (&chunk_item::Basic, &chunk_item::Basic)| {
+                transcode_chunk((src, dst)).map_err(|err| chunk_item::chunk_err(err, src.key()))
+            };
+            self.install_io(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    src_descriptions
+                        .iter()
+                        .zip(&dst_descriptions)
+                        .collect::<Vec<_>>(),
+                    try_for_each,
+                    transcode_chunk
+                )
+            })
+        })
+    }
+
+    /// Store the chunks with partial writes pending in the write buffer, if it is enabled.
+    fn flush(&self, py: Python) -> PyResult<()> {
+        py.allow_threads(|| self.flush_write_buffer())
+    }
+
+    /// Retrieve an orthogonal selection of an array with a regular chunk grid into `value`.
+    ///
+    /// The selection of each chunk is computed from the selection of the array in Rust, rather than being passed from zarr-python chunk by chunk.
+    /// `value` keeps a dimension for each dimension of the array, including those selected with an integer.
+    #[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+    fn retrieve_selection(
+        &self,
+        py: Python,
+        store: &Bound<'_, PyAny>,
+        chunk_key_encoding: (String, String),
+        chunk_spec: &Bound<'_, PyAny>,
+        array_shape: Vec<u64>,
+        selection: Vec<Bound<'_, PyAny>>,
+        value: &Bound<'_, PyUntypedArray>,
+    ) -> PyResult<()> {
+        let chunk_descriptions = Self::selection_to_chunk_descriptions(
+            store,
+            &chunk_key_encoding,
+            chunk_spec,
+            &array_shape,
+            &selection,
+            value,
+        )?;
+        self.retrieve_chunks_and_apply_index(
+            py,
+            chunk_descriptions,
+            value,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Store `value` to an orthogonal selection of an array with a regular chunk grid.
+    ///
+    /// See [`CodecPipelineImpl::retrieve_selection`].
+    #[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+    fn store_selection(
+        &self,
+        py: Python,
+        store: &Bound<'_, PyAny>,
+        chunk_key_encoding: (String, String),
+        chunk_spec: &Bound<'_, PyAny>,
+        array_shape: Vec<u64>,
+        selection: Vec<Bound<'_, PyAny>>,
+        value: &Bound<'_, PyUntypedArray>,
+    ) -> PyResult<()> {
+        let chunk_descriptions = Self::selection_to_chunk_descriptions(
+            store,
+            &chunk_key_encoding,
+            chunk_spec,
+            &array_shape,
+            &selection,
+            value,
+        )?;
+        self.store_chunks_with_indices(py, chunk_descriptions, value, None, None, None, None, None)
+    }
+}
+
+impl CodecPipelineImpl {
+    /// Decompose an orthogonal selection of an array into the chunk descriptions of the chunks it intersects.
+    fn selection_to_chunk_descriptions(
+        store: &Bound<'_, PyAny>,
+        (chunk_key_prefix, chunk_key_separator): &(String, String),
+        chunk_spec: &Bound<'_, PyAny>,
+        array_shape: &[u64],
+        selection: &[Bound<'_, PyAny>],
+        value: &Bound<'_, PyUntypedArray>,
+    ) -> PyResult<Vec<chunk_item::WithSubset>> {
+        let (chunk_descriptions, output_shape) = chunk_grid::chunk_selections(
+            &store.extract()?,
+            &chunk_grid::ChunkKeyEncoding {
+                prefix: chunk_key_prefix,
+                separator: chunk_key_separator,
+            },
+            &chunk_item::chunk_spec_to_representation(chunk_spec, None)?,
+            array_shape,
+            selection,
+        )?;
+        let value_shape: Vec<u64> = value.shape_zarr()?;
+        if value_shape != output_shape {
+            return Err(PyValueError::new_err(format!(
+                "value shape {value_shape:?} does not match the shape of the selection {output_shape:?}"
+            )));
+        }
+        Ok(chunk_descriptions)
+    }
+}
+
+impl Drop for CodecPipelineImpl {
+    /// Store the chunks with partial writes pending in the write buffer, so they are not lost if the pipeline is not flushed.
+    ///
+    /// A failure to store them cannot be raised, so it is reported like an exception in `__del__`.
+    fn drop(&mut self) {
+        let Some(write_buffer) = &self.write_buffer else {
+            return;
+        };
+        if matches!(write_buffer.is_empty(), Ok(true)) {
+            return;
+        }
+        Python::with_gil(|py| {
+            if let Err(err) = py.allow_threads(|| self.flush_write_buffer()) {
+                err.write_unraisable(py, None);
+            }
+        });
+    }
 }
 
 /// A Python module implemented in Rust.
@@ -426,7 +2180,15 @@ fn _internal(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CodecPipelineImpl>()?;
     m.add_class::<chunk_item::Basic>()?;
     m.add_class::<chunk_item::WithSubset>()?;
+    m.add_class::<CodecInfo>()?;
+    m.add_class::<ChunkVerification>()?;
+    m.add_class::<PerformanceMetrics>()?;
+    m.add_class::<StoreStats>()?;
     m.add_function(wrap_pyfunction!(codec_metadata_v2_to_v3, m)?)?;
+    m.add_function(wrap_pyfunction!(data_type::register_data_type, m)?)?;
+    m.add_function(wrap_pyfunction!(experimental::experimental_codecs, m)?)?;
+    m.add_function(wrap_pyfunction!(python_codec::register_codec, m)?)?;
+    m.add_function(wrap_pyfunction!(zstd_dictionary::train_zstd_dictionary, m)?)?;
     Ok(())
 }
 
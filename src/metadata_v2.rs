@@ -1,52 +1,144 @@
-use pyo3::{exceptions::PyRuntimeError, pyfunction, PyErr, PyResult};
-use zarrs::metadata::{
-    v2::{array::ArrayMetadataV2Order, MetadataV2},
-    v3::array::data_type::DataTypeMetadataV3,
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    pyfunction, PyErr, PyResult,
 };
+use serde_json::{Map, Value};
+use zarrs::{
+    array::Endianness,
+    metadata::{
+        v2::{
+            array::{
+                data_type_metadata_v2_to_endianness, ArrayMetadataV2Order, DataTypeMetadataV2,
+            },
+            MetadataV2,
+        },
+        v2_to_v3::data_type_metadata_v2_to_v3_data_type,
+        v3::{array::data_type::DataTypeMetadataV3, MetadataV3},
+    },
+};
+
+use crate::utils::PyErrExt;
+
+/// Whether a data type is a zarr V2 (numpy) data type string with a byte order character, e.g. `<f8`, `|u1`, or `>i4`.
+pub(crate) fn is_data_type_v2(dtype: &str) -> bool {
+    dtype.starts_with(['<', '>', '|'])
+}
+
+/// Convert a zarr V2 data type string (e.g. `<f8`, `|u1`, or `>i4`) to a zarr V3 data type, and the endianness of its elements if it has multibyte elements.
+pub(crate) fn data_type_v2_to_v3(
+    dtype: &str,
+) -> PyResult<(DataTypeMetadataV3, Option<Endianness>)> {
+    let data_type = DataTypeMetadataV2::Simple(dtype.to_string());
+    let endianness =
+        data_type_metadata_v2_to_endianness(&data_type).map_py_err::<PyValueError>()?;
+    let data_type =
+        data_type_metadata_v2_to_v3_data_type(&data_type).map_py_err::<PyValueError>()?;
+    Ok((data_type, endianness))
+}
+
+fn parse_metadata_v2(metadata: &str) -> PyResult<MetadataV2> {
+    serde_json::from_str::<MetadataV2>(metadata)
+        .map_err(|err| PyErr::new::<PyRuntimeError, _>(err.to_string()))
+}
+
+/// Convert the filters and compressor of a zarr V2 array to zarr V3 codecs.
+///
+/// The zarr V2 data type string `dtype` determines the endianness of encoded elements (otherwise they are little endian),
+/// and the chunks of F `order` arrays with `dimensionality` dimensions are transposed.
+fn codecs_v2_to_v3(
+    filters: Option<Vec<MetadataV2>>,
+    compressor: Option<MetadataV2>,
+    dtype: Option<&str>,
+    order: ArrayMetadataV2Order,
+    dimensionality: usize,
+) -> PyResult<Vec<MetadataV3>> {
+    let (data_type, endianness) = dtype
+        .map(data_type_v2_to_v3)
+        .transpose()?
+        .unwrap_or((DataTypeMetadataV3::Bool, None));
+
+    zarrs::metadata::v2_to_v3::codec_metadata_v2_to_v3(
+        order,
+        dimensionality,
+        &data_type,
+        endianness,
+        &filters,
+        &compressor,
+    )
+    .map_err(|err| {
+        // TODO: More informative error messages from zarrs for ArrayMetadataV2ToV3ConversionError
+        PyErr::new::<PyRuntimeError, _>(err.to_string())
+    })
+}
+
+/// Convert the codecs of zarr V2 array metadata, a JSON object with the `filters` and `compressor` of the array and optionally its `dtype`, `order`, and `shape`, to zarr V3 codecs.
+///
+/// See [`codecs_v2_to_v3`], the `shape` is required for F order arrays.
+pub(crate) fn array_metadata_v2_to_codecs(
+    metadata: &Map<String, Value>,
+) -> PyResult<Vec<MetadataV3>> {
+    let field = |name: &str| metadata.get(name).filter(|value| !value.is_null());
+    let invalid = |name: &str| {
+        PyErr::new::<PyValueError, _>(format!("invalid zarr V2 array metadata {name}"))
+    };
+    let filters = field("filters")
+        .map(|filters| serde_json::from_value::<Vec<MetadataV2>>(filters.clone()))
+        .transpose()
+        .map_err(|_| invalid("filters"))?;
+    let compressor = field("compressor")
+        .map(|compressor| serde_json::from_value::<MetadataV2>(compressor.clone()))
+        .transpose()
+        .map_err(|_| invalid("compressor"))?;
+    let dtype = field("dtype")
+        .map(|dtype| dtype.as_str().ok_or_else(|| invalid("dtype")))
+        .transpose()?;
+    let order = match field("order").map(Value::as_str) {
+        None | Some(Some("C")) => ArrayMetadataV2Order::C,
+        Some(Some("F")) => ArrayMetadataV2Order::F,
+        Some(_) => return Err(invalid("order")),
+    };
+    let dimensionality = match field("shape") {
+        Some(Value::Array(shape)) => shape.len(),
+        Some(_) => return Err(invalid("shape")),
+        None if matches!(order, ArrayMetadataV2Order::F) => {
+            return Err(PyErr::new::<PyValueError, _>(
+                "zarr V2 array metadata with F order requires the shape",
+            ))
+        }
+        None => 0, // unused with C order
+    };
+    codecs_v2_to_v3(filters, compressor, dtype, order, dimensionality)
+}
 
+/// Convert the filters and compressor of a zarr V2 array to zarr V3 codecs.
+///
+/// `dtype` is the zarr V2 data type string of the array (e.g. `>i4`), which determines the endianness of encoded elements, otherwise they are little endian.
 #[pyfunction]
-#[pyo3(signature = (filters=None, compressor=None))]
+#[pyo3(signature = (filters=None, compressor=None, dtype=None))]
 pub fn codec_metadata_v2_to_v3(
     filters: Option<Vec<String>>,
     compressor: Option<String>,
+    dtype: Option<String>,
 ) -> PyResult<Vec<String>> {
     // Try and convert filters/compressor to V2 metadata
-    let filters = if let Some(filters) = filters {
-        Some(
+    let filters = filters
+        .map(|filters| {
             filters
-                .into_iter()
-                .map(|filter| {
-                    serde_json::from_str::<MetadataV2>(&filter)
-                        .map_err(|err| PyErr::new::<PyRuntimeError, _>(err.to_string()))
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        )
-    } else {
-        None
-    };
-    let compressor = if let Some(compressor) = compressor {
-        Some(
-            serde_json::from_str::<MetadataV2>(&compressor)
-                .map_err(|err| PyErr::new::<PyRuntimeError, _>(err.to_string()))?,
-        )
-    } else {
-        None
-    };
+                .iter()
+                .map(|filter| parse_metadata_v2(filter))
+                .collect::<PyResult<Vec<_>>>()
+        })
+        .transpose()?;
+    let compressor = compressor.as_deref().map(parse_metadata_v2).transpose()?;
 
-    // FIXME: The array order, dimensionality, data type, and endianness are needed to exhaustively support all Zarr V2 data that zarrs can handle.
-    // However, CodecPipeline.from_codecs does not supply this information, and CodecPipeline.evolve_from_array_spec is seemingly never called.
-    let metadata = zarrs::metadata::v2_to_v3::codec_metadata_v2_to_v3(
+    // The array order and dimensionality are not known, F order arrays are converted by `array_metadata_v2_to_codecs`
+    let metadata = codecs_v2_to_v3(
+        filters,
+        compressor,
+        dtype.as_deref(),
         ArrayMetadataV2Order::C,
-        0,                         // unused with C order
-        &DataTypeMetadataV3::Bool, // FIXME
-        None,
-        &filters,
-        &compressor,
-    )
-    .map_err(|err| {
-        // TODO: More informative error messages from zarrs for ArrayMetadataV2ToV3ConversionError
-        PyErr::new::<PyRuntimeError, _>(err.to_string())
-    })?;
+        0, // unused with C order
+    )?;
     Ok(metadata
         .into_iter()
         .map(|metadata| serde_json::to_string(&metadata).expect("infallible")) // TODO: Add method to zarrs
@@ -0,0 +1,181 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    pyfunction,
+    types::{PyAnyMethods, PyBytes, PyBytesMethods, PyModule},
+    Py, PyAny, PyResult, Python,
+};
+use zarrs::{
+    array::{
+        codec::{
+            BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes, RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+use crate::utils::PyErrExt;
+
+/// The callables of a bytes to bytes codec registered from Python.
+struct PythonCodecFunctions {
+    encode: Py<PyAny>,
+    decode: Py<PyAny>,
+}
+
+/// The identifier of the codec plugin of the codecs registered from Python.
+const PYTHON_CODEC_PLUGIN: &str = "python";
+
+static PYTHON_CODECS: OnceLock<RwLock<HashMap<String, Arc<PythonCodecFunctions>>>> =
+    OnceLock::new();
+
+fn python_codecs() -> &'static RwLock<HashMap<String, Arc<PythonCodecFunctions>>> {
+    PYTHON_CODECS.get_or_init(RwLock::default)
+}
+
+/// Register a bytes to bytes codec (e.g. a compressor) named `name`, which is implemented by Python callables.
+///
+/// `encode(data, configuration)` and `decode(data, configuration)` are called with the `bytes` of a chunk and the `configuration` of the codec in the array metadata (a `dict`, or `None`), and return `bytes`.
+/// The GIL is only held while they run, so the other codecs of the pipeline are still applied to chunks in parallel.
+/// Registering a name again replaces its codec. Names of codecs implemented by `zarrs` cannot be registered, as either implementation may be used.
+#[pyfunction]
+pub fn register_codec(name: String, encode: Py<PyAny>, decode: Py<PyAny>) -> PyResult<()> {
+    if inventory::iter::<CodecPlugin>
+        .into_iter()
+        .any(|plugin| plugin.identifier() != PYTHON_CODEC_PLUGIN && plugin.match_name(&name))
+    {
+        return Err(PyValueError::new_err(format!(
+            "codec {name} is implemented by zarrs, so it cannot be registered"
+        )));
+    }
+    python_codecs()
+        .write()
+        .map_py_err::<PyRuntimeError>()?
+        .insert(name, Arc::new(PythonCodecFunctions { encode, decode }));
+    Ok(())
+}
+
+fn python_codec_functions(name: &str) -> Option<Arc<PythonCodecFunctions>> {
+    python_codecs().read().ok()?.get(name).cloned()
+}
+
+fn is_name_python_codec(name: &str) -> bool {
+    python_codec_functions(name).is_some()
+}
+
+fn create_codec_python(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let functions = python_codec_functions(metadata.name()).ok_or_else(|| {
+        PluginCreateError::Other(format!("codec {} is not registered", metadata.name()))
+    })?;
+    let configuration = Python::with_gil(|py| -> PyResult<Py<PyAny>> {
+        let configuration =
+            serde_json::to_string(&metadata.configuration()).map_py_err::<PyValueError>()?;
+        Ok(PyModule::import(py, "json")?
+            .getattr("loads")?
+            .call1((configuration,))?
+            .unbind())
+    })
+    .map_err(|err| {
+        PluginCreateError::Other(format!(
+            "codec {} has an invalid configuration: {err}",
+            metadata.name()
+        ))
+    })?;
+    Ok(Codec::BytesToBytes(Arc::new(PythonCodec {
+        metadata: metadata.clone(),
+        configuration,
+        functions,
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new(PYTHON_CODEC_PLUGIN, is_name_python_codec, create_codec_python)
+}
+
+/// A bytes to bytes codec which delegates to the callables registered with [`register_codec`].
+struct PythonCodec {
+    metadata: MetadataV3,
+    /// The configuration of the codec as a Python object, which is passed to every call.
+    configuration: Py<PyAny>,
+    functions: Arc<PythonCodecFunctions>,
+}
+
+impl std::fmt::Debug for PythonCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PythonCodec")
+            .field("metadata", &self.metadata)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PythonCodec {
+    fn call(&self, function: &Py<PyAny>, bytes: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Python::with_gil(|py| -> PyResult<Vec<u8>> {
+            let output = function
+                .bind(py)
+                .call1((PyBytes::new(py, bytes), self.configuration.bind(py)))?;
+            Ok(output.downcast::<PyBytes>()?.as_bytes().to_vec())
+        })
+        .map_err(|err| CodecError::Other(format!("codec {} failed: {err}", self.metadata.name())))
+    }
+}
+
+impl CodecTraits for PythonCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        Some(self.metadata.clone())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl BytesToBytesCodecTraits for PythonCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // The GIL is held while the codec runs
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encoded_representation(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        BytesRepresentation::UnboundedSize
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.call(&self.functions.encode, &decoded_value)
+            .map(Cow::Owned)
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        self.call(&self.functions.decode, &encoded_value)
+            .map(Cow::Owned)
+    }
+}
@@ -0,0 +1,116 @@
+use std::{num::NonZeroU64, sync::Arc};
+
+use zarrs::{
+    array::{
+        codec::{
+            ArrayCodecTraits, ArrayToArrayCodecTraits, Codec, CodecError, CodecOptions,
+            CodecPlugin, CodecTraits,
+        },
+        ArrayBytes, ArrayMetadataOptions, ChunkRepresentation, RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+/// The names of the `squeeze` codec of `zarrs`, as in the array metadata.
+const SQUEEZE_NAMES: [&str; 3] = [
+    "zarrs.squeeze",
+    "squeeze",
+    "https://codec.zarrs.dev/array_to_array/squeeze",
+];
+
+fn is_name_squeeze(name: &str) -> bool {
+    SQUEEZE_NAMES.contains(&name)
+}
+
+fn create_codec_squeeze(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    if metadata
+        .configuration()
+        .is_some_and(|configuration| !configuration.is_empty())
+    {
+        return Err(PluginCreateError::Other(format!(
+            "codec {} has no configuration",
+            metadata.name()
+        )));
+    }
+    Ok(Codec::ArrayToArray(Arc::new(SqueezeCodec {
+        metadata: metadata.clone(),
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new(SQUEEZE_NAMES[0], is_name_squeeze, create_codec_squeeze)
+}
+
+/// A codec which removes the dimensions of chunks with a size of 1, so chunks are encoded with fewer dimensions.
+///
+/// The elements are unchanged, since removing dimensions of size 1 does not change their C order.
+#[derive(Debug)]
+struct SqueezeCodec {
+    metadata: MetadataV3,
+}
+
+impl CodecTraits for SqueezeCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        Some(self.metadata.clone())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl ArrayCodecTraits for SqueezeCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+impl ArrayToArrayCodecTraits for SqueezeCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn ArrayToArrayCodecTraits> {
+        self as Arc<dyn ArrayToArrayCodecTraits>
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<ChunkRepresentation, CodecError> {
+        let shape: Vec<NonZeroU64> = decoded_representation
+            .shape()
+            .iter()
+            .copied()
+            .filter(|size| size.get() != 1)
+            .collect();
+        ChunkRepresentation::new(
+            shape,
+            decoded_representation.data_type().clone(),
+            decoded_representation.fill_value().clone(),
+        )
+        .map_err(|err| CodecError::Other(err.to_string()))
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        _decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(bytes)
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        _decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(bytes)
+    }
+}
@@ -1,32 +1,74 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 
-use opendal::Builder;
 use pyo3::{
-    exceptions::{PyNotImplementedError, PyValueError},
+    exceptions::{PyOSError, PyValueError},
     types::{PyAnyMethods, PyStringMethods, PyTypeMethods},
-    Bound, FromPyObject, PyAny, PyErr, PyResult,
+    Bound, FromPyObject, PyAny, PyErr, PyResult, Python,
 };
 use pyo3_stub_gen::derive::gen_stub_pyclass_enum;
 use zarrs::storage::{
-    storage_adapter::async_to_sync::AsyncToSyncStorageAdapter, ReadableWritableListableStorage,
+    byte_range::ByteRange, storage_adapter::async_to_sync::AsyncToSyncStorageAdapter, Bytes,
+    ReadableWritableListableStorage, StorageError, StoreKey, StoreKeysPrefixes, StorePrefix,
 };
 
 use crate::{runtime::tokio_block_on, utils::PyErrExt};
 
+mod cache;
+mod decoder_cache;
+mod disk_cache;
 mod filesystem;
 mod http;
+mod http_client;
+mod key_transform;
 mod manager;
+mod memory;
+mod multipart;
+mod operator;
+mod python;
+mod read_only;
+mod retry;
+mod s3;
+mod sqlite;
+mod stats;
+mod timeout;
+mod transformer;
+mod zip;
 
+pub(crate) use self::cache::CacheConfig;
+pub(crate) use self::disk_cache::DiskCache;
+pub(crate) use self::filesystem::FilesystemOptions;
 pub use self::filesystem::FilesystemStoreConfig;
 pub use self::http::HttpStoreConfig;
-pub(crate) use self::manager::StoreManager;
+pub(crate) use self::http_client::http_client;
+pub(crate) use self::key_transform::KeyTransform;
+pub(crate) use self::manager::{StoreManager, StoreManagerConfig};
+pub use self::memory::MemoryStoreConfig;
+pub(crate) use self::multipart::{MultipartConfig, MULTIPART_PART_SIZE_MINIMUM};
+pub use self::operator::OpendalStoreConfig;
+pub use self::python::PythonStoreConfig;
+pub(crate) use self::retry::RetryConfig;
+pub use self::s3::S3StoreConfig;
+pub use self::sqlite::SqliteStoreConfig;
+pub use self::stats::StoreStats;
+pub(crate) use self::timeout::storage_err_to_py_err;
+pub use self::transformer::PerformanceMetrics;
+pub(crate) use self::transformer::{StorageTransformer, StorageTransformers};
+pub use self::zip::ZipStoreConfig;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[gen_stub_pyclass_enum]
 pub enum StoreConfig {
     Filesystem(FilesystemStoreConfig),
     Http(HttpStoreConfig),
-    // TODO: Add support for more stores
+    Memory(MemoryStoreConfig),
+    Opendal(OpendalStoreConfig),
+    Python(PythonStoreConfig),
+    S3(S3StoreConfig),
+    Sqlite(SqliteStoreConfig),
+    Zip(ZipStoreConfig),
 }
 
 impl<'py> FromPyObject<'py> for StoreConfig {
@@ -38,48 +80,262 @@ impl<'py> FromPyObject<'py> for StoreConfig {
                 let root: String = store.getattr("root")?.call_method0("__str__")?.extract()?;
                 Ok(StoreConfig::Filesystem(FilesystemStoreConfig::new(root)))
             }
+            "MemoryStore" => Ok(StoreConfig::Memory(MemoryStoreConfig::new(store)?)),
+            "SQLiteStore" => Ok(StoreConfig::Sqlite(SqliteStoreConfig::new(store)?)),
+            "ZipStore" => {
+                if store.getattr("read_only")?.extract()? {
+                    Ok(StoreConfig::Zip(ZipStoreConfig::new(store)?))
+                } else {
+                    // The archive is open for writing by zarr-python, so it cannot be read independently
+                    Ok(StoreConfig::Python(PythonStoreConfig::new(store)?))
+                }
+            }
             "FsspecStore" => {
                 let fs = store.getattr("fs")?;
-                let fs_name = fs.get_type().name()?;
-                let fs_name = fs_name.to_str()?;
                 let path: String = store.getattr("path")?.extract()?;
-                let storage_options: HashMap<String, Bound<'py, PyAny>> =
-                    fs.getattr("storage_options")?.extract()?;
-                match fs_name {
-                    "HTTPFileSystem" => Ok(StoreConfig::Http(HttpStoreConfig::new(
-                        &path,
-                        &storage_options,
-                    )?)),
-                    _ => Err(PyErr::new::<PyNotImplementedError, _>(format!(
-                        "zarrs-python does not support {fs_name} (FsspecStore) stores"
-                    ))),
-                }
+                fsspec_store_config(store, &fs, &path)
             }
-            _ => Err(PyErr::new::<PyNotImplementedError, _>(format!(
-                "zarrs-python does not support {name} stores"
-            ))),
+            // Fall back to calling the methods of the Python store
+            _ => Ok(StoreConfig::Python(PythonStoreConfig::new(store)?)),
         }
     }
 }
 
-impl TryFrom<&StoreConfig> for ReadableWritableListableStorage {
-    type Error = PyErr;
+/// Resolve the store config of an `FsspecStore` from its file system.
+///
+/// File systems wrapping another file system (e.g. `simplecache::s3://...`) resolve to the wrapped file system.
+fn fsspec_store_config(
+    store: &Bound<'_, PyAny>,
+    fs: &Bound<'_, PyAny>,
+    path: &str,
+) -> PyResult<StoreConfig> {
+    let fs_name = fs.get_type().name()?;
+    let fs_name = fs_name.to_str()?;
+    match fs_name {
+        "SimpleCacheFileSystem" | "WholeFileCacheFileSystem" | "CachingFileSystem" => {
+            fsspec_store_config(store, &fs.getattr("fs")?, path)
+        }
+        "AsyncFileSystemWrapper" => fsspec_store_config(store, &fs.getattr("sync_fs")?, path),
+        "LocalFileSystem" => Ok(StoreConfig::Filesystem(FilesystemStoreConfig::new(
+            path.to_string(),
+        ))),
+        "HTTPFileSystem" => {
+            let storage_options: HashMap<String, Bound<'_, PyAny>> =
+                fs.getattr("storage_options")?.extract()?;
+            Ok(StoreConfig::Http(HttpStoreConfig::new(
+                path,
+                &storage_options,
+            )?))
+        }
+        "S3FileSystem" => {
+            let storage_options: HashMap<String, Bound<'_, PyAny>> =
+                fs.getattr("storage_options")?.extract()?;
+            Ok(StoreConfig::S3(S3StoreConfig::new(path, &storage_options)?))
+        }
+        // Delegate any other protocol to the methods of the FsspecStore
+        _ => Ok(StoreConfig::Python(PythonStoreConfig::new(store)?)),
+    }
+}
 
-    fn try_from(value: &StoreConfig) -> Result<Self, Self::Error> {
-        match value {
-            StoreConfig::Filesystem(config) => config.try_into(),
-            StoreConfig::Http(config) => config.try_into(),
+impl StoreConfig {
+    /// Whether the zarr-python store of this config has been dropped, so its store can be evicted.
+    ///
+    /// This acquires the GIL for stores which are only weakly referenced (e.g. `MemoryStore`).
+    pub(crate) fn is_dropped(&self) -> bool {
+        match self {
+            StoreConfig::Memory(config) => config.is_dropped(),
+            _ => false,
+        }
+    }
+
+    /// Create the store with the options of a [`StoreManager`].
+    ///
+    /// `storage_options` override the `OpenDAL` service configuration of remote stores, and are ignored for other stores.
+    pub(crate) fn to_storage(
+        &self,
+        manager_config: &StoreManagerConfig,
+    ) -> PyResult<ReadableWritableListableStorage> {
+        let with_storage_options = |mut config: BTreeMap<String, String>| {
+            config.extend(manager_config.storage_options.clone());
+            config
+        };
+        match self {
+            StoreConfig::Filesystem(config) => config.to_storage(manager_config.filesystem),
+            StoreConfig::Http(config) => {
+                check_storage_options::<opendal::services::HttpConfig>(
+                    "HTTP",
+                    &manager_config.storage_options,
+                )?;
+                opendal_service_to_sync_store::<opendal::services::HttpConfig>(
+                    with_storage_options(config.opendal_config()),
+                    manager_config,
+                    |builder| match &manager_config.http_client {
+                        Some(http_client) => builder.http_client(http_client.clone()),
+                        None => builder,
+                    },
+                )
+            }
+            StoreConfig::Memory(config) => config.try_into(),
+            StoreConfig::Opendal(config) => config.to_storage(manager_config),
+            StoreConfig::Python(config) => config.try_into(),
+            StoreConfig::S3(config) => config.to_storage(manager_config),
+            StoreConfig::Sqlite(config) => config.try_into(),
+            StoreConfig::Zip(config) => config.try_into(),
         }
     }
 }
 
-fn opendal_builder_to_sync_store<B: Builder>(
-    builder: B,
+/// Check that storage options are configuration options of the `OpenDAL` service `C`, as other options would be silently ignored.
+fn check_storage_options<C: opendal::Configurator>(
+    service: &str,
+    storage_options: &BTreeMap<String, String>,
+) -> PyResult<()> {
+    let config = C::from_iter(BTreeMap::new()).map_py_err::<PyValueError>()?;
+    let serde_json::Value::Object(options) =
+        serde_json::to_value(&config).map_py_err::<PyValueError>()?
+    else {
+        return Ok(());
+    };
+    match storage_options
+        .keys()
+        .find(|storage_option| !options.contains_key(storage_option.as_str()))
+    {
+        Some(storage_option) => Err(PyValueError::new_err(format!(
+            "Unsupported storage option for the {service} store: {storage_option}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Convert Python storage options to `OpenDAL` style string configuration options.
+pub(crate) fn storage_options_to_strings(
+    storage_options: &HashMap<String, Bound<'_, PyAny>>,
+) -> PyResult<BTreeMap<String, String>> {
+    storage_options
+        .iter()
+        .map(|(storage_option, value)| {
+            let value: String = if let Ok(value) = value.extract::<bool>() {
+                value.to_string()
+            } else {
+                value.str()?.extract()?
+            };
+            Ok((storage_option.clone(), value))
+        })
+        .collect()
+}
+
+fn opendal_config_to_sync_store(
+    scheme: opendal::Scheme,
+    config: BTreeMap<String, String>,
+    manager_config: &StoreManagerConfig,
 ) -> PyResult<ReadableWritableListableStorage> {
-    let operator = opendal::Operator::new(builder)
+    let operator = opendal::Operator::via_iter(scheme, config).map_py_err::<PyValueError>()?;
+    Ok(opendal_operator_to_sync_store(operator, manager_config))
+}
+
+/// Create the store of an `OpenDAL` service, configuring its builder (e.g. with a custom HTTP client).
+fn opendal_service_to_sync_store<C: opendal::Configurator>(
+    config: BTreeMap<String, String>,
+    manager_config: &StoreManagerConfig,
+    configure: impl FnOnce(C::Builder) -> C::Builder,
+) -> PyResult<ReadableWritableListableStorage> {
+    let operator = opendal_service_to_operator::<C>(config, configure)?;
+    Ok(opendal_operator_to_sync_store(operator, manager_config))
+}
+
+/// Create the operator of an `OpenDAL` service, configuring its builder (e.g. with a custom HTTP client).
+fn opendal_service_to_operator<C: opendal::Configurator>(
+    config: BTreeMap<String, String>,
+    configure: impl FnOnce(C::Builder) -> C::Builder,
+) -> PyResult<opendal::Operator> {
+    let builder = configure(
+        C::from_iter(config)
+            .map_py_err::<PyValueError>()?
+            .into_builder(),
+    );
+    Ok(opendal::Operator::new(builder)
         .map_py_err::<PyValueError>()?
-        .finish();
-    let store = Arc::new(zarrs_opendal::AsyncOpendalStore::new(operator));
-    let store = Arc::new(AsyncToSyncStorageAdapter::new(store, tokio_block_on()));
-    Ok(store)
+        .finish())
+}
+
+/// Create the store of an `OpenDAL` operator, with the multipart uploads and disk cache of a [`StoreManager`].
+fn opendal_operator_to_sync_store(
+    operator: opendal::Operator,
+    manager_config: &StoreManagerConfig,
+) -> ReadableWritableListableStorage {
+    // Temporary errors are retried by the operator, as they are converted to strings by the store
+    let operator = match manager_config.retry {
+        Some(retry) => operator.layer(retry.opendal_layer()),
+        None => operator,
+    };
+    let store = Arc::new(zarrs_opendal::AsyncOpendalStore::new(operator.clone()));
+    let mut store: ReadableWritableListableStorage =
+        Arc::new(AsyncToSyncStorageAdapter::new(store, tokio_block_on()));
+    if let Some(multipart) = manager_config.multipart {
+        store = Arc::new(multipart::MultipartStorageAdapter::new(
+            store,
+            operator.clone(),
+            multipart,
+        ));
+    }
+    if let Some(disk_cache) = &manager_config.disk_cache {
+        store = Arc::new(disk_cache::DiskCacheStorageAdapter::new(
+            store,
+            operator,
+            disk_cache.clone(),
+        ));
+    }
+    store
+}
+
+/// Convert a Python error to a storage error, keeping `OSError`s as I/O errors so they are retried.
+fn py_err_to_storage_err(err: PyErr) -> StorageError {
+    if Python::with_gil(|py| err.is_instance_of::<PyOSError>(py)) {
+        StorageError::IOError(std::io::Error::other(err.to_string()))
+    } else {
+        StorageError::Other(err.to_string())
+    }
+}
+
+/// Extract byte ranges from the complete value of a key.
+fn extract_byte_ranges(
+    key: &StoreKey,
+    bytes: &Bytes,
+    byte_ranges: &[ByteRange],
+) -> Result<Vec<Bytes>, StorageError> {
+    let size = bytes.len() as u64;
+    byte_ranges
+        .iter()
+        .map(|byte_range| {
+            if byte_range.end(size) > size {
+                return Err(StorageError::Other(format!(
+                    "byte range {byte_range} is out of bounds for {key} with size {size}"
+                )));
+            }
+            Ok(bytes.slice(byte_range.to_range_usize(size)))
+        })
+        .collect()
+}
+
+/// Split keys into the keys and prefixes immediately under `prefix`.
+fn keys_to_keys_prefixes(
+    keys: impl IntoIterator<Item = String>,
+    prefix: &StorePrefix,
+) -> Result<StoreKeysPrefixes, StorageError> {
+    let mut child_keys = BTreeSet::new();
+    let mut child_prefixes = BTreeSet::new();
+    for key in keys {
+        let Some(suffix) = key.strip_prefix(prefix.as_str()) else {
+            continue;
+        };
+        if let Some((child, _)) = suffix.split_once('/') {
+            child_prefixes.insert(StorePrefix::new(format!("{}{child}/", prefix.as_str()))?);
+        } else {
+            child_keys.insert(StoreKey::new(key)?);
+        }
+    }
+    Ok(StoreKeysPrefixes::new(
+        child_keys.into_iter().collect(),
+        child_prefixes.into_iter().collect(),
+    ))
 }
@@ -0,0 +1,255 @@
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+use zarrs::storage::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    ReadableWritableListableStorage, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+use super::{extract_byte_ranges, stats::StoreStatistics};
+
+/// The limits of an encoded chunk cache.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CacheConfig {
+    pub(crate) max_bytes: Option<usize>,
+    pub(crate) max_entries: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+enum CachedRange {
+    All,
+    FromStart(u64, Option<u64>),
+    Suffix(u64),
+}
+
+impl From<&ByteRange> for CachedRange {
+    fn from(byte_range: &ByteRange) -> Self {
+        match *byte_range {
+            ByteRange::FromStart(offset, length) => Self::FromStart(offset, length),
+            ByteRange::Suffix(length) => Self::Suffix(length),
+        }
+    }
+}
+
+struct Cache {
+    entries: LruCache<(StoreKey, CachedRange), MaybeBytes>,
+    size: usize,
+    /// The number of invalidations so far, so a value read while the cache was invalidated is not inserted.
+    generation: u64,
+}
+
+impl Cache {
+    fn get(&mut self, key: &StoreKey, range: CachedRange) -> Option<MaybeBytes> {
+        self.entries.get(&(key.clone(), range)).cloned()
+    }
+
+    fn insert(
+        &mut self,
+        config: &CacheConfig,
+        key: StoreKey,
+        range: CachedRange,
+        value: MaybeBytes,
+    ) {
+        let size = value.as_ref().map_or(0, Bytes::len);
+        if config.max_bytes.is_some_and(|max_bytes| size > max_bytes) {
+            return;
+        }
+        self.size += size;
+        if let Some((_, old)) = self.entries.push((key, range), value) {
+            self.size -= old.as_ref().map_or(0, Bytes::len);
+        }
+        while config
+            .max_bytes
+            .is_some_and(|max_bytes| self.size > max_bytes)
+            || config
+                .max_entries
+                .is_some_and(|max_entries| self.entries.len() > max_entries)
+        {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.size -= evicted.as_ref().map_or(0, Bytes::len);
+        }
+    }
+
+    fn invalidate(&mut self, matches: impl Fn(&StoreKey) -> bool) {
+        self.generation += 1;
+        let invalidated: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|((key, _), _)| matches(key))
+            .map(|(cache_key, _)| cache_key.clone())
+            .collect();
+        for cache_key in invalidated {
+            if let Some(value) = self.entries.pop(&cache_key) {
+                self.size -= value.as_ref().map_or(0, Bytes::len);
+            }
+        }
+    }
+}
+
+/// An LRU cache of encoded values (and byte ranges of values) between a store and the codec chain.
+///
+/// Writes through the adapter invalidate the cached values of the written keys once they complete.
+/// A value read from the store while the cache is invalidated is not cached, as it may predate the write.
+pub(crate) struct CacheStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    config: CacheConfig,
+    cache: Mutex<Cache>,
+    statistics: Arc<StoreStatistics>,
+}
+
+impl CacheStorageAdapter {
+    pub(crate) fn new(
+        storage: ReadableWritableListableStorage,
+        config: CacheConfig,
+        statistics: Arc<StoreStatistics>,
+    ) -> Self {
+        Self {
+            storage,
+            config,
+            cache: Mutex::new(Cache {
+                entries: LruCache::unbounded(),
+                size: 0,
+                generation: 0,
+            }),
+            statistics,
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Cache>, StorageError> {
+        self.cache
+            .lock()
+            .map_err(|err| StorageError::Other(err.to_string()))
+    }
+}
+
+impl ReadableStorageTraits for CacheStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let generation = {
+            let mut cache = self.lock()?;
+            if let Some(value) = cache.get(key, CachedRange::All) {
+                self.statistics.record_cache_hit();
+                return Ok(value);
+            }
+            cache.generation
+        };
+        self.statistics.record_cache_miss();
+        let value = self.storage.get(key)?;
+        let mut cache = self.lock()?;
+        if cache.generation == generation {
+            cache.insert(&self.config, key.clone(), CachedRange::All, value.clone());
+        }
+        Ok(value)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let generation = {
+            let mut cache = self.lock()?;
+            if let Some(value) = cache.get(key, CachedRange::All) {
+                self.statistics.record_cache_hit();
+                return value
+                    .map(|bytes| extract_byte_ranges(key, &bytes, byte_ranges))
+                    .transpose();
+            }
+            let cached: Option<Vec<MaybeBytes>> = byte_ranges
+                .iter()
+                .map(|byte_range| cache.get(key, byte_range.into()))
+                .collect();
+            if let Some(cached) = cached.filter(|cached| !cached.is_empty()) {
+                // Every byte range is cached, but the key may have been missing for some
+                self.statistics.record_cache_hit();
+                return Ok(cached.into_iter().collect());
+            }
+            cache.generation
+        };
+        self.statistics.record_cache_miss();
+
+        let values = self.storage.get_partial_values_key(key, byte_ranges)?;
+        let mut cache = self.lock()?;
+        if cache.generation != generation {
+            return Ok(values);
+        }
+        if let Some(values) = &values {
+            for (byte_range, value) in byte_ranges.iter().zip(values) {
+                cache.insert(
+                    &self.config,
+                    key.clone(),
+                    byte_range.into(),
+                    Some(value.clone()),
+                );
+            }
+        } else {
+            cache.insert(&self.config, key.clone(), CachedRange::All, None);
+        }
+        Ok(values)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        if let Some(value) = self.lock()?.get(key, CachedRange::All) {
+            return Ok(value.map(|bytes| bytes.len() as u64));
+        }
+        self.storage.size_key(key)
+    }
+}
+
+impl WritableStorageTraits for CacheStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let written = self.storage.set(key, value);
+        self.lock()?.invalidate(|cached_key| cached_key == key);
+        written
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        let written = self.storage.set_partial_values(key_offset_values);
+        self.lock()?.invalidate(|cached_key| {
+            key_offset_values
+                .iter()
+                .any(|key_offset_value| key_offset_value.key() == cached_key)
+        });
+        written
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let erased = self.storage.erase(key);
+        self.lock()?.invalidate(|cached_key| cached_key == key);
+        erased
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let erased = self.storage.erase_prefix(prefix);
+        self.lock()?
+            .invalidate(|cached_key| cached_key.as_str().starts_with(prefix.as_str()));
+        erased
+    }
+}
+
+impl ListableStorageTraits for CacheStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
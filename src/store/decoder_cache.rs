@@ -0,0 +1,84 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use pyo3::{exceptions::PyRuntimeError, PyResult};
+use zarrs::{array::codec::ArrayPartialDecoderTraits, storage::StoreKey};
+
+use crate::utils::PyErrExt as _;
+
+use super::StoreConfig;
+
+/// An LRU cache of the partial decoders of chunks, keyed by their store and key.
+///
+/// The partial decoder of a shard holds its decoded shard index, so repeated reads of a shard do not retrieve and decode its index again.
+/// Writes through the store manager invalidate the cached decoders of the written keys once they complete.
+/// Writes by other pipelines or processes are not detected, so the cache assumes that the pipeline is the only writer of the chunks it reads.
+pub(crate) struct PartialDecoderCache {
+    entries: Mutex<CachedDecoders>,
+}
+
+/// The cached partial decoders, and the number of invalidations so far.
+struct CachedDecoders {
+    decoders: LruCache<(StoreConfig, StoreKey), Arc<dyn ArrayPartialDecoderTraits>>,
+    generation: u64,
+}
+
+impl PartialDecoderCache {
+    pub(crate) fn new(max_entries: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(CachedDecoders {
+                decoders: LruCache::new(max_entries),
+                generation: 0,
+            }),
+        }
+    }
+
+    /// The cached partial decoder of a key, or the decoder created by `create`, which is cached.
+    ///
+    /// The created decoder is not cached if the cache is invalidated while it is created, as it may hold a chunk (e.g. a shard index) read before a write completed.
+    pub(crate) fn get_or_try_insert(
+        &self,
+        store_config: StoreConfig,
+        key: &StoreKey,
+        create: impl FnOnce() -> PyResult<Arc<dyn ArrayPartialDecoderTraits>>,
+    ) -> PyResult<Arc<dyn ArrayPartialDecoderTraits>> {
+        let cache_key = (store_config, key.clone());
+        let generation = {
+            let mut entries = self.entries.lock().map_py_err::<PyRuntimeError>()?;
+            if let Some(decoder) = entries.decoders.get(&cache_key) {
+                return Ok(decoder.clone());
+            }
+            entries.generation
+        };
+        // The decoder is created without holding the lock, as it may read from the store (e.g. the shard index)
+        let decoder = create()?;
+        let mut entries = self.entries.lock().map_py_err::<PyRuntimeError>()?;
+        if entries.generation == generation {
+            entries.decoders.put(cache_key, decoder.clone());
+        }
+        Ok(decoder)
+    }
+
+    /// Remove the cached partial decoders of the keys of a store that `matches`, after they are written.
+    pub(crate) fn invalidate(
+        &self,
+        store_config: &StoreConfig,
+        matches: impl Fn(&StoreKey) -> bool,
+    ) -> PyResult<()> {
+        let mut entries = self.entries.lock().map_py_err::<PyRuntimeError>()?;
+        entries.generation += 1;
+        let invalidated: Vec<_> = entries
+            .decoders
+            .iter()
+            .filter(|((config, key), _)| config == store_config && matches(key))
+            .map(|(cache_key, _)| cache_key.clone())
+            .collect();
+        for cache_key in invalidated {
+            entries.decoders.pop(&cache_key);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+use std::{
+    ffi::OsString,
+    fs::File,
+    future::IntoFuture,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+use lru::LruCache;
+use zarrs::storage::{
+    byte_range::ByteRange, storage_adapter::async_to_sync::AsyncToSyncBlockOn, Bytes,
+    ListableStorageTraits, MaybeBytes, ReadableStorageTraits, ReadableWritableListableStorage,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+
+use super::extract_byte_ranges;
+use crate::runtime::tokio_block_on;
+
+struct DiskCacheIndex {
+    entries: LruCache<PathBuf, u64>,
+    size: u64,
+}
+
+/// A persistent cache of encoded values in a local directory, shared by the remote stores of a pipeline.
+///
+/// Entries are evicted in least-recently-used order once the cache exceeds `max_bytes`.
+/// The order is persisted through the modification times of the cached files, so it carries over between sessions.
+/// The index of cached files is built by scanning the directory when a pipeline first uses it, so `max_bytes` is only enforced for the entries written by this pipeline (and those found by the scan).
+/// Other pipelines and processes sharing the directory keep their own index, so the directory can grow beyond `max_bytes` until the next scan.
+pub(crate) struct DiskCache {
+    directory: PathBuf,
+    max_bytes: Option<u64>,
+    index: Mutex<Option<DiskCacheIndex>>,
+    temporary_files: AtomicU64,
+}
+
+impl DiskCache {
+    pub(crate) fn new(directory: PathBuf, max_bytes: Option<u64>) -> Self {
+        Self {
+            directory,
+            max_bytes,
+            index: Mutex::default(),
+            temporary_files: AtomicU64::default(),
+        }
+    }
+
+    /// Run `f` on the index of cached files, scanning the cache directory on first use.
+    fn with_index(&self, f: impl FnOnce(&mut DiskCacheIndex)) {
+        let Ok(mut index) = self.index.lock() else {
+            return;
+        };
+        let index = index.get_or_insert_with(|| {
+            let mut files = Vec::new();
+            scan_directory(&self.directory, &mut files);
+            files.sort_by_key(|(_, _, modified)| *modified);
+            let mut index = DiskCacheIndex {
+                entries: LruCache::unbounded(),
+                size: 0,
+            };
+            for (path, size, _) in files {
+                index.size += size;
+                index.entries.push(path, size);
+            }
+            index
+        });
+        f(index);
+    }
+
+    fn read(&self, path: &Path) -> Option<Bytes> {
+        let bytes = std::fs::read(path).ok()?;
+        // Mark the entry as recently used, also for later sessions
+        if let Ok(file) = File::options().write(true).open(path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        self.with_index(|index| {
+            if index.entries.get(path).is_none() {
+                index.size += bytes.len() as u64;
+                index.entries.push(path.to_path_buf(), bytes.len() as u64);
+            }
+        });
+        Some(Bytes::from(bytes))
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) {
+        // Write to a temporary file first, so that a concurrent reader never sees a partially written entry
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        let mut temporary_path = OsString::from(path.as_os_str());
+        temporary_path.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            self.temporary_files.fetch_add(1, Ordering::Relaxed)
+        ));
+        if std::fs::create_dir_all(parent).is_err()
+            || std::fs::write(&temporary_path, bytes).is_err()
+            || std::fs::rename(&temporary_path, path).is_err()
+        {
+            let _ = std::fs::remove_file(&temporary_path);
+            return;
+        }
+        self.with_index(|index| {
+            index.size += bytes.len() as u64;
+            if let Some((_, size)) = index.entries.push(path.to_path_buf(), bytes.len() as u64) {
+                index.size -= size;
+            }
+            while self
+                .max_bytes
+                .is_some_and(|max_bytes| index.size > max_bytes)
+            {
+                let Some((path, size)) = index.entries.pop_lru() else {
+                    break;
+                };
+                index.size -= size;
+                let _ = std::fs::remove_file(path);
+            }
+        });
+    }
+}
+
+/// Collect the path, size, and modification time of every cached file under `directory`.
+fn scan_directory(directory: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            scan_directory(&path, files);
+        } else if path.extension().is_none_or(|extension| extension != "tmp") {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((path, metadata.len(), modified));
+        }
+    }
+}
+
+/// Replace characters that are not safe in a file name.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Caches the values (and byte ranges of values) of an `OpenDAL` store in a [`DiskCache`].
+///
+/// Cached entries are keyed by the `ETag` (or last modified time) of the value, so values that change upstream are retrieved again.
+/// Validating an entry requires a metadata request, which is much cheaper than retrieving the value for large chunks.
+/// A value is read conditionally on the version it is cached under, so a value changing between its metadata request and its read is not cached under the wrong version.
+pub(crate) struct DiskCacheStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    operator: opendal::Operator,
+    cache: Arc<DiskCache>,
+    directory: PathBuf,
+}
+
+impl DiskCacheStorageAdapter {
+    pub(crate) fn new(
+        storage: ReadableWritableListableStorage,
+        operator: opendal::Operator,
+        cache: Arc<DiskCache>,
+    ) -> Self {
+        let info = operator.info();
+        let directory = cache
+            .directory
+            .join(sanitize(info.scheme().into_static()))
+            .join(sanitize(info.name()))
+            .join(sanitize(info.root()));
+        Self {
+            storage,
+            operator,
+            cache,
+            directory,
+        }
+    }
+
+    /// The metadata of a value, or [`None`] if the key does not exist.
+    fn stat(&self, key: &StoreKey) -> Result<Option<opendal::Metadata>, StorageError> {
+        match tokio_block_on().block_on(self.operator.stat(key.as_str())) {
+            Ok(metadata) => Ok(Some(metadata)),
+            Err(err) if err.kind() == opendal::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(StorageError::Other(err.to_string())),
+        }
+    }
+
+    /// The version of a value, its `ETag` or last modified time, or [`None`] if the value is not versioned.
+    fn version(metadata: &opendal::Metadata) -> Option<String> {
+        if let Some(etag) = metadata.etag() {
+            Some(etag.to_string())
+        } else {
+            let last_modified = metadata.last_modified()?;
+            Some(format!(
+                "{}-{}",
+                last_modified.timestamp(),
+                metadata.content_length()
+            ))
+        }
+    }
+
+    /// Read a value (or a byte range of a value) at the version of `metadata`, or [`None`] if the value changed (or cannot be read conditionally).
+    ///
+    /// The read is conditional on the `ETag` of the value, otherwise the metadata of the value is retrieved again and compared after the read.
+    fn read_version(
+        &self,
+        key: &StoreKey,
+        metadata: &opendal::Metadata,
+        range: Option<Range<u64>>,
+    ) -> Result<Option<Bytes>, StorageError> {
+        let mut read = self.operator.read_with(key.as_str());
+        if let Some(range) = range {
+            read = read.range(range);
+        }
+        if let Some(etag) = metadata.etag() {
+            read = read.if_match(etag);
+        }
+        let buffer = match tokio_block_on().block_on(read.into_future()) {
+            Ok(buffer) => buffer,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    opendal::ErrorKind::ConditionNotMatch
+                        | opendal::ErrorKind::NotFound
+                        | opendal::ErrorKind::Unsupported
+                ) =>
+            {
+                return Ok(None);
+            }
+            Err(err) => return Err(StorageError::Other(err.to_string())),
+        };
+        if metadata.etag().is_none()
+            && self.stat(key)?.as_ref().and_then(Self::version) != Self::version(metadata)
+        {
+            return Ok(None);
+        }
+        Ok(Some(buffer.to_bytes()))
+    }
+
+    /// The path of a cached value (or byte range of a value) at a version, or [`None`] if the value is not versioned.
+    fn entry_path(
+        &self,
+        key: &StoreKey,
+        metadata: &opendal::Metadata,
+        byte_range: Option<&ByteRange>,
+    ) -> Option<PathBuf> {
+        let version = Self::version(metadata)?;
+        let mut file_name = format!("{}@{}", key.as_str(), sanitize(&version));
+        if let Some(byte_range) = byte_range {
+            let size = metadata.content_length();
+            file_name.push_str(&format!(
+                "@{}-{}",
+                byte_range.start(size),
+                byte_range.end(size)
+            ));
+        }
+        Some(self.directory.join(file_name))
+    }
+}
+
+impl ReadableStorageTraits for DiskCacheStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let Some(metadata) = self.stat(key)? else {
+            return Ok(None);
+        };
+        let Some(path) = self.entry_path(key, &metadata, None) else {
+            return self.storage.get(key);
+        };
+        if let Some(bytes) = self.cache.read(&path) {
+            return Ok(Some(bytes));
+        }
+        match self.read_version(key, &metadata, None)? {
+            Some(bytes) => {
+                self.cache.write(&path, &bytes);
+                Ok(Some(bytes))
+            }
+            // The value changed since its metadata was retrieved, so it is read without caching it
+            None => self.storage.get(key),
+        }
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(metadata) = self.stat(key)? else {
+            return Ok(None);
+        };
+        let Some(path) = self.entry_path(key, &metadata, None) else {
+            return self.storage.get_partial_values_key(key, byte_ranges);
+        };
+        if let Some(bytes) = self.cache.read(&path) {
+            return extract_byte_ranges(key, &bytes, byte_ranges).map(Some);
+        }
+
+        let paths: Vec<PathBuf> = byte_ranges
+            .iter()
+            .filter_map(|byte_range| self.entry_path(key, &metadata, Some(byte_range)))
+            .collect();
+        let cached: Option<Vec<Bytes>> = paths.iter().map(|path| self.cache.read(path)).collect();
+        if let Some(cached) = cached {
+            return Ok(Some(cached));
+        }
+
+        let size = metadata.content_length();
+        let values: Option<Vec<Bytes>> = byte_ranges
+            .iter()
+            .map(|byte_range| {
+                let range = byte_range.start(size)..byte_range.end(size);
+                self.read_version(key, &metadata, Some(range))
+            })
+            .collect::<Result<_, _>>()?;
+        match values {
+            Some(values) => {
+                for (path, value) in paths.iter().zip(&values) {
+                    self.cache.write(path, value);
+                }
+                Ok(Some(values))
+            }
+            // The value changed since its metadata was retrieved, so it is read without caching it
+            None => self.storage.get_partial_values_key(key, byte_ranges),
+        }
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Ok(self.stat(key)?.map(|metadata| metadata.content_length()))
+    }
+}
+
+impl WritableStorageTraits for DiskCacheStorageAdapter {
+    // Cached entries of overwritten values are not removed, they become stale as the version of the value changes
+
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.storage.set(key, value)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix)
+    }
+}
+
+impl ListableStorageTraits for DiskCacheStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
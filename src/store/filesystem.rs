@@ -1,9 +1,24 @@
-use std::sync::Arc;
+use std::{
+    fs::File,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use pyo3::{exceptions::PyRuntimeError, pyclass, PyErr};
+use memmap2::Mmap;
+use pyo3::{exceptions::PyRuntimeError, pyclass, PyResult};
 use pyo3_stub_gen::derive::gen_stub_pyclass;
-use zarrs::{filesystem::FilesystemStore, storage::ReadableWritableListableStorage};
+use zarrs::{
+    filesystem::{FilesystemStore, FilesystemStoreOptions},
+    storage::{
+        byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+        ReadableWritableListableStorage, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+        StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+    },
+};
 
+use super::extract_byte_ranges;
 use crate::utils::PyErrExt;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -14,18 +29,143 @@ pub struct FilesystemStoreConfig {
     pub root: String,
 }
 
+/// Options applied to every filesystem store of a pipeline.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FilesystemOptions {
+    /// Read values by memory mapping their files.
+    pub(crate) memory_map: bool,
+    /// Bypass the page cache by opening files with `O_DIRECT`.
+    pub(crate) direct_io: bool,
+}
+
 impl FilesystemStoreConfig {
     pub fn new(root: String) -> Self {
         Self { root }
     }
+
+    pub(super) fn to_storage(
+        &self,
+        options: FilesystemOptions,
+    ) -> PyResult<ReadableWritableListableStorage> {
+        let mut store_options = FilesystemStoreOptions::default();
+        store_options.direct_io(options.direct_io);
+        let store = Arc::new(
+            FilesystemStore::new_with_options(self.root.clone(), store_options)
+                .map_py_err::<PyRuntimeError>()?,
+        );
+        if options.memory_map {
+            Ok(Arc::new(MemoryMappedFilesystemStore { store }))
+        } else {
+            Ok(store)
+        }
+    }
+}
+
+/// A filesystem store that reads values by memory mapping their files rather than copying them to the heap.
+///
+/// Retrieved bytes reference the mapped file, so a file that is truncated by another process while its bytes are in use raises `SIGBUS`.
+/// Values are set by replacing their files rather than truncating them, so the store does not truncate the files it maps itself.
+struct MemoryMappedFilesystemStore {
+    store: Arc<FilesystemStore>,
+}
+
+/// A counter making the names of the temporary files of [`MemoryMappedFilesystemStore::set`] unique within the process.
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+impl MemoryMappedFilesystemStore {
+    fn map(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let file = match File::open(self.store.key_to_fspath(key)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if file.metadata()?.len() == 0 {
+            return Ok(Some(Bytes::new()));
+        }
+        // SAFETY: The file must not be truncated while the map is alive.
+        // This store replaces files rather than truncating them, and truncation by other processes is documented as unsupported on `codec_pipeline.memory_map`.
+        let mmap = unsafe { Mmap::map(&file) }?;
+        Ok(Some(Bytes::from_owner(mmap)))
+    }
+}
+
+impl ReadableStorageTraits for MemoryMappedFilesystemStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.map(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.map(key)?
+            .map(|bytes| extract_byte_ranges(key, &bytes, byte_ranges))
+            .transpose()
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.store.size_key(key)
+    }
 }
 
-impl TryInto<ReadableWritableListableStorage> for &FilesystemStoreConfig {
-    type Error = PyErr;
+impl WritableStorageTraits for MemoryMappedFilesystemStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        // The value is written to a temporary file which replaces the file of the key,
+        // so mappings of the previous file keep its contents rather than being truncated
+        let path = self.store.key_to_fspath(key);
+        let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return self.store.set(key, value);
+        };
+        std::fs::create_dir_all(parent)?;
+        let temporary_path = parent.join(format!(
+            ".{}.{}.{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&temporary_path, &value)
+            .and_then(|()| std::fs::rename(&temporary_path, &path))
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&temporary_path);
+            })?;
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.store.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.store.erase(key)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.store.erase_prefix(prefix)
+    }
+}
+
+impl ListableStorageTraits for MemoryMappedFilesystemStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.store.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.store.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.store.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.store.size_prefix(prefix)
+    }
 
-    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
-        let store =
-            Arc::new(FilesystemStore::new(self.root.clone()).map_py_err::<PyRuntimeError>()?);
-        Ok(store)
+    fn size(&self) -> Result<u64, StorageError> {
+        self.store.size()
     }
 }
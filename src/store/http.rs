@@ -1,10 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use pyo3::{exceptions::PyValueError, pyclass, Bound, PyAny, PyErr, PyResult};
+use pyo3::{exceptions::PyValueError, pyclass, Bound, PyAny, PyResult};
 use pyo3_stub_gen::derive::gen_stub_pyclass;
-use zarrs::storage::ReadableWritableListableStorage;
-
-use super::opendal_builder_to_sync_store;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[gen_stub_pyclass]
@@ -34,13 +31,9 @@ impl HttpStoreConfig {
             endpoint: path.to_string(),
         })
     }
-}
-
-impl TryInto<ReadableWritableListableStorage> for &HttpStoreConfig {
-    type Error = PyErr;
 
-    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
-        let builder = opendal::services::Http::default().endpoint(&self.endpoint);
-        opendal_builder_to_sync_store(builder)
+    /// The configuration of the `OpenDAL` HTTP service.
+    pub(super) fn opendal_config(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([("endpoint".to_string(), self.endpoint.clone())])
     }
 }
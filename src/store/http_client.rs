@@ -0,0 +1,53 @@
+use std::{collections::HashMap, str::FromStr};
+
+use opendal::raw::HttpClient;
+use pyo3::{exceptions::PyValueError, types::PyAnyMethods, Bound, PyAny, PyResult};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+
+use crate::utils::PyErrExt;
+
+/// Create the HTTP client of remote stores from the `http_options` of a pipeline.
+///
+/// Supported options are `headers` (a dictionary of request headers), `bearer_token`, `user_agent`, and `proxy` (a proxy URL for all requests).
+pub(crate) fn http_client(
+    http_options: &HashMap<String, Bound<'_, PyAny>>,
+) -> PyResult<HttpClient> {
+    let mut headers = HeaderMap::new();
+    let mut builder = reqwest::Client::builder();
+    for (option, value) in http_options {
+        match option.as_str() {
+            "headers" => {
+                let value: HashMap<String, String> = value.extract()?;
+                for (name, value) in value {
+                    headers.insert(
+                        HeaderName::from_str(&name).map_py_err::<PyValueError>()?,
+                        HeaderValue::from_str(&value).map_py_err::<PyValueError>()?,
+                    );
+                }
+            }
+            "bearer_token" => {
+                let token: String = value.extract()?;
+                let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+                    .map_py_err::<PyValueError>()?;
+                value.set_sensitive(true);
+                headers.insert(AUTHORIZATION, value);
+            }
+            "user_agent" => builder = builder.user_agent(value.extract::<String>()?),
+            "proxy" => {
+                let proxy =
+                    reqwest::Proxy::all(value.extract::<String>()?).map_py_err::<PyValueError>()?;
+                builder = builder.proxy(proxy);
+            }
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported HTTP option: {option}"
+                )))
+            }
+        }
+    }
+    let client = builder
+        .default_headers(headers)
+        .build()
+        .map_py_err::<PyValueError>()?;
+    Ok(HttpClient::with(client))
+}
@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use pyo3::{exceptions::PyValueError, PyErr};
+use zarrs::storage::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, ReadableWritableListableStorage, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// A transformation of the chunk keys of a pipeline before they are passed to the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum KeyTransform {
+    /// Remove a prefix from keys that start with it.
+    StripPrefix(String),
+    /// Add a prefix to every key.
+    AddPrefix(String),
+    /// Prepend a directory named after the first `n` hexadecimal digits of a hash of the key.
+    HashDirectory(usize),
+    /// Map the default chunk key encoding (e.g. `c/0/0`) to the `v2` chunk key encoding with a separator (e.g. `0.0`).
+    V2ChunkKeys(String),
+}
+
+impl FromStr for KeyTransform {
+    type Err = PyErr;
+
+    fn from_str(transform: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match transform.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (transform, None),
+        };
+        match (name, argument) {
+            ("strip_prefix", Some(prefix)) => Ok(Self::StripPrefix(prefix.to_string())),
+            ("add_prefix", Some(prefix)) => Ok(Self::AddPrefix(prefix.to_string())),
+            ("hash_directory", Some(digits)) => match digits.parse() {
+                Ok(digits @ 1..=16) => Ok(Self::HashDirectory(digits)),
+                _ => Err(PyValueError::new_err(format!(
+                    "Unsupported key transform: {transform}, the number of hash_directory digits must be from 1 to 16"
+                ))),
+            },
+            ("v2_chunk_keys", separator) => {
+                Ok(Self::V2ChunkKeys(separator.unwrap_or(".").to_string()))
+            }
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported key transform: {transform}, expected strip_prefix:<prefix>, add_prefix:<prefix>, hash_directory:<digits>, or v2_chunk_keys[:<separator>]"
+            ))),
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which is stable across platforms and versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl KeyTransform {
+    fn apply(&self, key: String) -> String {
+        match self {
+            Self::StripPrefix(prefix) => match key.strip_prefix(prefix.as_str()) {
+                Some(suffix) => suffix.to_string(),
+                None => key,
+            },
+            Self::AddPrefix(prefix) => format!("{prefix}{key}"),
+            Self::HashDirectory(digits) => {
+                let hash = format!("{:016x}", fnv1a(key.as_bytes()));
+                format!("{}/{key}", &hash[..*digits])
+            }
+            Self::V2ChunkKeys(separator) => {
+                let segments: Vec<&str> = key.split('/').collect();
+                // The chunk grid indices follow the last `c` segment
+                let Some(position) = segments.iter().rposition(|&segment| segment == "c") else {
+                    return key;
+                };
+                let indices = &segments[position + 1..];
+                if !indices
+                    .iter()
+                    .all(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+                {
+                    return key;
+                }
+                let chunk = if indices.is_empty() {
+                    "0".to_string()
+                } else {
+                    indices.join(separator)
+                };
+                segments[..position]
+                    .iter()
+                    .copied()
+                    .chain([chunk.as_str()])
+                    .collect::<Vec<_>>()
+                    .join("/")
+            }
+        }
+    }
+}
+
+/// Transforms the keys of store operations with [`KeyTransform`]s, in order.
+///
+/// Listing keys and erasing prefixes fail, since transformed keys cannot always be mapped back (e.g. with a hash directory or a stripped prefix).
+pub(crate) struct KeyTransformStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    transforms: Vec<KeyTransform>,
+}
+
+impl KeyTransformStorageAdapter {
+    pub(crate) fn new(
+        storage: ReadableWritableListableStorage,
+        transforms: Vec<KeyTransform>,
+    ) -> Self {
+        Self {
+            storage,
+            transforms,
+        }
+    }
+
+    fn transform(&self, key: &StoreKey) -> Result<StoreKey, StorageError> {
+        let key = self
+            .transforms
+            .iter()
+            .fold(key.as_str().to_string(), |key, transform| {
+                transform.apply(key)
+            });
+        Ok(StoreKey::new(key)?)
+    }
+
+    fn unsupported(operation: &str) -> StorageError {
+        StorageError::Other(format!(
+            "{operation} is not supported with key transforms, as transformed keys cannot be mapped back to the keys of the store"
+        ))
+    }
+}
+
+impl ReadableStorageTraits for KeyTransformStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(&self.transform(key)?)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.storage
+            .get_partial_values_key(&self.transform(key)?, byte_ranges)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(&self.transform(key)?)
+    }
+}
+
+impl WritableStorageTraits for KeyTransformStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.storage.set(&self.transform(key)?, value)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(&self.transform(key)?)
+    }
+
+    fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        Err(Self::unsupported("erasing a prefix"))
+    }
+}
+
+impl ListableStorageTraits for KeyTransformStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Err(Self::unsupported("listing keys"))
+    }
+
+    fn list_prefix(&self, _prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Err(Self::unsupported("listing keys"))
+    }
+
+    fn list_dir(&self, _prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        Err(Self::unsupported("listing keys"))
+    }
+
+    fn size_prefix(&self, _prefix: &StorePrefix) -> Result<u64, StorageError> {
+        Err(Self::unsupported("the size of a prefix"))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
@@ -1,61 +1,391 @@
 use std::{
     collections::BTreeMap,
+    num::NonZeroUsize,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use pyo3::{exceptions::PyRuntimeError, PyResult};
+use opendal::raw::HttpClient;
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    PyResult,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use zarrs::{
-    array::codec::StoragePartialDecoder,
-    storage::{Bytes, MaybeBytes, ReadableWritableListableStorage, StorageHandle},
+    array::codec::{ArrayPartialDecoderTraits, StoragePartialDecoder, StoragePartialEncoder},
+    storage::{
+        Bytes, MaybeBytes, ReadableWritableListableStorage, StorageHandle, StoreKeyOffsetValue,
+        StoreKeys, StoreKeysPrefixes, StorePrefix,
+    },
 };
 
 use crate::{chunk_item::ChunksItem, store::PyErrExt as _};
 
-use super::StoreConfig;
+use super::{
+    cache::{CacheConfig, CacheStorageAdapter},
+    decoder_cache::PartialDecoderCache,
+    disk_cache::DiskCache,
+    filesystem::FilesystemOptions,
+    key_transform::{KeyTransform, KeyTransformStorageAdapter},
+    multipart::MultipartConfig,
+    read_only::ReadOnlyStorageAdapter,
+    retry::{RetryConfig, RetryStorageAdapter},
+    stats::{StatisticsStorageAdapter, StoreStatistics, StoreStats},
+    timeout::{storage_err_to_py_err, TimeoutStorageAdapter},
+    transformer::{PerformanceMetrics, StorageTransformers},
+    StoreConfig,
+};
 
+/// Options applied to every store created by a [`StoreManager`].
 #[derive(Default)]
-pub(crate) struct StoreManager(Mutex<BTreeMap<StoreConfig, ReadableWritableListableStorage>>);
+pub(crate) struct StoreManagerConfig {
+    /// If set, this store is used for every chunk instead of the store of the chunk item.
+    pub(crate) store_config_override: Option<StoreConfig>,
+    /// Options overriding the `OpenDAL` service configuration of remote stores.
+    pub(crate) storage_options: BTreeMap<String, String>,
+    /// If set, the HTTP client of remote stores (e.g. with custom headers or a proxy).
+    pub(crate) http_client: Option<HttpClient>,
+    /// If set, large values are uploaded to object stores in parts.
+    pub(crate) multipart: Option<MultipartConfig>,
+    /// If set, values of remote stores are cached in a local directory.
+    pub(crate) disk_cache: Option<Arc<DiskCache>>,
+    /// Transformations of chunk keys, applied in order before they are passed to the store.
+    pub(crate) key_transforms: Vec<KeyTransform>,
+    /// If true, every store rejects writes.
+    pub(crate) read_only: bool,
+    /// Options of filesystem stores.
+    pub(crate) filesystem: FilesystemOptions,
+    /// If set, store operations that do not complete within this duration fail.
+    pub(crate) timeout: Option<Duration>,
+    /// If set, failed store operations are retried.
+    pub(crate) retry: Option<RetryConfig>,
+    /// If set, encoded values are cached between the store and the codec chain.
+    pub(crate) cache: Option<CacheConfig>,
+    /// Storage transformers applied on top of every store (and its cache).
+    pub(crate) storage_transformers: StorageTransformers,
+    /// If set, the partial decoders of up to this many chunks (e.g. shards, with their decoded indices) are cached.
+    ///
+    /// Only writes through this store manager invalidate cached decoders, so the cache assumes it is the only writer of the chunks it reads.
+    pub(crate) partial_decoder_cache_max_entries: Option<NonZeroUsize>,
+}
+
+#[derive(Default)]
+pub(crate) struct StoreManager {
+    stores: Mutex<BTreeMap<StoreConfig, ReadableWritableListableStorage>>,
+    config: StoreManagerConfig,
+    statistics: Arc<StoreStatistics>,
+    partial_decoders: Option<PartialDecoderCache>,
+}
 
 impl StoreManager {
+    pub(crate) fn new(config: StoreManagerConfig) -> Self {
+        Self {
+            stores: Mutex::default(),
+            partial_decoders: config
+                .partial_decoder_cache_max_entries
+                .map(PartialDecoderCache::new),
+            config,
+            statistics: Arc::default(),
+        }
+    }
+
+    fn create_store(
+        &self,
+        store_config: &StoreConfig,
+    ) -> PyResult<ReadableWritableListableStorage> {
+        let mut store = store_config.to_storage(&self.config)?;
+        if !self.config.key_transforms.is_empty() {
+            store = Arc::new(KeyTransformStorageAdapter::new(
+                store,
+                self.config.key_transforms.clone(),
+            ));
+        }
+        if self.config.read_only {
+            store = Arc::new(ReadOnlyStorageAdapter::new(store));
+        }
+        if let Some(timeout) = self.config.timeout {
+            store = Arc::new(TimeoutStorageAdapter::new(store, timeout));
+        }
+        if let Some(retry) = self.config.retry {
+            store = Arc::new(RetryStorageAdapter::new(store, retry));
+        }
+        // Statistics are recorded below the cache, so cache hits are not counted as store reads
+        store = Arc::new(StatisticsStorageAdapter::new(
+            store,
+            self.statistics.clone(),
+        ));
+        if let Some(cache) = self.config.cache {
+            store = Arc::new(CacheStorageAdapter::new(
+                store,
+                cache,
+                self.statistics.clone(),
+            ));
+        }
+        Ok(self.config.storage_transformers.apply(store))
+    }
+
+    pub(crate) fn stats(&self) -> StoreStats {
+        self.statistics.stats()
+    }
+
+    pub(crate) fn reset_stats(&self) {
+        self.statistics.reset();
+    }
+
+    /// Record the encoding of a chunk of `bytes_decoded` bytes to `bytes_encoded` bytes.
+    pub(crate) fn record_encode(&self, bytes_decoded: usize, bytes_encoded: usize) {
+        self.statistics.record_encode(bytes_decoded, bytes_encoded);
+    }
+
+    pub(crate) fn performance_metrics(&self) -> Option<PerformanceMetrics> {
+        self.config.storage_transformers.performance_metrics()
+    }
+
+    fn store_config<I: ChunksItem>(&self, item: &I) -> StoreConfig {
+        self.config
+            .store_config_override
+            .clone()
+            .unwrap_or_else(|| item.store_config())
+    }
+
     fn store<I: ChunksItem>(&self, item: &I) -> PyResult<ReadableWritableListableStorage> {
+        self.store_from_config(self.store_config(item))
+    }
+
+    /// Remove the cached partial decoder of the chunk of an item, after it is written.
+    ///
+    /// The decoder is invalidated whether or not the write succeeded, as a failed write may have changed the chunk.
+    fn invalidate_partial_decoder<I: ChunksItem, T>(
+        &self,
+        item: &I,
+        written: PyResult<T>,
+    ) -> PyResult<T> {
+        if let Some(partial_decoders) = &self.partial_decoders {
+            partial_decoders.invalidate(&self.store_config(item), |key| key == item.key())?;
+        }
+        written
+    }
+
+    /// Evict the stores of zarr-python stores which have been dropped (e.g. `MemoryStore`s), so they do not accumulate.
+    ///
+    /// Checking whether a store has been dropped may acquire the GIL, so it is not done while holding the lock of the stores.
+    fn evict_dropped_stores(&self) -> PyResult<()> {
+        let store_configs: Vec<StoreConfig> = self
+            .stores
+            .lock()
+            .map_py_err::<PyRuntimeError>()?
+            .keys()
+            .cloned()
+            .collect();
+        let dropped: Vec<StoreConfig> = store_configs
+            .into_iter()
+            .filter(StoreConfig::is_dropped)
+            .collect();
+        if !dropped.is_empty() {
+            let mut stores = self.stores.lock().map_py_err::<PyRuntimeError>()?;
+            for store_config in &dropped {
+                stores.remove(store_config);
+            }
+        }
+        Ok(())
+    }
+
+    fn store_from_config(
+        &self,
+        store_config: StoreConfig,
+    ) -> PyResult<ReadableWritableListableStorage> {
         use std::collections::btree_map::Entry::{Occupied, Vacant};
+        let store = self
+            .stores
+            .lock()
+            .map_py_err::<PyRuntimeError>()?
+            .get(&store_config)
+            .cloned();
+        if let Some(store) = store {
+            return Ok(store);
+        }
+        self.evict_dropped_stores()?;
         match self
-            .0
+            .stores
             .lock()
             .map_py_err::<PyRuntimeError>()?
-            .entry(item.store_config())
+            .entry(store_config)
         {
             Occupied(e) => Ok(e.get().clone()),
-            Vacant(e) => Ok(e.insert((&item.store_config()).try_into()?).clone()),
+            Vacant(e) => {
+                let store = self.create_store(e.key())?;
+                Ok(e.insert(store).clone())
+            }
+        }
+    }
+
+    /// The store that chunks of arrays in a zarr-python store are read from/written to.
+    fn store_of(&self, store_config: StoreConfig) -> PyResult<ReadableWritableListableStorage> {
+        self.store_from_config(
+            self.config
+                .store_config_override
+                .clone()
+                .unwrap_or(store_config),
+        )
+    }
+
+    pub(crate) fn list(&self, store_config: StoreConfig) -> PyResult<StoreKeys> {
+        self.store_of(store_config)?
+            .list()
+            .map_err(storage_err_to_py_err::<PyRuntimeError>)
+    }
+
+    pub(crate) fn list_prefix(
+        &self,
+        store_config: StoreConfig,
+        prefix: &StorePrefix,
+    ) -> PyResult<StoreKeys> {
+        self.store_of(store_config)?
+            .list_prefix(prefix)
+            .map_err(storage_err_to_py_err::<PyRuntimeError>)
+    }
+
+    pub(crate) fn list_dir(
+        &self,
+        store_config: StoreConfig,
+        prefix: &StorePrefix,
+    ) -> PyResult<StoreKeysPrefixes> {
+        self.store_of(store_config)?
+            .list_dir(prefix)
+            .map_err(storage_err_to_py_err::<PyRuntimeError>)
+    }
+
+    /// Erase every key under a prefix, erasing the keys in parallel on the current thread pool.
+    ///
+    /// Fails with key transforms, as the listed keys of the store would be transformed again when erased.
+    pub(crate) fn erase_prefix(
+        &self,
+        store_config: StoreConfig,
+        prefix: &StorePrefix,
+    ) -> PyResult<()> {
+        if !self.config.key_transforms.is_empty() {
+            return Err(PyValueError::new_err(
+                "cannot erase a prefix with key_transforms, as transformed keys cannot be mapped back to the keys of the store",
+            ));
+        }
+        let store = self.store_of(store_config.clone())?;
+        let erased = store
+            .list_prefix(prefix)
+            .and_then(|keys| keys.into_par_iter().try_for_each(|key| store.erase(&key)))
+            // Remove anything left under the prefix (e.g. empty directories)
+            .and_then(|()| store.erase_prefix(prefix))
+            .map_err(storage_err_to_py_err::<PyRuntimeError>);
+        if let Some(partial_decoders) = &self.partial_decoders {
+            let store_config = self
+                .config
+                .store_config_override
+                .clone()
+                .unwrap_or(store_config);
+            partial_decoders.invalidate(&store_config, |key| {
+                key.as_str().starts_with(prefix.as_str())
+            })?;
         }
+        erased
     }
 
     pub(crate) fn get<I: ChunksItem>(&self, item: &I) -> PyResult<MaybeBytes> {
         self.store(item)?
             .get(item.key())
-            .map_py_err::<PyRuntimeError>()
+            .map_err(storage_err_to_py_err::<PyRuntimeError>)
     }
 
-    pub(crate) fn set<I: ChunksItem>(&self, item: &I, value: Bytes) -> PyResult<()> {
+    /// Whether the chunk of an item is stored.
+    pub(crate) fn exists<I: ChunksItem>(&self, item: &I) -> PyResult<bool> {
+        Ok(self.size(item)?.is_some())
+    }
+
+    /// The size in bytes of the stored chunk of an item, or `None` if it is not stored.
+    pub(crate) fn size<I: ChunksItem>(&self, item: &I) -> PyResult<Option<u64>> {
         self.store(item)?
+            .size_key(item.key())
+            .map_err(storage_err_to_py_err::<PyRuntimeError>)
+    }
+
+    /// Whether the store of an item writes values at byte offsets of a key in place.
+    ///
+    /// Other stores, and the key transform and timeout adapters, read and rewrite the whole value instead.
+    pub(crate) fn writes_partial_values<I: ChunksItem>(&self, item: &I) -> bool {
+        matches!(self.store_config(item), StoreConfig::Filesystem(_))
+            && self.config.key_transforms.is_empty()
+            && self.config.timeout.is_none()
+    }
+
+    pub(crate) fn set<I: ChunksItem>(&self, item: &I, value: Bytes) -> PyResult<()> {
+        let written = self
+            .store(item)?
             .set(item.key(), value)
-            .map_py_err::<PyRuntimeError>()
+            .map_err(storage_err_to_py_err::<PyRuntimeError>);
+        self.invalidate_partial_decoder(item, written)
+    }
+
+    /// Write values at byte offsets of the stored chunk of an item.
+    pub(crate) fn set_partial_values<I: ChunksItem>(
+        &self,
+        item: &I,
+        offset_values: &[(u64, &[u8])],
+    ) -> PyResult<()> {
+        let key_offset_values: Vec<_> = offset_values
+            .iter()
+            .map(|&(offset, value)| StoreKeyOffsetValue::new(item.key().clone(), offset, value))
+            .collect();
+        let written = self
+            .store(item)?
+            .set_partial_values(&key_offset_values)
+            .map_err(storage_err_to_py_err::<PyRuntimeError>);
+        self.invalidate_partial_decoder(item, written)
     }
 
     pub(crate) fn erase<I: ChunksItem>(&self, item: &I) -> PyResult<()> {
-        self.store(item)?
+        let erased = self
+            .store(item)?
             .erase(item.key())
-            .map_py_err::<PyRuntimeError>()
+            .map_err(storage_err_to_py_err::<PyRuntimeError>);
+        self.invalidate_partial_decoder(item, erased)
     }
 
     pub(crate) fn decoder<I: ChunksItem>(&self, item: &I) -> PyResult<StoragePartialDecoder> {
         // Partially decode the chunk into the output buffer
+        // NOTE: zarr-python does not forward the storage transformers of the array metadata to the codec pipeline,
+        // the storage transformers configured on the pipeline are applied by the store instead
         let storage_handle = Arc::new(StorageHandle::new(self.store(item)?));
-        // NOTE: Normally a storage transformer would exist between the storage handle and the input handle
-        // but zarr-python does not support them nor forward them to the codec pipeline
         Ok(StoragePartialDecoder::new(
             storage_handle,
             item.key().clone(),
         ))
     }
+
+    /// The partial decoder of the chunk of an item, which is created by `create` from a decoder of the stored chunk unless it is cached.
+    pub(crate) fn partial_decoder<I: ChunksItem>(
+        &self,
+        item: &I,
+        create: impl FnOnce(StoragePartialDecoder) -> PyResult<Arc<dyn ArrayPartialDecoderTraits>>,
+    ) -> PyResult<Arc<dyn ArrayPartialDecoderTraits>> {
+        match &self.partial_decoders {
+            Some(partial_decoders) => {
+                partial_decoders.get_or_try_insert(self.store_config(item), item.key(), || {
+                    create(self.decoder(item)?)
+                })
+            }
+            None => create(self.decoder(item)?),
+        }
+    }
+
+    /// Partially encode the chunk of an item with `encode`, given an encoder writing only the byte ranges that change (e.g. inner chunks and the shard index).
+    pub(crate) fn partial_encode<I: ChunksItem, T>(
+        &self,
+        item: &I,
+        encode: impl FnOnce(StoragePartialEncoder) -> PyResult<T>,
+    ) -> PyResult<T> {
+        let storage_handle = Arc::new(StorageHandle::new(self.store(item)?));
+        let encoded = encode(StoragePartialEncoder::new(
+            storage_handle,
+            item.key().clone(),
+        ));
+        self.invalidate_partial_decoder(item, encoded)
+    }
 }
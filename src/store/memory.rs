@@ -0,0 +1,226 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use pyo3::{
+    exceptions::PyRuntimeError,
+    pyclass,
+    types::{
+        PyAnyMethods, PyBytes, PyBytesMethods, PyDict, PyDictMethods, PyModule, PyWeakrefMethods,
+        PyWeakrefReference,
+    },
+    Bound, Py, PyAny, PyErr, PyResult, Python,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, ReadableWritableListableStorage, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+use super::{extract_byte_ranges, keys_to_keys_prefixes, py_err_to_storage_err};
+
+/// A zarr-python `MemoryStore`, identified by a weak reference to it and whether it is read-only.
+///
+/// The store is only weakly referenced, so the stores created from this config do not keep it (and its dictionary) alive.
+/// Python returns the same weak reference for every config of a store, and a weak reference is not freed while a config holds it, so it identifies the store.
+#[derive(Debug, Clone)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct MemoryStoreConfig {
+    store: Arc<Py<PyWeakrefReference>>,
+    #[pyo3(get)]
+    pub read_only: bool,
+}
+
+impl MemoryStoreConfig {
+    pub fn new(store: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            store: Arc::new(PyWeakrefReference::new(store)?.unbind()),
+            read_only: store.getattr("read_only")?.extract()?,
+        })
+    }
+
+    fn id(&self) -> (usize, bool) {
+        (self.store.as_ptr() as usize, self.read_only)
+    }
+
+    /// Whether the `MemoryStore` has been dropped.
+    pub(crate) fn is_dropped(&self) -> bool {
+        Python::with_gil(|py| self.store.bind(py).upgrade().is_none())
+    }
+}
+
+impl Hash for MemoryStoreConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl PartialEq for MemoryStoreConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for MemoryStoreConfig {}
+
+impl PartialOrd for MemoryStoreConfig {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MemoryStoreConfig {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id().cmp(&other.id())
+    }
+}
+
+impl TryInto<ReadableWritableListableStorage> for &MemoryStoreConfig {
+    type Error = PyErr;
+
+    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
+        Ok(Arc::new(PyMemoryStore {
+            store: self.store.clone(),
+            read_only: self.read_only,
+        }))
+    }
+}
+
+/// A store that reads and writes the dictionary of a zarr-python `MemoryStore` directly.
+///
+/// The GIL is only held while the dictionary is accessed. Accesses fail once the `MemoryStore` has been dropped.
+struct PyMemoryStore {
+    store: Arc<Py<PyWeakrefReference>>,
+    read_only: bool,
+}
+
+impl PyMemoryStore {
+    fn with_dict<T>(
+        &self,
+        f: impl FnOnce(Python<'_>, &Bound<'_, PyDict>) -> PyResult<T>,
+    ) -> Result<T, StorageError> {
+        Python::with_gil(|py| {
+            let store = self
+                .store
+                .bind(py)
+                .upgrade()
+                .ok_or_else(|| PyRuntimeError::new_err("the MemoryStore has been dropped"))?;
+            let store_dict = store.getattr("_store_dict")?.downcast_into::<PyDict>()?;
+            f(py, &store_dict)
+        })
+        .map_err(py_err_to_storage_err)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, StorageError> {
+        self.with_dict(|_py, store_dict| {
+            store_dict
+                .keys()
+                .iter()
+                .map(|key| key.extract::<String>())
+                .collect()
+        })
+    }
+}
+
+impl ReadableStorageTraits for PyMemoryStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.with_dict(|_py, store_dict| {
+            let Some(buffer) = store_dict.get_item(key.as_str())? else {
+                return Ok(None);
+            };
+            let bytes = buffer.call_method0("to_bytes")?;
+            let bytes = bytes.downcast::<PyBytes>()?;
+            Ok(Some(Bytes::copy_from_slice(bytes.as_bytes())))
+        })
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.get(key)?
+            .map(|bytes| extract_byte_ranges(key, &bytes, byte_ranges))
+            .transpose()
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Ok(self.get(key)?.map(|bytes| bytes.len() as u64))
+    }
+}
+
+impl WritableStorageTraits for PyMemoryStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        if self.read_only {
+            return Err(StorageError::ReadOnly);
+        }
+        self.with_dict(|py, store_dict| {
+            let buffer = PyModule::import(py, "zarr.core.buffer.cpu")?
+                .getattr("Buffer")?
+                .call_method1("from_bytes", (PyBytes::new(py, &value),))?;
+            store_dict.set_item(key.as_str(), buffer)
+        })
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.read_only {
+            return Err(StorageError::ReadOnly);
+        }
+        self.with_dict(|_py, store_dict| {
+            if store_dict.contains(key.as_str())? {
+                store_dict.del_item(key.as_str())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        for key in self.list_prefix(prefix)? {
+            self.erase(&key)?;
+        }
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for PyMemoryStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = self
+            .keys()?
+            .into_iter()
+            .filter(|key| key.starts_with(prefix.as_str()))
+            .map(StoreKey::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        keys_to_keys_prefixes(self.keys()?, prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.size_prefix(&StorePrefix::root())
+    }
+}
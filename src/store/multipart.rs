@@ -0,0 +1,125 @@
+use zarrs::storage::{
+    byte_range::ByteRange, storage_adapter::async_to_sync::AsyncToSyncBlockOn, Bytes,
+    ListableStorageTraits, MaybeBytes, ReadableStorageTraits, ReadableWritableListableStorage,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+
+use crate::runtime::tokio_block_on;
+
+/// The minimum part size of multipart uploads, which is the minimum size of all but the last part of an S3 multipart upload.
+pub(crate) const MULTIPART_PART_SIZE_MINIMUM: usize = 5 * 1024 * 1024;
+
+/// Options of multipart uploads of large values to object stores.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MultipartConfig {
+    /// Values larger than this are uploaded in parts of this size.
+    pub(crate) part_size: usize,
+    /// The maximum number of parts of a value uploaded concurrently.
+    pub(crate) concurrency: usize,
+}
+
+/// Uploads values larger than the part size of an `OpenDAL` store in parts (e.g. an S3 multipart upload), rather than in a single request.
+pub(crate) struct MultipartStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    operator: opendal::Operator,
+    config: MultipartConfig,
+}
+
+impl MultipartStorageAdapter {
+    pub(crate) fn new(
+        storage: ReadableWritableListableStorage,
+        operator: opendal::Operator,
+        config: MultipartConfig,
+    ) -> Self {
+        Self {
+            storage,
+            operator,
+            config,
+        }
+    }
+}
+
+impl ReadableStorageTraits for MultipartStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+}
+
+impl WritableStorageTraits for MultipartStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        if value.len() <= self.config.part_size {
+            return self.storage.set(key, value);
+        }
+        tokio_block_on()
+            .block_on(async {
+                let mut writer = self
+                    .operator
+                    .writer_with(key.as_str())
+                    .chunk(self.config.part_size)
+                    .concurrent(self.config.concurrency)
+                    .await?;
+                let result = async {
+                    writer.write(value).await?;
+                    writer.close().await?;
+                    Ok::<_, opendal::Error>(())
+                }
+                .await;
+                if result.is_err() {
+                    // Abort the upload so its parts are not left in the object store, the error of the upload is returned
+                    let _ = writer.abort().await;
+                }
+                result
+            })
+            .map_err(|err| StorageError::Other(err.to_string()))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix)
+    }
+}
+
+impl ListableStorageTraits for MultipartStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
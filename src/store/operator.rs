@@ -0,0 +1,40 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use pyo3::{exceptions::PyValueError, pyclass, PyResult};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::ReadableWritableListableStorage;
+
+use super::{opendal_config_to_sync_store, StoreManagerConfig};
+use crate::utils::PyErrExt;
+
+/// A store backed by any [OpenDAL](https://opendal.apache.org/) service.
+///
+/// The `scheme` selects the service (e.g. `s3`, `gcs`, `azblob`, `webdav`, `redis`) and the remaining options are passed through as the service configuration.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct OpendalStoreConfig {
+    #[pyo3(get, set)]
+    pub scheme: String,
+    #[pyo3(get, set)]
+    pub options: BTreeMap<String, String>,
+}
+
+impl OpendalStoreConfig {
+    pub fn new(mut options: BTreeMap<String, String>) -> PyResult<Self> {
+        let Some(scheme) = options.remove("scheme") else {
+            return Err(PyValueError::new_err(
+                "storage_options must include an OpenDAL scheme",
+            ));
+        };
+        Ok(Self { scheme, options })
+    }
+
+    pub(super) fn to_storage(
+        &self,
+        manager_config: &StoreManagerConfig,
+    ) -> PyResult<ReadableWritableListableStorage> {
+        let scheme = opendal::Scheme::from_str(&self.scheme).map_py_err::<PyValueError>()?;
+        opendal_config_to_sync_store(scheme, self.options.clone(), manager_config)
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::BTreeSet,
+    ffi::CString,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use pyo3::{
+    exceptions::{PyFileNotFoundError, PyUserWarning},
+    pyclass,
+    types::{PyAnyMethods, PyBytes, PyBytesMethods, PyModule, PyStringMethods, PyTypeMethods},
+    Bound, Py, PyAny, PyErr, PyResult, PyTypeInfo, Python,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, ReadableWritableListableStorage, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+use super::{keys_to_keys_prefixes, py_err_to_storage_err};
+
+/// An arbitrary zarr-python `Store` without a native equivalent, identified by the store object.
+#[derive(Debug, Clone)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PythonStoreConfig {
+    store: Arc<Py<PyAny>>,
+    #[pyo3(get)]
+    pub name: String,
+}
+
+/// The names of the stores which have been warned about falling back to their Python methods.
+static WARNED_STORES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+impl PythonStoreConfig {
+    /// Create the config of a store, warning (once for each type of store) that it falls back to its Python methods.
+    pub fn new(store: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let name = store.get_type().name().map_or_else(
+            |_| String::from("Store"),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let first_use = match WARNED_STORES.lock() {
+            Ok(mut warned) => warned.insert(name.clone()),
+            Err(_) => true,
+        };
+        if first_use {
+            let message = CString::new(format!(
+                "{name} has no native zarrs equivalent, so its chunks are read and written through the zarr-python Store methods, which is slower"
+            ))?;
+            PyErr::warn(
+                store.py(),
+                PyUserWarning::type_object(store.py()).as_any(),
+                &message,
+                1,
+            )?;
+        }
+        Ok(Self {
+            store: Arc::new(store.clone().unbind()),
+            name,
+        })
+    }
+
+    fn id(&self) -> usize {
+        self.store.as_ptr() as usize
+    }
+}
+
+impl Hash for PythonStoreConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl PartialEq for PythonStoreConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for PythonStoreConfig {}
+
+impl PartialOrd for PythonStoreConfig {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PythonStoreConfig {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id().cmp(&other.id())
+    }
+}
+
+impl TryInto<ReadableWritableListableStorage> for &PythonStoreConfig {
+    type Error = PyErr;
+
+    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
+        Ok(Arc::new(PyObjectStoreBridge {
+            store: self.store.clone(),
+        }))
+    }
+}
+
+/// A store that calls back into the async methods of a zarr-python `Store`.
+///
+/// Each operation acquires the GIL and runs the store coroutine to completion with `zarr.core.sync.sync`.
+/// The GIL is released while waiting on the zarr-python event loop, so the store may be accessed concurrently.
+/// Byte ranges (e.g. of shards) are requested with zarr-python `ByteRequest`s rather than by retrieving whole values.
+struct PyObjectStoreBridge {
+    store: Arc<Py<PyAny>>,
+}
+
+/// Run a store coroutine to completion on the zarr-python event loop.
+fn sync<'py>(py: Python<'py>, coroutine: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    PyModule::import(py, "zarr.core.sync")?
+        .getattr("sync")?
+        .call1((coroutine,))
+}
+
+impl PyObjectStoreBridge {
+    fn call<T>(
+        &self,
+        f: impl for<'py> FnOnce(Python<'py>, &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>>,
+        extract: impl for<'py> FnOnce(&Bound<'py, PyAny>) -> PyResult<T>,
+    ) -> Result<T, StorageError> {
+        Python::with_gil(|py| {
+            let coroutine = f(py, self.store.bind(py))?;
+            extract(&sync(py, coroutine)?)
+        })
+        .map_err(py_err_to_storage_err)
+    }
+
+    fn list_prefix_keys(&self, prefix: &StorePrefix) -> Result<Vec<String>, StorageError> {
+        self.call(
+            |py, store| {
+                let keys = store.call_method1("list_prefix", (prefix.as_str(),))?;
+                PyModule::import(py, "zarr.core.sync")?
+                    .getattr("_collect_aiterator")?
+                    .call1((keys,))
+            },
+            |keys| keys.extract(),
+        )
+    }
+}
+
+fn default_buffer_prototype(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    PyModule::import(py, "zarr.core.buffer")?
+        .getattr("default_buffer_prototype")?
+        .call0()
+}
+
+/// Convert a byte range to the equivalent zarr-python `ByteRequest`.
+fn byte_request<'py>(py: Python<'py>, byte_range: &ByteRange) -> PyResult<Bound<'py, PyAny>> {
+    let store_module = PyModule::import(py, "zarr.abc.store")?;
+    match *byte_range {
+        ByteRange::FromStart(offset, None) => {
+            store_module.getattr("OffsetByteRequest")?.call1((offset,))
+        }
+        ByteRange::FromStart(offset, Some(length)) => store_module
+            .getattr("RangeByteRequest")?
+            .call1((offset, offset + length)),
+        ByteRange::Suffix(length) => store_module.getattr("SuffixByteRequest")?.call1((length,)),
+    }
+}
+
+fn buffer_to_bytes(buffer: &Bound<'_, PyAny>) -> PyResult<Bytes> {
+    let bytes = buffer.call_method0("to_bytes")?;
+    let bytes = bytes.downcast::<PyBytes>()?;
+    Ok(Bytes::copy_from_slice(bytes.as_bytes()))
+}
+
+impl ReadableStorageTraits for PyObjectStoreBridge {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.call(
+            |py, store| store.call_method1("get", (key.as_str(), default_buffer_prototype(py)?)),
+            |buffer| {
+                if buffer.is_none() {
+                    return Ok(None);
+                }
+                buffer_to_bytes(buffer).map(Some)
+            },
+        )
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        if byte_ranges.is_empty() {
+            return Ok(self.size_key(key)?.map(|_| Vec::new()));
+        }
+        self.call(
+            |py, store| {
+                let key_ranges = byte_ranges
+                    .iter()
+                    .map(|byte_range| Ok((key.as_str(), byte_request(py, byte_range)?)))
+                    .collect::<PyResult<Vec<_>>>()?;
+                store.call_method1(
+                    "get_partial_values",
+                    (default_buffer_prototype(py)?, key_ranges),
+                )
+            },
+            |buffers| {
+                // The buffers of a missing key are None
+                let buffers: Vec<Bound<'_, PyAny>> = buffers.extract()?;
+                if buffers.iter().any(|buffer| buffer.is_none()) {
+                    return Ok(None);
+                }
+                buffers
+                    .iter()
+                    .map(buffer_to_bytes)
+                    .collect::<PyResult<_>>()
+                    .map(Some)
+            },
+        )
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Python::with_gil(|py| {
+            let coroutine = self
+                .store
+                .bind(py)
+                .call_method1("getsize", (key.as_str(),))?;
+            match sync(py, coroutine) {
+                Ok(size) => size.extract().map(Some),
+                Err(err) if err.is_instance_of::<PyFileNotFoundError>(py) => Ok(None),
+                Err(err) => Err(err),
+            }
+        })
+        .map_err(py_err_to_storage_err)
+    }
+}
+
+impl WritableStorageTraits for PyObjectStoreBridge {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.call(
+            |py, store| {
+                let buffer = default_buffer_prototype(py)?
+                    .getattr("buffer")?
+                    .call_method1("from_bytes", (PyBytes::new(py, &value),))?;
+                store.call_method1("set", (key.as_str(), buffer))
+            },
+            |_| Ok(()),
+        )
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.call(
+            |_py, store| store.call_method1("delete", (key.as_str(),)),
+            |_| Ok(()),
+        )
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.call(
+            |_py, store| store.call_method1("delete_dir", (prefix.as_str(),)),
+            |_| Ok(()),
+        )
+    }
+}
+
+impl ListableStorageTraits for PyObjectStoreBridge {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = self
+            .list_prefix_keys(prefix)?
+            .into_iter()
+            .map(StoreKey::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        keys_to_keys_prefixes(self.list_prefix_keys(prefix)?, prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.size_prefix(&StorePrefix::root())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    time::Duration,
+};
+
+use zarrs::storage::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    ReadableWritableListableStorage, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// A retry policy with exponential backoff.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryConfig {
+    /// The maximum number of attempts of each operation, including the first.
+    pub(crate) max_attempts: usize,
+    /// The delay before the first retry, which doubles with each subsequent retry.
+    pub(crate) base_delay: Duration,
+    /// The fraction (in `[0, 1]`) of each delay that is randomised.
+    pub(crate) jitter: f64,
+}
+
+impl RetryConfig {
+    fn delay(&self, retry: usize) -> Duration {
+        let factor = 2u32.saturating_pow(u32::try_from(retry).unwrap_or(u32::MAX));
+        let delay = self.base_delay.saturating_mul(factor);
+        // A random number in [0, 1) without depending on a random number generator
+        #[allow(clippy::cast_precision_loss)]
+        let random = (RandomState::new().hash_one(retry) >> 11) as f64 / (1u64 << 53) as f64;
+        delay.mul_f64(1.0 - self.jitter.clamp(0.0, 1.0) * random)
+    }
+
+    /// The equivalent retry layer of an `OpenDAL` operator, which only retries temporary errors (e.g. HTTP 5xx responses).
+    pub(crate) fn opendal_layer(&self) -> opendal::layers::RetryLayer {
+        let layer = opendal::layers::RetryLayer::new()
+            .with_max_times(self.max_attempts.saturating_sub(1))
+            .with_min_delay(self.base_delay)
+            .with_factor(2.0);
+        if self.jitter > 0.0 {
+            layer.with_jitter()
+        } else {
+            layer
+        }
+    }
+}
+
+/// Errors that may succeed if the operation is retried, such as I/O errors (including timeouts and Python `OSError`s).
+///
+/// The errors of `OpenDAL` stores are converted to strings by `zarrs_opendal`, so they cannot be classified here.
+/// Instead, their operators retry temporary errors with [`RetryConfig::opendal_layer`], and other errors are not retried.
+fn is_transient(err: &StorageError) -> bool {
+    matches!(err, StorageError::IOError(_))
+}
+
+/// Retries the operations of a store that fail with a transient error.
+pub(crate) struct RetryStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    config: RetryConfig,
+}
+
+impl RetryStorageAdapter {
+    pub(crate) fn new(storage: ReadableWritableListableStorage, config: RetryConfig) -> Self {
+        Self { storage, config }
+    }
+
+    fn retry<T>(&self, f: impl Fn() -> Result<T, StorageError>) -> Result<T, StorageError> {
+        let mut retry = 0;
+        loop {
+            match f() {
+                Err(err) if is_transient(&err) && retry + 1 < self.config.max_attempts => {
+                    std::thread::sleep(self.config.delay(retry));
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl ReadableStorageTraits for RetryStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.retry(|| self.storage.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.retry(|| self.storage.get_partial_values_key(key, byte_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.retry(|| self.storage.size_key(key))
+    }
+}
+
+impl WritableStorageTraits for RetryStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.retry(|| self.storage.set(key, value.clone()))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.retry(|| self.storage.set_partial_values(key_offset_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.retry(|| self.storage.erase(key))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.retry(|| self.storage.erase_prefix(prefix))
+    }
+}
+
+impl ListableStorageTraits for RetryStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.retry(|| self.storage.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.retry(|| self.storage.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.retry(|| self.storage.list_dir(prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.retry(|| self.storage.size_prefix(prefix))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.retry(|| self.storage.size())
+    }
+}
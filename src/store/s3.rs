@@ -0,0 +1,295 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use pyo3::{
+    exceptions::PyValueError,
+    pyclass,
+    types::{PyAnyMethods, PyDict, PyDictMethods},
+    Bound, PyAny, PyResult,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::{
+    byte_range::ByteRange, storage_adapter::async_to_sync::AsyncToSyncBlockOn, Bytes,
+    ListableStorageTraits, MaybeBytes, ReadableStorageTraits, ReadableWritableListableStorage,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+
+use super::{
+    check_storage_options, opendal_operator_to_sync_store, opendal_service_to_operator,
+    StoreManagerConfig,
+};
+use crate::runtime::tokio_block_on;
+
+#[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct S3StoreConfig {
+    #[pyo3(get, set)]
+    pub bucket: String,
+    #[pyo3(get, set)]
+    pub root: String,
+    #[pyo3(get, set)]
+    pub region: Option<String>,
+    #[pyo3(get, set)]
+    pub endpoint: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub session_token: Option<String>,
+    #[pyo3(get, set)]
+    pub anonymous: bool,
+}
+
+impl std::fmt::Debug for S3StoreConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Secrets are redacted, as store configs are included in error messages and logs
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("S3StoreConfig")
+            .field("bucket", &self.bucket)
+            .field("root", &self.root)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &redacted(&self.secret_access_key))
+            .field("session_token", &redacted(&self.session_token))
+            .field("anonymous", &self.anonymous)
+            .finish()
+    }
+}
+
+fn extract_option(value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    if value.is_none() {
+        Ok(None)
+    } else {
+        Ok(Some(value.extract()?))
+    }
+}
+
+impl S3StoreConfig {
+    pub fn new(path: &str, storage_options: &HashMap<String, Bound<'_, PyAny>>) -> PyResult<Self> {
+        // s3fs strips the protocol, so the path is of the form bucket/root
+        let path = path.strip_prefix("s3://").unwrap_or(path);
+        let (bucket, root) = path.split_once('/').unwrap_or((path, ""));
+        if bucket.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "S3 path {path} does not contain a bucket name"
+            )));
+        }
+
+        let mut config = Self {
+            bucket: bucket.to_string(),
+            root: format!("/{root}"),
+            region: None,
+            endpoint: None,
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            anonymous: false,
+        };
+
+        for (storage_option, value) in storage_options {
+            match storage_option.as_str() {
+                "anon" => config.anonymous = value.extract()?,
+                "key" => config.access_key_id = extract_option(value)?,
+                "secret" => config.secret_access_key = extract_option(value)?,
+                "token" => config.session_token = extract_option(value)?,
+                "endpoint_url" => config.endpoint = extract_option(value)?,
+                "client_kwargs" => {
+                    let client_kwargs = value.downcast::<PyDict>()?;
+                    for (client_kwarg, value) in client_kwargs.iter() {
+                        let client_kwarg: String = client_kwarg.extract()?;
+                        match client_kwarg.as_str() {
+                            "region_name" => config.region = extract_option(&value)?,
+                            "endpoint_url" => config.endpoint = extract_option(&value)?,
+                            _ => {
+                                return Err(PyValueError::new_err(format!(
+                                    "Unsupported client_kwargs option for S3FileSystem: {client_kwarg}"
+                                )));
+                            }
+                        }
+                    }
+                }
+                "asynchronous" => {}
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "Unsupported storage option for S3FileSystem: {storage_option}"
+                    )));
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The configuration of the `OpenDAL` S3 service.
+    pub(super) fn opendal_config(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::from([
+            ("bucket".to_string(), self.bucket.clone()),
+            ("root".to_string(), self.root.clone()),
+        ]);
+        let mut options = vec![("region", &self.region), ("endpoint", &self.endpoint)];
+        if self.anonymous {
+            for key in [
+                "allow_anonymous",
+                "disable_config_load",
+                "disable_ec2_metadata",
+            ] {
+                config.insert(key.to_string(), "true".to_string());
+            }
+        } else {
+            options.extend([
+                ("access_key_id", &self.access_key_id),
+                ("secret_access_key", &self.secret_access_key),
+                ("session_token", &self.session_token),
+            ]);
+        }
+        for (key, value) in options {
+            if let Some(value) = value {
+                config.insert(key.to_string(), value.clone());
+            }
+        }
+        config
+    }
+
+    /// Create the store, overriding the `OpenDAL` service configuration with the storage options of a [`StoreManager`](super::StoreManager).
+    ///
+    /// If the `anonymous_fallback` storage option is `true`, reads are attempted anonymously and retried with credentials if permission is denied.
+    pub(super) fn to_storage(
+        &self,
+        manager_config: &StoreManagerConfig,
+    ) -> PyResult<ReadableWritableListableStorage> {
+        let mut storage_options = manager_config.storage_options.clone();
+        let anonymous_fallback = storage_options
+            .remove("anonymous_fallback")
+            .is_some_and(|value| value == "true");
+        check_storage_options::<opendal::services::S3Config>("S3", &storage_options)?;
+        let operator = |anonymous: bool| {
+            let mut config = Self {
+                anonymous,
+                ..self.clone()
+            }
+            .opendal_config();
+            config.extend(storage_options.clone());
+            opendal_service_to_operator::<opendal::services::S3Config>(config, |builder| {
+                match &manager_config.http_client {
+                    Some(http_client) => builder.http_client(http_client.clone()),
+                    None => builder,
+                }
+            })
+        };
+        if anonymous_fallback && !self.anonymous {
+            let anonymous_operator = operator(true)?;
+            Ok(Arc::new(AnonymousFallbackStorageAdapter {
+                anonymous: opendal_operator_to_sync_store(
+                    anonymous_operator.clone(),
+                    manager_config,
+                ),
+                anonymous_operator,
+                signed: opendal_operator_to_sync_store(operator(false)?, manager_config),
+            }))
+        } else {
+            Ok(opendal_operator_to_sync_store(
+                operator(self.anonymous)?,
+                manager_config,
+            ))
+        }
+    }
+}
+
+/// Reads from a store anonymously, retrying with credentials if permission is denied.
+///
+/// Writes always use credentials.
+struct AnonymousFallbackStorageAdapter {
+    anonymous: ReadableWritableListableStorage,
+    anonymous_operator: opendal::Operator,
+    signed: ReadableWritableListableStorage,
+}
+
+impl AnonymousFallbackStorageAdapter {
+    /// Whether anonymous access to a path is denied.
+    ///
+    /// The `OpenDAL` errors of the store are converted to strings by `zarrs_opendal`,
+    /// so the error kind is retrieved with a metadata request of the anonymous operator instead.
+    fn is_permission_denied(&self, path: &str) -> bool {
+        matches!(
+            tokio_block_on().block_on(self.anonymous_operator.stat(path)),
+            Err(err) if err.kind() == opendal::ErrorKind::PermissionDenied
+        )
+    }
+
+    fn fallback<T>(
+        &self,
+        path: &str,
+        f: impl Fn(&ReadableWritableListableStorage) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        match f(&self.anonymous) {
+            Err(_) if self.is_permission_denied(path) => f(&self.signed),
+            result => result,
+        }
+    }
+}
+
+impl ReadableStorageTraits for AnonymousFallbackStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.fallback(key.as_str(), |storage| storage.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.fallback(key.as_str(), |storage| {
+            storage.get_partial_values_key(key, byte_ranges)
+        })
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.fallback(key.as_str(), |storage| storage.size_key(key))
+    }
+}
+
+impl WritableStorageTraits for AnonymousFallbackStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.signed.set(key, value)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.signed.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.signed.erase(key)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.signed.erase_prefix(prefix)
+    }
+}
+
+impl ListableStorageTraits for AnonymousFallbackStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.fallback("", |storage| storage.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.fallback(prefix.as_str(), |storage| storage.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.fallback(prefix.as_str(), |storage| storage.list_dir(prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.fallback(prefix.as_str(), |storage| storage.size_prefix(prefix))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.fallback("", |storage| storage.size())
+    }
+}
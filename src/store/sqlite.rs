@@ -0,0 +1,206 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use pyo3::{
+    exceptions::PyRuntimeError, pyclass, types::PyAnyMethods, Bound, PyAny, PyErr, PyResult,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use zarrs::storage::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, ReadableWritableListableStorage, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+use super::{extract_byte_ranges, keys_to_keys_prefixes, read_only::ReadOnlyStorageAdapter};
+use crate::utils::PyErrExt;
+
+/// A `zarrs.storage.SQLiteStore`, where each key is a row of a single SQLite database file.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct SqliteStoreConfig {
+    #[pyo3(get, set)]
+    pub path: String,
+    #[pyo3(get, set)]
+    pub read_only: bool,
+}
+
+impl SqliteStoreConfig {
+    pub fn new(store: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            path: store.getattr("path")?.extract()?,
+            read_only: store.getattr("read_only")?.extract()?,
+        })
+    }
+}
+
+impl TryInto<ReadableWritableListableStorage> for &SqliteStoreConfig {
+    type Error = PyErr;
+
+    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
+        let store = SqliteStore::open(&self.path, self.read_only).map_py_err::<PyRuntimeError>()?;
+        if self.read_only {
+            Ok(Arc::new(ReadOnlyStorageAdapter::new(Arc::new(store))))
+        } else {
+            Ok(Arc::new(store))
+        }
+    }
+}
+
+fn sqlite_err_to_storage_err(err: rusqlite::Error) -> StorageError {
+    StorageError::Other(err.to_string())
+}
+
+/// Selects the keys with a prefix, where `?1` is the prefix.
+const PREFIX_FILTER: &str = "substr(key, 1, length(?1)) = ?1";
+
+/// A store of keys and values in the `zarr` table of a SQLite database.
+///
+/// The schema matches `zarrs.storage.SQLiteStore`, so both can access the same database concurrently.
+struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    fn open(path: &str, read_only: bool) -> Result<Self, rusqlite::Error> {
+        let connection = if read_only {
+            Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?
+        } else {
+            let connection = Connection::open(path)?;
+            connection.execute(
+                "CREATE TABLE IF NOT EXISTS zarr (key TEXT PRIMARY KEY, value BLOB NOT NULL)",
+                [],
+            )?;
+            connection
+        };
+        // Wait for writes by other connections (e.g. zarr-python) rather than failing
+        connection.busy_timeout(std::time::Duration::from_secs(5))?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> Result<MutexGuard<'_, Connection>, StorageError> {
+        self.connection
+            .lock()
+            .map_err(|err| StorageError::Other(err.to_string()))
+    }
+
+    fn keys_with_prefix(&self, prefix: &StorePrefix) -> Result<Vec<String>, StorageError> {
+        let connection = self.connection()?;
+        let mut statement = connection
+            .prepare_cached(&format!(
+                "SELECT key FROM zarr WHERE {PREFIX_FILTER} ORDER BY key"
+            ))
+            .map_err(sqlite_err_to_storage_err)?;
+        let keys = statement
+            .query_map(params![prefix.as_str()], |row| row.get(0))
+            .map_err(sqlite_err_to_storage_err)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(sqlite_err_to_storage_err)?;
+        Ok(keys)
+    }
+}
+
+impl ReadableStorageTraits for SqliteStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let value: Option<Vec<u8>> = self
+            .connection()?
+            .query_row(
+                "SELECT value FROM zarr WHERE key = ?1",
+                params![key.as_str()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(sqlite_err_to_storage_err)?;
+        Ok(value.map(Bytes::from))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.get(key)?
+            .map(|bytes| extract_byte_ranges(key, &bytes, byte_ranges))
+            .transpose()
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.connection()?
+            .query_row(
+                "SELECT length(value) FROM zarr WHERE key = ?1",
+                params![key.as_str()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(sqlite_err_to_storage_err)
+    }
+}
+
+impl WritableStorageTraits for SqliteStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.connection()?
+            .execute(
+                "INSERT OR REPLACE INTO zarr (key, value) VALUES (?1, ?2)",
+                params![key.as_str(), &value[..]],
+            )
+            .map_err(sqlite_err_to_storage_err)?;
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.connection()?
+            .execute("DELETE FROM zarr WHERE key = ?1", params![key.as_str()])
+            .map_err(sqlite_err_to_storage_err)?;
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.connection()?
+            .execute(
+                &format!("DELETE FROM zarr WHERE {PREFIX_FILTER}"),
+                params![prefix.as_str()],
+            )
+            .map_err(sqlite_err_to_storage_err)?;
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for SqliteStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.keys_with_prefix(prefix)?
+            .into_iter()
+            .map(|key| StoreKey::new(key).map_err(StorageError::from))
+            .collect()
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        keys_to_keys_prefixes(self.keys_with_prefix(prefix)?, prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.connection()?
+            .query_row(
+                &format!("SELECT COALESCE(SUM(length(value)), 0) FROM zarr WHERE {PREFIX_FILTER}"),
+                params![prefix.as_str()],
+                |row| row.get(0),
+            )
+            .map_err(sqlite_err_to_storage_err)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.size_prefix(&StorePrefix::root())
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use pyo3::pyclass;
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    ReadableWritableListableStorage, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// Store I/O and encoding statistics of a pipeline.
+#[derive(Debug, Clone, Copy, Default)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct StoreStats {
+    #[pyo3(get)]
+    pub bytes_read: usize,
+    #[pyo3(get)]
+    pub bytes_written: usize,
+    #[pyo3(get)]
+    pub gets: usize,
+    #[pyo3(get)]
+    pub sets: usize,
+    #[pyo3(get)]
+    pub erases: usize,
+    #[pyo3(get)]
+    pub cache_hits: usize,
+    #[pyo3(get)]
+    pub cache_misses: usize,
+    #[pyo3(get)]
+    pub chunks_encoded: usize,
+    #[pyo3(get)]
+    pub bytes_before_encoding: usize,
+    #[pyo3(get)]
+    pub bytes_after_encoding: usize,
+}
+
+/// Counters of the store operations of a pipeline, shared by all of its stores.
+#[derive(Debug, Default)]
+pub(crate) struct StoreStatistics {
+    bytes_read: AtomicUsize,
+    bytes_written: AtomicUsize,
+    gets: AtomicUsize,
+    sets: AtomicUsize,
+    erases: AtomicUsize,
+    cache_hits: AtomicUsize,
+    cache_misses: AtomicUsize,
+    chunks_encoded: AtomicUsize,
+    bytes_before_encoding: AtomicUsize,
+    bytes_after_encoding: AtomicUsize,
+}
+
+impl StoreStatistics {
+    pub(crate) fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_encode(&self, bytes_decoded: usize, bytes_encoded: usize) {
+        self.chunks_encoded.fetch_add(1, Ordering::Relaxed);
+        self.bytes_before_encoding
+            .fetch_add(bytes_decoded, Ordering::Relaxed);
+        self.bytes_after_encoding
+            .fetch_add(bytes_encoded, Ordering::Relaxed);
+    }
+
+    fn record_get(&self, bytes: usize) {
+        self.gets.fetch_add(1, Ordering::Relaxed);
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn record_set(&self, bytes: usize) {
+        self.sets.fetch_add(1, Ordering::Relaxed);
+        self.bytes_written.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn record_erase(&self) {
+        self.erases.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> StoreStats {
+        StoreStats {
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            gets: self.gets.load(Ordering::Relaxed),
+            sets: self.sets.load(Ordering::Relaxed),
+            erases: self.erases.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            chunks_encoded: self.chunks_encoded.load(Ordering::Relaxed),
+            bytes_before_encoding: self.bytes_before_encoding.load(Ordering::Relaxed),
+            bytes_after_encoding: self.bytes_after_encoding.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.bytes_read,
+            &self.bytes_written,
+            &self.gets,
+            &self.sets,
+            &self.erases,
+            &self.cache_hits,
+            &self.cache_misses,
+            &self.chunks_encoded,
+            &self.bytes_before_encoding,
+            &self.bytes_after_encoding,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Records the operations of a store in [`StoreStatistics`].
+pub(crate) struct StatisticsStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    statistics: Arc<StoreStatistics>,
+}
+
+impl StatisticsStorageAdapter {
+    pub(crate) fn new(
+        storage: ReadableWritableListableStorage,
+        statistics: Arc<StoreStatistics>,
+    ) -> Self {
+        Self {
+            storage,
+            statistics,
+        }
+    }
+}
+
+impl ReadableStorageTraits for StatisticsStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let value = self.storage.get(key)?;
+        self.statistics
+            .record_get(value.as_ref().map_or(0, Bytes::len));
+        Ok(value)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let values = self.storage.get_partial_values_key(key, byte_ranges)?;
+        self.statistics.record_get(
+            values
+                .as_ref()
+                .map_or(0, |values| values.iter().map(Bytes::len).sum()),
+        );
+        Ok(values)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+}
+
+impl WritableStorageTraits for StatisticsStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let bytes = value.len();
+        self.storage.set(key, value)?;
+        self.statistics.record_set(bytes);
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage.set_partial_values(key_offset_values)?;
+        for key_offset_value in key_offset_values {
+            self.statistics.record_set(key_offset_value.value().len());
+        }
+        Ok(())
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key)?;
+        self.statistics.record_erase();
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix)?;
+        self.statistics.record_erase();
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for StatisticsStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
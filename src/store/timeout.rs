@@ -0,0 +1,129 @@
+use std::{io::ErrorKind, sync::Arc, time::Duration};
+
+use pyo3::{exceptions::PyTimeoutError, PyErr, PyTypeInfo};
+use zarrs::storage::{
+    byte_range::ByteRange, storage_adapter::async_to_sync::AsyncToSyncBlockOn,
+    store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    ReadableWritableListableStorage, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+use crate::runtime::tokio_block_on;
+
+/// Convert a storage error to a Python `TimeoutError` if a store operation timed out, otherwise to `PE`.
+pub(crate) fn storage_err_to_py_err<PE: PyTypeInfo>(err: StorageError) -> PyErr {
+    match &err {
+        StorageError::IOError(io_err) if io_err.kind() == ErrorKind::TimedOut => {
+            PyTimeoutError::new_err(err.to_string())
+        }
+        _ => PyErr::new::<PE, _>(err.to_string()),
+    }
+}
+
+/// Fails store operations that do not complete within a timeout.
+///
+/// Each operation runs on the blocking thread pool of the Tokio runtime, and is awaited with a timeout.
+/// This bounds the time a hung operation (e.g. on a stalled network filesystem or HTTP connection) blocks the pipeline, but the operation itself is not cancelled.
+/// Its thread is returned to the pool once the operation completes.
+pub(crate) struct TimeoutStorageAdapter {
+    storage: ReadableWritableListableStorage,
+    timeout: Duration,
+}
+
+impl TimeoutStorageAdapter {
+    pub(crate) fn new(storage: ReadableWritableListableStorage, timeout: Duration) -> Self {
+        Self { storage, timeout }
+    }
+
+    fn timeout<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&ReadableWritableListableStorage) -> Result<T, StorageError> + Send + 'static,
+    ) -> Result<T, StorageError> {
+        let storage = Arc::clone(&self.storage);
+        let timeout = self.timeout;
+        // The operation is spawned within the runtime, which is only entered by `block_on`
+        let result = tokio_block_on().block_on(async move {
+            tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || f(&storage))).await
+        });
+        match result {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(StorageError::Other("store operation panicked".to_string())),
+            Err(_) => Err(StorageError::IOError(std::io::Error::new(
+                ErrorKind::TimedOut,
+                format!("store operation timed out after {:?}", self.timeout),
+            ))),
+        }
+    }
+}
+
+impl ReadableStorageTraits for TimeoutStorageAdapter {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let key = key.clone();
+        self.timeout(move |storage| storage.get(&key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let key = key.clone();
+        let byte_ranges = byte_ranges.to_vec();
+        self.timeout(move |storage| storage.get_partial_values_key(&key, &byte_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let key = key.clone();
+        self.timeout(move |storage| storage.size_key(&key))
+    }
+}
+
+impl WritableStorageTraits for TimeoutStorageAdapter {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let key = key.clone();
+        self.timeout(move |storage| storage.set(&key, value))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        // The values are borrowed, so each key is read and written with a timeout instead
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let key = key.clone();
+        self.timeout(move |storage| storage.erase(&key))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let prefix = prefix.clone();
+        self.timeout(move |storage| storage.erase_prefix(&prefix))
+    }
+}
+
+impl ListableStorageTraits for TimeoutStorageAdapter {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.timeout(|storage| storage.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let prefix = prefix.clone();
+        self.timeout(move |storage| storage.list_prefix(&prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let prefix = prefix.clone();
+        self.timeout(move |storage| storage.list_dir(&prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let prefix = prefix.clone();
+        self.timeout(move |storage| storage.size_prefix(&prefix))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.timeout(|storage| storage.size())
+    }
+}
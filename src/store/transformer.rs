@@ -0,0 +1,117 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use pyo3::{exceptions::PyValueError, pyclass, PyErr};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::storage::{
+    storage_adapter::{
+        performance_metrics::PerformanceMetricsStorageAdapter, usage_log::UsageLogStorageAdapter,
+    },
+    ReadableWritableListableStorage, ReadableWritableListableStorageTraits,
+};
+
+/// A storage transformer applied between each store of a pipeline and the codec chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StorageTransformer {
+    /// Log every store operation to stderr.
+    UsageLog,
+    /// Count the reads, writes, and bytes read/written.
+    PerformanceMetrics,
+}
+
+impl FromStr for StorageTransformer {
+    type Err = PyErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "usage_log" => Ok(Self::UsageLog),
+            "performance_metrics" => Ok(Self::PerformanceMetrics),
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported storage transformer: {name}, expected usage_log or performance_metrics"
+            ))),
+        }
+    }
+}
+
+type PerformanceMetricsStorage =
+    PerformanceMetricsStorageAdapter<dyn ReadableWritableListableStorageTraits>;
+
+/// The storage transformers of a pipeline, and the state needed to report their metrics.
+#[derive(Default)]
+pub(crate) struct StorageTransformers {
+    transformers: Vec<StorageTransformer>,
+    performance_metrics: Mutex<Vec<Arc<PerformanceMetricsStorage>>>,
+}
+
+impl StorageTransformers {
+    pub(crate) fn new(transformers: Vec<StorageTransformer>) -> Self {
+        Self {
+            transformers,
+            performance_metrics: Mutex::default(),
+        }
+    }
+
+    /// Apply the storage transformers to `storage`, in order.
+    pub(crate) fn apply(
+        &self,
+        mut storage: ReadableWritableListableStorage,
+    ) -> ReadableWritableListableStorage {
+        for transformer in &self.transformers {
+            storage = match transformer {
+                StorageTransformer::UsageLog => Arc::new(UsageLogStorageAdapter::new(
+                    storage,
+                    Arc::new(Mutex::new(std::io::stderr())),
+                    || "zarrs: ".to_string(),
+                )),
+                StorageTransformer::PerformanceMetrics => {
+                    let adapter = Arc::new(PerformanceMetricsStorageAdapter::new(storage));
+                    if let Ok(mut performance_metrics) = self.performance_metrics.lock() {
+                        performance_metrics.push(adapter.clone());
+                    }
+                    adapter
+                }
+            };
+        }
+        storage
+    }
+
+    /// The performance metrics summed over every store, if the performance metrics transformer is enabled.
+    pub(crate) fn performance_metrics(&self) -> Option<PerformanceMetrics> {
+        if !self
+            .transformers
+            .contains(&StorageTransformer::PerformanceMetrics)
+        {
+            return None;
+        }
+        let mut metrics = PerformanceMetrics::default();
+        if let Ok(performance_metrics) = self.performance_metrics.lock() {
+            for adapter in performance_metrics.iter() {
+                metrics.bytes_read += adapter.bytes_read();
+                metrics.bytes_written += adapter.bytes_written();
+                metrics.reads += adapter.reads();
+                metrics.writes += adapter.writes();
+                metrics.keys_erased += adapter.keys_erased();
+            }
+        }
+        Some(metrics)
+    }
+}
+
+/// Store access counts recorded by the `performance_metrics` storage transformer.
+#[derive(Debug, Clone, Copy, Default)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct PerformanceMetrics {
+    #[pyo3(get)]
+    pub bytes_read: usize,
+    #[pyo3(get)]
+    pub bytes_written: usize,
+    #[pyo3(get)]
+    pub reads: usize,
+    #[pyo3(get)]
+    pub writes: usize,
+    #[pyo3(get)]
+    pub keys_erased: usize,
+}
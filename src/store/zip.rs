@@ -0,0 +1,59 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    pyclass,
+    types::PyAnyMethods,
+    Bound, PyAny, PyErr, PyResult,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::{
+    filesystem::FilesystemStore,
+    storage::{ReadableWritableListableStorage, StoreKey},
+};
+use zarrs_zip::ZipStorageAdapter;
+
+use super::read_only::ReadOnlyStorageAdapter;
+use crate::utils::PyErrExt;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct ZipStoreConfig {
+    #[pyo3(get, set)]
+    pub path: String,
+}
+
+impl ZipStoreConfig {
+    pub fn new(store: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let path: String = store.getattr("path")?.call_method0("__str__")?.extract()?;
+        Ok(Self { path })
+    }
+}
+
+impl TryInto<ReadableWritableListableStorage> for &ZipStoreConfig {
+    type Error = PyErr;
+
+    fn try_into(self) -> Result<ReadableWritableListableStorage, Self::Error> {
+        let path = PathBuf::from(&self.path);
+        let Some(file_name) = path.file_name() else {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "invalid zip archive path {}",
+                self.path
+            )));
+        };
+        let directory = path
+            .parent()
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let directory_store =
+            Arc::new(FilesystemStore::new(directory).map_py_err::<PyRuntimeError>()?);
+        let key = StoreKey::new(file_name.to_string_lossy()).map_py_err::<PyValueError>()?;
+        let zip_store =
+            ZipStorageAdapter::new(directory_store, key).map_py_err::<PyRuntimeError>()?;
+        Ok(Arc::new(ReadOnlyStorageAdapter::new(Arc::new(zip_store))))
+    }
+}
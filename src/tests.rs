@@ -6,6 +6,11 @@ use pyo3::{
     Bound, PyResult, Python,
 };
 
+use zarrs::array::{DataType, FillValue};
+
+use crate::fill_value::element_is_fill_value;
+use crate::indexing::{copy, gather, scatter, Layout};
+use crate::store::{S3StoreConfig, StoreConfig};
 use crate::CodecPipelineImpl;
 
 #[test]
@@ -26,8 +31,209 @@ fn test_nparray_to_unsafe_cell_slice_empty() -> PyResult<()> {
         .call0()?
         .extract()?;
 
-        let slice = CodecPipelineImpl::nparray_to_unsafe_cell_slice(&arr)?;
+        let (slice, _) = CodecPipelineImpl::nparray_to_unsafe_cell_slice(&arr)?;
         assert!(slice.is_empty());
         Ok(())
     })
 }
+
+const ELEMENT_SIZES: [usize; 5] = [1, 2, 4, 8, 16];
+
+/// The bytes of a C order array whose elements are distinct.
+#[allow(clippy::cast_possible_truncation)]
+fn array_bytes(num_elements: usize, element_size: usize) -> Vec<u8> {
+    (0..num_elements * element_size)
+        .map(|i| (i / element_size * 7 + i % element_size) as u8)
+        .collect()
+}
+
+/// The bytes of the element at `index` of `bytes`.
+fn element(bytes: &[u8], index: usize, element_size: usize) -> &[u8] {
+    &bytes[index * element_size..(index + 1) * element_size]
+}
+
+#[test]
+fn test_gather_scatter_steps() -> PyResult<()> {
+    // Every other row and every third column of a 6x6 array
+    let indices = vec![vec![1, 3, 5], vec![0, 3]];
+    for element_size in ELEMENT_SIZES {
+        let layout = Layout::c_order(&[6, 6], element_size);
+        let bytes = array_bytes(36, element_size);
+        let gathered = gather(&bytes, &layout, &indices, element_size)?;
+        let expected: Vec<u8> = [6, 9, 18, 21, 30, 33]
+            .iter()
+            .flat_map(|&index| element(&bytes, index, element_size))
+            .copied()
+            .collect();
+        assert_eq!(gathered, expected);
+
+        let mut scattered = vec![0; bytes.len()];
+        scatter(&mut scattered, &layout, &indices, element_size, &gathered)?;
+        for index in 0..36 {
+            let selected = [6, 9, 18, 21, 30, 33].contains(&index);
+            assert_eq!(
+                element(&scattered, index, element_size),
+                if selected {
+                    element(&bytes, index, element_size).to_vec()
+                } else {
+                    vec![0; element_size]
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_gather_negative_strides() -> PyResult<()> {
+    for element_size in ELEMENT_SIZES {
+        // A reversed view of a 1-dimensional array of 5 elements
+        let layout = Layout::new(vec![-isize::try_from(element_size)?], 4 * element_size);
+        let bytes = array_bytes(5, element_size);
+        let gathered = gather(&bytes, &layout, &[vec![0, 2, 3]], element_size)?;
+        let expected: Vec<u8> = [4, 2, 1]
+            .iter()
+            .flat_map(|&index| element(&bytes, index, element_size))
+            .copied()
+            .collect();
+        assert_eq!(gathered, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_copy_matches_gather_scatter() -> PyResult<()> {
+    let src_indices = vec![vec![5, 3, 1], vec![0, 1, 2, 4]];
+    // The destination drops an axis and is F order
+    let dst_indices = vec![vec![0], vec![1, 2, 3], vec![0, 2, 3, 4]];
+    for element_size in ELEMENT_SIZES {
+        let src_layout = Layout::c_order(&[6, 6], element_size);
+        let dst_layout = Layout::f_order(&[1, 4, 5], element_size);
+        let src = array_bytes(36, element_size);
+
+        let mut expected = vec![0; 20 * element_size];
+        scatter(
+            &mut expected,
+            &dst_layout,
+            &dst_indices,
+            element_size,
+            &gather(&src, &src_layout, &src_indices, element_size)?,
+        )?;
+        let mut dst = vec![0; 20 * element_size];
+        copy(
+            &src,
+            &src_layout,
+            &src_indices,
+            &mut dst,
+            &dst_layout,
+            &dst_indices,
+            element_size,
+        )?;
+        assert_eq!(dst, expected);
+
+        // The selections must have the same number of elements
+        assert!(copy(
+            &src,
+            &src_layout,
+            &src_indices,
+            &mut dst,
+            &dst_layout,
+            &[vec![0], vec![1, 2], vec![0, 2, 3, 4]],
+            element_size,
+        )
+        .is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_element_is_nan_fill_value() {
+    // A quiet NaN fill value matches a negative NaN and a NaN with a payload, but not infinity
+    let cases: [(DataType, Vec<u8>, Vec<u8>, Vec<u8>); 4] = [
+        (
+            DataType::Float16,
+            0x7e00_u16.to_ne_bytes().to_vec(),
+            0xfe01_u16.to_ne_bytes().to_vec(),
+            0x7c00_u16.to_ne_bytes().to_vec(),
+        ),
+        (
+            DataType::BFloat16,
+            0x7fc0_u16.to_ne_bytes().to_vec(),
+            0xffc1_u16.to_ne_bytes().to_vec(),
+            0x7f80_u16.to_ne_bytes().to_vec(),
+        ),
+        (
+            DataType::Float32,
+            f32::NAN.to_ne_bytes().to_vec(),
+            (-f32::NAN).to_ne_bytes().to_vec(),
+            f32::INFINITY.to_ne_bytes().to_vec(),
+        ),
+        (
+            DataType::Float64,
+            f64::NAN.to_ne_bytes().to_vec(),
+            f64::from_bits(0x7ff8_0000_0000_0001).to_ne_bytes().to_vec(),
+            f64::INFINITY.to_ne_bytes().to_vec(),
+        ),
+    ];
+    for (data_type, fill_value, nan, infinity) in cases {
+        let fill_value = FillValue::new(fill_value);
+        assert!(element_is_fill_value(&data_type, &nan, &fill_value));
+        assert!(!element_is_fill_value(&data_type, &infinity, &fill_value));
+    }
+    // NaN bits of an integer data type are not special
+    let fill_value = FillValue::new(0x7e00_u16.to_ne_bytes().to_vec());
+    assert!(!element_is_fill_value(
+        &DataType::UInt16,
+        &0xfe01_u16.to_ne_bytes(),
+        &fill_value
+    ));
+}
+
+#[test]
+fn test_element_is_signed_zero_and_complex_fill_value() {
+    // Negative zero matches a zero fill value, as in numpy
+    let fill_value = FillValue::new(0.0_f32.to_ne_bytes().to_vec());
+    assert!(element_is_fill_value(
+        &DataType::Float32,
+        &(-0.0_f32).to_ne_bytes(),
+        &fill_value
+    ));
+    let fill_value = FillValue::new(0x0000_u16.to_ne_bytes().to_vec());
+    assert!(element_is_fill_value(
+        &DataType::Float16,
+        &0x8000_u16.to_ne_bytes(),
+        &fill_value
+    ));
+
+    // The components of complex numbers are compared as floats
+    let complex = |re: f64, im: f64| [re.to_ne_bytes(), im.to_ne_bytes()].concat();
+    let fill_value = FillValue::new(complex(f64::NAN, 0.0));
+    assert!(element_is_fill_value(
+        &DataType::Complex128,
+        &complex(-f64::NAN, -0.0),
+        &fill_value
+    ));
+    assert!(!element_is_fill_value(
+        &DataType::Complex128,
+        &complex(f64::NAN, 1.0),
+        &fill_value
+    ));
+}
+
+#[test]
+fn test_s3_store_config_debug_redacts_secrets() {
+    let config = S3StoreConfig {
+        bucket: "bucket".to_string(),
+        root: "/".to_string(),
+        region: None,
+        endpoint: None,
+        access_key_id: Some("access-key-id".to_string()),
+        secret_access_key: Some("secret-access-key".to_string()),
+        session_token: Some("session-token".to_string()),
+        anonymous: false,
+    };
+    let debug = format!("{:?}", StoreConfig::S3(config));
+    assert!(debug.contains("access-key-id"));
+    assert!(!debug.contains("secret-access-key"));
+    assert!(!debug.contains("session-token"));
+}
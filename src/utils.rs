@@ -1,7 +1,16 @@
 use std::fmt::Display;
 
 use numpy::{PyUntypedArray, PyUntypedArrayMethods};
-use pyo3::{Bound, PyErr, PyResult, PyTypeInfo};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    types::{
+        PyAnyMethods, PyBool, PyBoolMethods, PyDict, PyDictMethods, PyFloat, PyFloatMethods, PyInt,
+        PyList, PyString, PyStringMethods, PyTuple, PyTypeMethods,
+    },
+    Bound, PyAny, PyErr, PyResult, PyTypeInfo,
+};
+use serde_json::{Map, Value};
+use zarrs::metadata::v3::MetadataV3;
 
 pub(crate) trait PyErrExt<T> {
     fn map_py_err<PE: PyTypeInfo>(self) -> PyResult<T>;
@@ -29,3 +38,86 @@ impl PyUntypedArrayExt for Bound<'_, PyUntypedArray> {
         })
     }
 }
+
+/// Rewrite the JSON object of every codec of codec metadata with `visit`, including the inner and index codecs of `sharding_indexed` codecs.
+pub(crate) fn map_codecs_metadata(
+    codecs: Vec<MetadataV3>,
+    mut visit: impl FnMut(&mut Map<String, Value>),
+) -> PyResult<Vec<MetadataV3>> {
+    let mut codecs = serde_json::to_value(codecs).map_py_err::<PyValueError>()?;
+    visit_codecs_metadata(&mut codecs, &mut visit);
+    serde_json::from_value(codecs).map_py_err::<PyValueError>()
+}
+
+fn visit_codecs_metadata(codecs: &mut Value, visit: &mut impl FnMut(&mut Map<String, Value>)) {
+    let Some(codecs) = codecs.as_array_mut() else {
+        return;
+    };
+    for codec in codecs {
+        // Codecs without a configuration may be just their name
+        if let Some(name) = codec.as_str().map(str::to_string) {
+            *codec = serde_json::json!({ "name": name });
+        }
+        let Some(codec) = codec.as_object_mut() else {
+            continue;
+        };
+        visit(codec);
+        if let Some(configuration) = codec
+            .get_mut("configuration")
+            .and_then(Value::as_object_mut)
+        {
+            for inner_codecs in ["codecs", "index_codecs"] {
+                if let Some(inner_codecs) = configuration.get_mut(inner_codecs) {
+                    visit_codecs_metadata(inner_codecs, visit);
+                }
+            }
+        }
+    }
+}
+
+/// Convert metadata held by Python (e.g. a `list` of codec `dict`s) to JSON without serializing it to a string.
+///
+/// A `str` is parsed as JSON metadata.
+pub(crate) fn py_to_json_metadata(metadata: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if let Ok(metadata) = metadata.downcast::<PyString>() {
+        return serde_json::from_str(metadata.to_str()?).map_py_err::<PyTypeError>();
+    }
+    py_to_json(metadata)
+}
+
+fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = value.downcast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if value.is_instance_of::<PyInt>() {
+        if let Ok(value) = value.extract::<i64>() {
+            Ok(value.into())
+        } else {
+            Ok(value.extract::<u64>()?.into())
+        }
+    } else if let Ok(value) = value.downcast::<PyFloat>() {
+        serde_json::Number::from_f64(value.value())
+            .map(Value::Number)
+            .ok_or_else(|| PyTypeError::new_err(format!("{value} is not valid JSON metadata")))
+    } else if let Ok(value) = value.downcast::<PyString>() {
+        Ok(Value::String(value.to_str()?.to_string()))
+    } else if let Ok(value) = value.downcast::<PyDict>() {
+        value
+            .iter()
+            .map(|(key, value)| Ok((key.extract::<String>()?, py_to_json(&value)?)))
+            .collect::<PyResult<Map<_, _>>>()
+            .map(Value::Object)
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        value
+            .try_iter()?
+            .map(|value| py_to_json(&value?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "{} is not valid JSON metadata",
+            value.get_type().name()?
+        )))
+    }
+}
@@ -0,0 +1,55 @@
+use pyo3::pyclass;
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+use zarrs::array::codec::CodecError;
+
+/// The status of a chunk verified by `CodecPipelineImpl.verify_chunks`.
+pub(crate) enum ChunkStatus {
+    Valid,
+    Missing,
+    Corrupt(String),
+    Undecodable(String),
+}
+
+impl From<Result<(), CodecError>> for ChunkStatus {
+    fn from(result: Result<(), CodecError>) -> Self {
+        match result {
+            Ok(()) => Self::Valid,
+            Err(err @ CodecError::InvalidChecksum(_)) => Self::Corrupt(err.to_string()),
+            Err(err) => Self::Undecodable(err.to_string()),
+        }
+    }
+}
+
+/// A report of the chunks verified by `CodecPipelineImpl.verify_chunks`.
+#[derive(Debug, Clone, Default)]
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct ChunkVerification {
+    /// The number of chunks which were decoded with valid checksums.
+    #[pyo3(get)]
+    pub valid: usize,
+    /// The keys of the missing chunks.
+    #[pyo3(get)]
+    pub missing: Vec<String>,
+    /// The keys of the chunks with an invalid checksum, and the error.
+    #[pyo3(get)]
+    pub corrupt: Vec<(String, String)>,
+    /// The keys of the chunks which could not be retrieved or decoded for another reason, and the error.
+    #[pyo3(get)]
+    pub undecodable: Vec<(String, String)>,
+}
+
+impl FromIterator<(String, ChunkStatus)> for ChunkVerification {
+    fn from_iter<T: IntoIterator<Item = (String, ChunkStatus)>>(iter: T) -> Self {
+        let mut verification = Self::default();
+        for (key, status) in iter {
+            match status {
+                ChunkStatus::Valid => verification.valid += 1,
+                ChunkStatus::Missing => verification.missing.push(key),
+                ChunkStatus::Corrupt(err) => verification.corrupt.push((key, err)),
+                ChunkStatus::Undecodable(err) => verification.undecodable.push((key, err)),
+            }
+        }
+        verification
+    }
+}
@@ -0,0 +1,66 @@
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    types::{PyAnyMethods, PyBytes, PyBytesMethods, PyString, PyStringMethods},
+    Bound, PyAny, PyErr, PyObject, PyResult, Python,
+};
+use zarrs::array::DataType;
+
+use crate::utils::PyErrExt;
+
+fn unsupported_data_type_err(data_type: &DataType) -> PyErr {
+    PyErr::new::<PyTypeError, _>(format!(
+        "data type {data_type} is not a supported variable length data type"
+    ))
+}
+
+/// Convert the bytes and offsets of the elements of a chunk with a variable length data type to Python objects.
+///
+/// Elements of the `string` data type are converted to `str`, and elements of the `bytes` data type to `bytes`.
+pub(crate) fn elements_to_py(
+    py: Python,
+    data_type: &DataType,
+    bytes: &[u8],
+    offsets: &[usize],
+) -> PyResult<Vec<PyObject>> {
+    offsets
+        .windows(2)
+        .map(|offsets| {
+            let element = &bytes[offsets[0]..offsets[1]];
+            match data_type {
+                DataType::String => Ok(PyString::new(
+                    py,
+                    std::str::from_utf8(element).map_py_err::<PyValueError>()?,
+                )
+                .into_any()
+                .unbind()),
+                DataType::Bytes => Ok(PyBytes::new(py, element).into_any().unbind()),
+                _ => Err(unsupported_data_type_err(data_type)),
+            }
+        })
+        .collect()
+}
+
+/// Convert Python objects to the bytes and offsets of the elements of a chunk with a variable length data type.
+///
+/// Elements of the `string` data type must be `str`, and elements of the `bytes` data type must be `bytes`.
+pub(crate) fn elements_from_py(
+    data_type: &DataType,
+    elements: &[Bound<'_, PyAny>],
+) -> PyResult<(Vec<u8>, Vec<usize>)> {
+    let mut bytes = Vec::new();
+    let mut offsets = Vec::with_capacity(elements.len() + 1);
+    offsets.push(0);
+    for element in elements {
+        match data_type {
+            DataType::String => {
+                bytes.extend_from_slice(element.downcast::<PyString>()?.to_str()?.as_bytes());
+            }
+            DataType::Bytes => {
+                bytes.extend_from_slice(element.downcast::<PyBytes>()?.as_bytes());
+            }
+            _ => return Err(unsupported_data_type_err(data_type)),
+        }
+        offsets.push(bytes.len());
+    }
+    Ok((bytes, offsets))
+}
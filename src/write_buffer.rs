@@ -0,0 +1,112 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use pyo3::{exceptions::PyRuntimeError, PyResult};
+use zarrs::array::codec::CodecOptions;
+
+use crate::{
+    chunk_item::{Basic, ChunksItem},
+    store::StoreConfig,
+    utils::PyErrExt,
+};
+
+/// A chunk with pending updates, and the codec options of the write that last updated it.
+pub(crate) struct BufferedChunk {
+    pub(crate) item: Basic,
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) codec_options: CodecOptions,
+}
+
+/// The decoded bytes of the chunks with pending updates.
+#[derive(Default)]
+struct BufferedChunks {
+    chunks: BTreeMap<(StoreConfig, String), BufferedChunk>,
+    size: usize,
+}
+
+/// A write-back buffer of decoded chunks.
+///
+/// Partial writes are applied to the decoded bytes of a chunk in the buffer, so a chunk updated by many writes is retrieved and stored once.
+/// Chunks are stored when the buffer is flushed, which the pipeline does before any read, when the buffer exceeds its maximum size, and when it is dropped.
+pub(crate) struct WriteBuffer {
+    max_bytes: usize,
+    chunks: Mutex<BufferedChunks>,
+}
+
+impl WriteBuffer {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            chunks: Mutex::default(),
+        }
+    }
+
+    fn lock(&self) -> PyResult<MutexGuard<'_, BufferedChunks>> {
+        self.chunks.lock().map_py_err::<PyRuntimeError>()
+    }
+
+    fn chunk_key<I: ChunksItem>(item: &I) -> (StoreConfig, String) {
+        (item.store_config(), item.key().as_str().to_string())
+    }
+
+    /// Take the decoded bytes of a chunk out of the buffer, if it has pending updates.
+    pub(crate) fn take<I: ChunksItem>(&self, item: &I) -> PyResult<Option<Vec<u8>>> {
+        let mut buffered = self.lock()?;
+        let chunk = buffered.chunks.remove(&Self::chunk_key(item));
+        if let Some(chunk) = &chunk {
+            buffered.size -= chunk.bytes.len();
+        }
+        Ok(chunk.map(|chunk| chunk.bytes))
+    }
+
+    /// Put the decoded bytes of a chunk with pending updates in the buffer, with the codec options to store it with.
+    ///
+    /// Returns true if the buffer exceeds its maximum size and should be flushed.
+    pub(crate) fn insert(
+        &self,
+        item: Basic,
+        bytes: Vec<u8>,
+        codec_options: CodecOptions,
+    ) -> PyResult<bool> {
+        let mut buffered = self.lock()?;
+        buffered.size += bytes.len();
+        let chunk = BufferedChunk {
+            item,
+            bytes,
+            codec_options,
+        };
+        if let Some(replaced) = buffered.chunks.insert(Self::chunk_key(&chunk.item), chunk) {
+            buffered.size -= replaced.bytes.len();
+        }
+        Ok(buffered.size > self.max_bytes)
+    }
+
+    /// Take every chunk out of the buffer.
+    pub(crate) fn drain(&self) -> PyResult<Vec<BufferedChunk>> {
+        let mut buffered = self.lock()?;
+        buffered.size = 0;
+        Ok(std::mem::take(&mut buffered.chunks).into_values().collect())
+    }
+
+    /// Put chunks which failed to be stored back in the buffer.
+    ///
+    /// A chunk updated again since it was taken out of the buffer is not replaced, as its newer bytes were retrieved from the store.
+    pub(crate) fn restore(&self, chunks: Vec<BufferedChunk>) -> PyResult<()> {
+        let mut buffered = self.lock()?;
+        for chunk in chunks {
+            let key = Self::chunk_key(&chunk.item);
+            if !buffered.chunks.contains_key(&key) {
+                buffered.size += chunk.bytes.len();
+                buffered.chunks.insert(key, chunk);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if no chunks have pending updates.
+    pub(crate) fn is_empty(&self) -> PyResult<bool> {
+        Ok(self.lock()?.chunks.is_empty())
+    }
+}
@@ -0,0 +1,167 @@
+use std::{borrow::Cow, io::Read, sync::Arc};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use pyo3::{
+    exceptions::PyValueError,
+    pyfunction,
+    types::{PyBytes, PyBytesMethods},
+    Bound, PyResult, Python,
+};
+use serde_json::Value;
+use zarrs::{
+    array::{
+        codec::{
+            BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes, RecommendedConcurrency,
+    },
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+use crate::utils::PyErrExt as _;
+
+/// The name of the zstd codec with a dictionary, as in the array metadata.
+const ZSTD_DICTIONARY: &str = "zarrs_python.zstd_dictionary";
+
+/// Train a zstd dictionary of at most `max_size` bytes from sample chunks (e.g. the bytes of decoded chunks of an array).
+///
+/// The dictionary is the `dictionary` of the `zarrs_python.zstd_dictionary` codec, which improves the compression of many small, similar chunks.
+#[pyfunction]
+#[pyo3(signature = (samples, max_size=112_640))]
+pub fn train_zstd_dictionary<'py>(
+    py: Python<'py>,
+    samples: Vec<Bound<'py, PyBytes>>,
+    max_size: usize,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let samples: Vec<&[u8]> = samples.iter().map(PyBytesMethods::as_bytes).collect();
+    let dictionary = py
+        .allow_threads(|| zstd::dict::from_samples(&samples, max_size))
+        .map_py_err::<PyValueError>()?;
+    Ok(PyBytes::new(py, &dictionary))
+}
+
+fn is_name_zstd_dictionary(name: &str) -> bool {
+    name == ZSTD_DICTIONARY
+}
+
+fn create_codec_zstd_dictionary(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration = metadata.configuration().cloned().unwrap_or_default();
+    let invalid = |field: &str| {
+        PluginCreateError::Other(format!(
+            "codec {ZSTD_DICTIONARY} has an invalid {field} configuration"
+        ))
+    };
+    let level = match configuration.get("level") {
+        Some(level) => level
+            .as_i64()
+            .and_then(|level| i32::try_from(level).ok())
+            .ok_or_else(|| invalid("level"))?,
+        None => zstd::DEFAULT_COMPRESSION_LEVEL,
+    };
+    let checksum = match configuration.get("checksum") {
+        Some(checksum) => checksum.as_bool().ok_or_else(|| invalid("checksum"))?,
+        None => false,
+    };
+    let dictionary = configuration
+        .get("dictionary")
+        .and_then(Value::as_str)
+        .and_then(|dictionary| STANDARD.decode(dictionary).ok())
+        .ok_or_else(|| invalid("dictionary"))?;
+    Ok(Codec::BytesToBytes(Arc::new(ZstdDictionaryCodec {
+        metadata: metadata.clone(),
+        level,
+        checksum,
+        encoder_dictionary: EncoderDictionary::copy(&dictionary, level),
+        decoder_dictionary: DecoderDictionary::copy(&dictionary),
+    })))
+}
+
+inventory::submit! {
+    CodecPlugin::new(ZSTD_DICTIONARY, is_name_zstd_dictionary, create_codec_zstd_dictionary)
+}
+
+/// A zstd codec which compresses every chunk with the same dictionary, which is stored in the codec configuration as base64.
+struct ZstdDictionaryCodec {
+    metadata: MetadataV3,
+    level: i32,
+    checksum: bool,
+    encoder_dictionary: EncoderDictionary<'static>,
+    decoder_dictionary: DecoderDictionary<'static>,
+}
+
+impl std::fmt::Debug for ZstdDictionaryCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZstdDictionaryCodec")
+            .field("level", &self.level)
+            .field("checksum", &self.checksum)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CodecTraits for ZstdDictionaryCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        Some(self.metadata.clone())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl BytesToBytesCodecTraits for ZstdDictionaryCodec {
+    fn into_dyn(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encoded_representation(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        decoded_representation
+            .size()
+            .map_or(BytesRepresentation::UnboundedSize, |size| {
+                // The worst case of zstd, plus the frame header and checksum
+                let size = usize::try_from(size).unwrap_or(usize::MAX);
+                BytesRepresentation::BoundedSize(zstd::zstd_safe::compress_bound(size) as u64 + 22)
+            })
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let mut compressor =
+            zstd::bulk::Compressor::with_prepared_dictionary(&self.encoder_dictionary)?;
+        compressor.include_checksum(self.checksum)?;
+        Ok(Cow::Owned(compressor.compress(&decoded_value)?))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let mut decoder = zstd::stream::read::Decoder::with_prepared_dictionary(
+            &*encoded_value,
+            &self.decoder_dictionary,
+        )?;
+        let mut decoded_value = Vec::new();
+        decoder.read_to_end(&mut decoded_value)?;
+        Ok(Cow::Owned(decoded_value))
+    }
+}
@@ -0,0 +1,104 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def chunk_desc(tmp_path, path: str, dtype: str, fill_value) -> WithSubset:
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path=path),
+        SimpleNamespace(shape=(4,), dtype=np.dtype(dtype), fill_value=fill_value),
+    )
+    return WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+
+@pytest.mark.parametrize(
+    ("dtype", "astype"),
+    [
+        ("int16", "float32"),
+        ("uint8", "int64"),
+        ("float64", "float32"),
+        ("float32", "int32"),
+        ("int32", "bool"),
+    ],
+)
+def test_retrieve_astype(tmp_path, dtype, astype):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    data = np.array([0, 1, 7, 100], dtype=dtype)
+    desc = chunk_desc(tmp_path, "c/0", dtype, np.array(0, dtype=dtype)[()])
+    impl.store_chunks_with_indices([desc], data)
+
+    out = np.zeros(4, dtype=astype)
+    impl.retrieve_chunks_and_apply_index([desc], out, astype=astype)
+    assert np.array_equal(out, data.astype(astype))
+
+    # Missing chunks are read as the fill value cast to the output data type
+    missing = chunk_desc(tmp_path, "c/1", dtype, np.array(3, dtype=dtype)[()])
+    impl.retrieve_chunks_and_apply_index([missing], out, astype=astype)
+    assert np.array_equal(out, np.full(4, 3, dtype=dtype).astype(astype))
+
+
+def test_retrieve_astype_indexed(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    data = np.array([-2, 5, 9, -7, 3, 11, 0, 1], dtype=np.int16)
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(8,), dtype=data.dtype, fill_value=np.int16(0)),
+    )
+    impl.store_chunks_with_indices(
+        [WithSubset(chunk, [slice(None)], [slice(None)], (8,))], data
+    )
+    out = np.zeros(3, dtype=np.float64)
+    impl.retrieve_chunks_and_apply_index(
+        [WithSubset(chunk, [np.array([6, 1, 3])], [slice(None)], (3,))],
+        out,
+        astype="float64",
+    )
+    assert np.array_equal(out, data[[6, 1, 3]].astype(np.float64))
+
+
+def test_retrieve_astype_invalid(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    desc = chunk_desc(tmp_path, "c/0", "int16", np.int16(0))
+    with pytest.raises(ValueError, match="does not match the size"):
+        impl.retrieve_chunks_and_apply_index(
+            [desc], np.zeros(4, dtype=np.float32), astype="float64"
+        )
+    with pytest.raises(TypeError, match="cannot cast"):
+        impl.retrieve_chunks_and_apply_index(
+            [desc], np.zeros(4, dtype=np.complex64), astype="complex64"
+        )
+
+
+def test_scale_offset(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    desc = chunk_desc(tmp_path, "c/0", "int16", np.int16(0))
+    values = np.array([10.0, 10.5, 12.25, -3.0], dtype=np.float32)
+    impl.store_chunks_with_indices(
+        [desc], values, astype="float32", scale_offset=(0.25, 10.0)
+    )
+    # Packed elements are rounded to the nearest integer
+    packed = np.frombuffer((tmp_path / "c" / "0").read_bytes(), dtype=np.int16)
+    assert np.array_equal(packed, [0, 2, 9, -52])
+
+    out = np.zeros(4, dtype=np.float32)
+    impl.retrieve_chunks_and_apply_index(
+        [desc], out, astype="float32", scale_offset=(0.25, 10.0)
+    )
+    assert np.array_equal(out, values)
+
+    # The scale and offset of the pipeline apply unless they are overridden
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]), scale_offset=(2.0, 1.0))
+    impl.retrieve_chunks_and_apply_index([desc], out, astype="float32")
+    assert np.array_equal(out, packed * 2.0 + 1.0)
+
+
+def test_scale_offset_invalid():
+    with pytest.raises(ValueError, match="nonzero"):
+        CodecPipelineImpl(json.dumps([{"name": "bytes"}]), scale_offset=(0.0, 1.0))
@@ -0,0 +1,39 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+ml_dtypes = pytest.importorskip("ml_dtypes")
+
+
+def test_bfloat16(tmp_path):
+    # zarr-python does not create bfloat16 arrays, so the pipeline is used directly
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes", "configuration": {"endian": "big"}}])
+    )
+    dtype = np.dtype(ml_dtypes.bfloat16)
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(
+            shape=(4,), dtype=dtype, fill_value=np.array(np.nan, dtype=dtype)[()]
+        ),
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+    data = np.array([1.5, -2.0, 3.25, np.inf], dtype=dtype)
+    impl.store_chunks_with_indices([chunk_desc], data)
+    # Elements are byte swapped by the bytes codec, unlike raw bits
+    expected = data.view(np.uint16).byteswap().tobytes()
+    assert (tmp_path / "c" / "0").read_bytes() == expected
+
+    out = np.zeros(4, dtype=dtype)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, data)
+
+    impl.store_chunks_with_indices([chunk_desc], np.full(4, -np.nan, dtype=dtype))
+    assert not (tmp_path / "c" / "0").exists()
@@ -0,0 +1,29 @@
+import numpy as np
+import pytest
+from numcodecs import BitRound
+from numcodecs.zarr3 import BitRound as BitRoundCodec
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.codecs import BytesCodec, ZstdCodec
+from zarr.storage import StorePath
+
+
+@pytest.mark.parametrize("dtype", ["float32", "float64"])
+@pytest.mark.parametrize("keepbits", [2, 4, 10])
+def test_bitround(*, store: Store, dtype: str, keepbits: int) -> None:
+    data = np.linspace(-100, 100, 256, dtype=dtype).reshape((16, 16))
+
+    a = Array.create(
+        StorePath(store, path="bitround"),
+        shape=data.shape,
+        chunk_shape=(8, 8),
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=[BitRoundCodec(keepbits=keepbits), BytesCodec(), ZstdCodec()],
+    )
+    assert a._async_array.codec_pipeline.impl is not None
+
+    a[:, :] = data
+    # The mantissa is rounded as by numcodecs
+    expected = BitRound(keepbits=keepbits).encode(data)
+    assert np.array_equal(expected, a[:, :])
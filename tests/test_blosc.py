@@ -2,11 +2,14 @@ import json
 
 import numpy as np
 import pytest
+import zarr
 from zarr import AsyncArray
 from zarr.abc.store import Store
 from zarr.codecs import BloscCodec, BytesCodec, ShardingCodec
 from zarr.core.buffer import default_buffer_prototype
-from zarr.storage import StorePath
+from zarr.storage import LocalStore, StorePath
+
+from zarrs._internal import CodecPipelineImpl
 
 
 @pytest.mark.parametrize("dtype", ["uint8", "uint16"])
@@ -55,3 +58,37 @@ async def test_blosc_evolve(store: Store, dtype: str) -> None:
         assert blosc_configuration_json["shuffle"] == "bitshuffle"
     else:
         assert blosc_configuration_json["shuffle"] == "shuffle"
+
+
+def test_blosc_options(tmp_path) -> None:
+    with zarr.config.set(
+        {
+            "codec_pipeline.blosc_nthreads": 2,
+            "codec_pipeline.blosc_blocksize": 256,
+            "codec_pipeline.blosc_shuffle": "noshuffle",
+        }
+    ):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(4096,),
+            chunks=(4096,),
+            dtype=np.uint16,
+            compressors=BloscCodec(cname="lz4", shuffle="shuffle"),
+        )
+    data = np.arange(4096, dtype=np.uint16) % 64
+    arr[:] = data
+    assert np.array_equal(arr[:], data)
+
+    # The blosc header records the shuffle flags and the block size
+    header = (tmp_path / "array.zarr" / "c" / "0").read_bytes()[:16]
+    assert header[2] & 0b101 == 0
+    assert int.from_bytes(header[8:12], "little") == 256
+
+    (_, blosc) = arr._async_array.codec_pipeline.impl.codecs()
+    assert blosc.name == "blosc"
+    assert blosc.configuration["shuffle"] == "noshuffle"
+
+
+def test_blosc_options_invalid() -> None:
+    with pytest.raises(ValueError, match="blosc_shuffle"):
+        CodecPipelineImpl("[]", blosc_shuffle="byteshuffle")
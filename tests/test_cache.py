@@ -0,0 +1,103 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import zarr
+from zarr.storage import LocalStore, LoggingStore
+
+
+def test_cache_avoids_repeated_store_reads(tmp_path):
+    store = LoggingStore(LocalStore(tmp_path / "array.zarr"))
+    data = np.arange(64, dtype=np.int32).reshape(8, 8)
+    with zarr.config.set({"codec_pipeline.cache_max_entries": 16}):
+        arr = zarr.create_array(
+            store, shape=data.shape, chunks=(4, 4), dtype=data.dtype
+        )
+        arr[:] = data
+
+        assert np.array_equal(arr[:], data)
+        gets = store.counter["get"]
+        assert np.array_equal(arr[:], data)
+        assert store.counter["get"] == gets
+
+        # writes invalidate the cached chunk
+        arr[0, 0] = -1
+        data[0, 0] = -1
+        assert np.array_equal(arr[:], data)
+
+
+def test_cache_stats(tmp_path):
+    with zarr.config.set({"codec_pipeline.cache_max_entries": 16}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(8, 8),
+            chunks=(4, 4),
+            dtype=np.uint8,
+            compressors=None,
+        )
+    impl = arr._async_array.codec_pipeline.impl
+    arr[:] = 1
+    stats = impl.stats()
+    assert stats.sets == 4
+    assert stats.bytes_written == 64
+
+    impl.reset_stats()
+    arr[:]
+    arr[:]
+    stats = impl.stats()
+    assert stats.gets == 4
+    assert stats.bytes_read == 64
+    assert stats.cache_misses == 4
+    assert stats.cache_hits == 4
+
+
+def test_partial_decoder_cache(tmp_path):
+    with zarr.config.set({"codec_pipeline.partial_decoder_cache_max_entries": 4}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(16, 16),
+            shards=(16, 16),
+            chunks=(4, 4),
+            dtype=np.int32,
+            compressors=None,
+        )
+    impl = arr._async_array.codec_pipeline.impl
+    data = np.arange(256, dtype=np.int32).reshape(16, 16)
+    arr[:] = data
+
+    impl.reset_stats()
+    assert np.array_equal(arr[:2, :2], data[:2, :2])
+    gets = impl.stats().gets
+    # The shard index is decoded once, so later reads only retrieve inner chunks
+    impl.reset_stats()
+    assert np.array_equal(arr[4:6, 4:6], data[4:6, 4:6])
+    assert impl.stats().gets < gets
+
+    # writes invalidate the cached decoder of the shard
+    arr[:] = -data
+    assert np.array_equal(arr[4:6, 4:6], -data[4:6, 4:6])
+
+
+def test_partial_decoder_cache_partial_encoding(tmp_path):
+    with zarr.config.set(
+        {
+            "codec_pipeline.partial_decoder_cache_max_entries": 4,
+            "codec_pipeline.partial_encoding": True,
+        }
+    ):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(16, 16),
+            shards=(16, 16),
+            chunks=(4, 4),
+            dtype=np.int32,
+            compressors=None,
+        )
+    data = np.arange(256, dtype=np.int32).reshape(16, 16)
+    arr[:] = data
+    assert np.array_equal(arr[4:6, 4:6], data[4:6, 4:6])
+
+    # A partially encoded write rewrites the shard index, so its cached decoder is invalidated
+    arr[4:8, 4:8] = -1
+    data[4:8, 4:8] = -1
+    assert np.array_equal(arr[4:6, 4:6], data[4:6, 4:6])
+    assert np.array_equal(arr[:], data)
@@ -0,0 +1,57 @@
+import numpy as np
+import pytest
+import zarr
+from numcodecs import Fletcher32
+from numcodecs.zarr3 import Adler32 as Adler32Codec
+from numcodecs.zarr3 import Fletcher32 as Fletcher32Codec
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.codecs import BytesCodec
+from zarr.storage import LocalStore, StorePath
+
+
+@pytest.mark.parametrize(
+    ("codec", "native"), [(Adler32Codec(), False), (Fletcher32Codec(), True)]
+)
+def test_checksum(*, store: Store, codec, native) -> None:
+    data = np.arange(64, dtype="<u4").reshape((8, 8))
+
+    a = Array.create(
+        StorePath(store, path="checksum"),
+        shape=data.shape,
+        chunk_shape=data.shape,
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=[BytesCodec(), codec],
+    )
+    # zarrs has no adler32 codec, so it falls back to zarr-python
+    assert (a._async_array.codec_pipeline.impl is not None) == native
+
+    a[:, :] = data
+    assert np.array_equal(data, a[:, :])
+
+
+def test_checksum_v2(tmp_path) -> None:
+    checksum = Fletcher32()
+    data = np.arange(64, dtype="<u4")
+    a = zarr.create(
+        shape=data.shape,
+        chunks=data.shape,
+        store=LocalStore(tmp_path / "data.zarr"),
+        dtype=data.dtype,
+        zarr_format=2,
+        filters=[checksum],
+        compressor=None,
+    )
+    a[:] = data
+    # The checksum is computed as by numcodecs
+    chunk = (tmp_path / "data.zarr" / "0").read_bytes()
+    assert chunk == bytes(checksum.encode(data.tobytes()))
+    assert np.array_equal(a[:], data)
+
+    # A corrupt checksum is detected
+    (tmp_path / "data.zarr" / "0").write_bytes(
+        chunk[:-4] + bytes([chunk[-4] ^ 1]) + chunk[-3:]
+    )
+    with pytest.raises(Exception, match="checksum"):
+        a[:]
@@ -0,0 +1,111 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import MemoryStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def test_codecs():
+    metadata = [
+        {"name": "transpose", "configuration": {"order": [1, 0]}},
+        {"name": "bytes", "configuration": {"endian": "little"}},
+        {"name": "zstd", "configuration": {"level": 3, "checksum": False}},
+    ]
+    codecs = CodecPipelineImpl(json.dumps(metadata)).codecs()
+
+    assert [codec.name for codec in codecs] == ["transpose", "bytes", "zstd"]
+    assert [codec.kind for codec in codecs] == [
+        "array_to_array",
+        "array_to_bytes",
+        "bytes_to_bytes",
+    ]
+    assert codecs[0].configuration == {"order": [1, 0]}
+    assert codecs[2].configuration["level"] == 3
+    # The bytes codec decodes subsets of chunks, but not the zstd codec
+    assert codecs[1].partial_decoding
+    assert not codecs[2].partial_decoding
+
+
+def test_codecs_metadata_objects():
+    metadata = [
+        {"name": "transpose", "configuration": {"order": (1, 0)}},
+        {"name": "bytes", "configuration": {"endian": "little"}},
+        {"name": "blosc", "configuration": {"cname": "zstd", "clevel": 5}},
+    ]
+    # The metadata is converted without serializing it to JSON
+    codecs = CodecPipelineImpl(metadata).codecs()
+    codecs_json = CodecPipelineImpl(json.dumps(metadata)).codecs()
+    assert [codec.name for codec in codecs] == [codec.name for codec in codecs_json]
+    assert [codec.configuration for codec in codecs] == [
+        codec.configuration for codec in codecs_json
+    ]
+
+    # The filters and compressor of a zarr V2 array
+    metadata_v2 = {"filters": None, "compressor": None, "dtype": "<i4", "shape": [4]}
+    assert [codec.name for codec in CodecPipelineImpl(metadata_v2).codecs()] == [
+        "bytes"
+    ]
+
+    with pytest.raises(TypeError, match="not valid JSON metadata"):
+        CodecPipelineImpl([{"name": "bytes", "configuration": {"endian": object()}}])
+    with pytest.raises(TypeError, match="not valid JSON metadata"):
+        CodecPipelineImpl([{"name": "zstd", "configuration": {"level": float("nan")}}])
+
+
+def test_codecs_sharding():
+    metadata = [
+        {
+            "name": "sharding_indexed",
+            "configuration": {
+                "chunk_shape": [2],
+                "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+                "index_codecs": [
+                    {"name": "bytes", "configuration": {"endian": "little"}},
+                    {"name": "crc32c"},
+                ],
+            },
+        }
+    ]
+    (codec,) = CodecPipelineImpl(json.dumps(metadata)).codecs()
+    assert codec.name == "sharding_indexed"
+    assert codec.kind == "array_to_bytes"
+    assert codec.partial_decoding
+    assert codec.configuration["chunk_shape"] == [2]
+
+
+def chunk_description(shape, dtype):
+    return Basic(
+        SimpleNamespace(store=MemoryStore(), path="c/0"),
+        SimpleNamespace(shape=shape, dtype=np.dtype(dtype), fill_value=0),
+    )
+
+
+def test_validate():
+    metadata = [
+        {"name": "transpose", "configuration": {"order": [1, 0]}},
+        {"name": "bytes", "configuration": {"endian": "little"}},
+    ]
+    impl = CodecPipelineImpl(json.dumps(metadata))
+    impl.validate(chunk_description((4, 8), "float32"))
+
+    # The codec rejecting the chunks is named
+    with pytest.raises(TypeError, match="codec transpose does not support") as err:
+        impl.validate(chunk_description((2, 4, 8), "float32"))
+    assert "float32" in str(err.value)
+    with pytest.raises(TypeError, match="codec transpose"):
+        impl.retrieve_chunks_and_apply_index(
+            [
+                WithSubset(
+                    chunk_description((2, 4, 8), "float32"),
+                    [slice(None)] * 3,
+                    [slice(None)] * 3,
+                    (2, 4, 8),
+                )
+            ],
+            np.zeros((2, 4, 8), dtype="float32"),
+        )
@@ -0,0 +1,86 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def chunk_description(tmp_path):
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("uint16"), fill_value=np.uint16(0)),
+    )
+    return WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+
+def test_store_empty_chunks_override(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]), store_empty_chunks=False)
+    chunk_desc = chunk_description(tmp_path)
+    empty = np.zeros(4, dtype="uint16")
+
+    impl.store_chunks_with_indices([chunk_desc], empty)
+    assert not (tmp_path / "c" / "0").exists()
+    impl.store_chunks_with_indices([chunk_desc], empty, store_empty_chunks=True)
+    assert (tmp_path / "c" / "0").read_bytes() == empty.tobytes()
+
+
+@pytest.mark.parametrize("store_empty_chunks", [True, False])
+def test_store_empty_chunks(tmp_path, store_empty_chunks):
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes"}]), store_empty_chunks=store_empty_chunks
+    )
+    chunk_desc = chunk_description(tmp_path)
+    impl.store_chunks_with_indices([chunk_desc], np.arange(1, 5, dtype="uint16"))
+
+    # Overwriting a chunk with fill values erases it unless empty chunks are stored
+    empty = np.zeros(4, dtype="uint16")
+    impl.store_chunks_with_indices([chunk_desc], empty)
+    path = tmp_path / "c" / "0"
+    assert path.exists() == store_empty_chunks
+    if store_empty_chunks:
+        assert path.read_bytes() == empty.tobytes()
+
+
+def test_validate_checksums_override(tmp_path):
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes"}, {"name": "crc32c"}]), validate_checksums=True
+    )
+    chunk_desc = chunk_description(tmp_path)
+    data = np.arange(1, 5, dtype="uint16")
+    impl.store_chunks_with_indices([chunk_desc], data, concurrent_target=1)
+
+    # Corrupt the stored checksum
+    path = tmp_path / "c" / "0"
+    path.write_bytes(path.read_bytes()[:-4] + b"\x00" * 4)
+    out = np.zeros(4, dtype="uint16")
+    with pytest.raises(Exception, match="checksum"):
+        impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out, validate_checksums=False)
+    assert np.array_equal(out, data)
+
+
+def test_unsafe_skip_write_checksums(tmp_path):
+    metadata = json.dumps([{"name": "bytes"}, {"name": "crc32c"}])
+    impl = CodecPipelineImpl(
+        metadata, unsafe_skip_write_checksums=True, validate_checksums=False
+    )
+    chunk_desc = chunk_description(tmp_path)
+    data = np.arange(1, 5, dtype="uint16")
+    impl.store_chunks_with_indices([chunk_desc], data)
+
+    # The checksum is not computed
+    assert (tmp_path / "c" / "0").read_bytes() == data.tobytes() + b"\x00" * 4
+    out = np.zeros(4, dtype="uint16")
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, data)
+    with pytest.raises(Exception, match="checksum"):
+        CodecPipelineImpl(metadata).retrieve_chunks_and_apply_index([chunk_desc], out)
+
+    # Reads of the pipeline would fail if checksums were validated
+    with pytest.raises(ValueError, match="requires validate_checksums to be false"):
+        CodecPipelineImpl(metadata, unsafe_skip_write_checksums=True)
@@ -398,3 +398,62 @@ async def test_resize(store: Store) -> None:
     )
     assert await store.get(f"{path}/1.0", prototype=default_buffer_prototype()) is None
     assert await store.get(f"{path}/1.1", prototype=default_buffer_prototype()) is None
+
+
+@pytest.mark.parametrize("store", ["local", "memory"], indirect=["store"])
+@pytest.mark.parametrize("dtype", ["float16", "float32", "float64"])
+def test_nan_fill_value_empty_chunks(store: Store, dtype: str) -> None:
+    a = Array.create(
+        StorePath(store, "nan_fill_value"),
+        shape=(8,),
+        chunk_shape=(4,),
+        dtype=dtype,
+        fill_value=np.nan,
+    )
+    data = np.arange(8, dtype=dtype)
+    a[:] = data
+    assert np.array_equal(a[:], data)
+    # Chunks of NaN with any bits (here negative) are erased like the fill value
+    a[:4] = -np.array(np.nan, dtype=dtype)
+    data[:4] = np.nan
+    assert np.array_equal(a[:], data, equal_nan=True)
+    assert a.nchunks_initialized == 1
+
+
+@pytest.mark.parametrize("store", ["local", "memory"], indirect=["store"])
+@pytest.mark.parametrize("fill_value", [1.5, -np.inf, np.nan])
+def test_float16(store: Store, fill_value: float) -> None:
+    a = Array.create(
+        StorePath(store, "float16"),
+        shape=(8,),
+        chunk_shape=(4,),
+        dtype="float16",
+        fill_value=fill_value,
+    )
+    # Unwritten elements are read as the half precision fill value
+    data = np.full(8, fill_value, dtype="float16")
+    data[5:7] = [0.25, -2.0]
+    a[5:7] = data[5:7]
+    assert np.array_equal(a[:], data, equal_nan=True)
+    assert a.nchunks_initialized == 1
+    # A chunk overwritten with the fill value is erased
+    a[4:] = fill_value
+    assert a.nchunks_initialized == 0
+
+
+@pytest.mark.parametrize("store", ["local", "memory"], indirect=["store"])
+@pytest.mark.parametrize("dtype", ["float32", "complex64", "complex128"])
+def test_signed_zero_fill_value_empty_chunks(store: Store, dtype: str) -> None:
+    a = Array.create(
+        StorePath(store, "zero_fill_value"),
+        shape=(8,),
+        chunk_shape=(4,),
+        dtype=dtype,
+        fill_value=0,
+    )
+    # Chunks of negative zero are equal to the fill value, so they are erased
+    data = np.full(8, -0.0, dtype=dtype)
+    data[5] = 1
+    a[:] = data
+    assert np.array_equal(a[:], data)
+    assert a.nchunks_initialized == 1
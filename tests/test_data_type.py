@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+import zarrs
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def test_register_data_type(tmp_path):
+    ml_dtypes = pytest.importorskip("ml_dtypes")
+    dtype = np.dtype(ml_dtypes.float8_e4m3fn)
+    zarrs.register_data_type(
+        str(dtype), 1, lambda fill_value: np.array(fill_value, dtype=dtype).tobytes()
+    )
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=dtype, fill_value=1.0),
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+    # The fill value is parsed by the registered parser
+    out = np.zeros(4, dtype=dtype)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, np.ones(4, dtype=dtype))
+
+    data = np.array([0.5, -2.0, 3.0, 448.0], dtype=dtype)
+    impl.store_chunks_with_indices([chunk_desc], data)
+    assert (tmp_path / "c" / "0").read_bytes() == data.tobytes()
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, data)
+
+
+def test_register_data_type_zero_size():
+    with pytest.raises(ValueError, match="at least 1 byte"):
+        zarrs.register_data_type("empty", 0)
+
+
+def test_raw_bits_data_type(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    byte_interface = SimpleNamespace(store=LocalStore(tmp_path), path="c/0")
+    chunk = Basic(
+        byte_interface,
+        SimpleNamespace(shape=(2,), dtype=np.dtype("V3"), fill_value=[1, 2, 3]),
+        data_type="r24",
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (2,))
+    out = np.zeros(2, dtype="V3")
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert out.tobytes() == b"\x01\x02\x03" * 2
+
+    data = np.frombuffer(b"abcdef", dtype="V3")
+    impl.store_chunks_with_indices([chunk_desc], data)
+    assert (tmp_path / "c" / "0").read_bytes() == b"abcdef"
+
+    with pytest.raises(ValueError, match="does not match the size"):
+        Basic(
+            byte_interface,
+            SimpleNamespace(shape=(2,), dtype=np.dtype("V2"), fill_value=b"\0\0"),
+            data_type="r24",
+        )
+
+
+def test_object_array_rejected(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(2,), dtype=np.dtype("int64"), fill_value=0),
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (2,))
+    value = np.array(["a", 1], dtype=object)
+    with pytest.raises(TypeError, match="store_chunks_vlen"):
+        impl.store_chunks_with_indices([chunk_desc], value)
+    with pytest.raises(TypeError, match="holds Python objects"):
+        impl.retrieve_chunks_and_apply_index([chunk_desc], value)
+    assert not (tmp_path / "c" / "0").exists()
+    assert value.tolist() == ["a", 1]
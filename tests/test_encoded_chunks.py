@@ -0,0 +1,81 @@
+#!/usr/bin/env python3
+
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+import zarr
+from zarr.codecs import ZstdCodec
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic
+
+
+def test_retrieve_chunks_encoded(tmp_path):
+    store = LocalStore(tmp_path / "array.zarr")
+    arr = zarr.create_array(
+        store,
+        shape=(8,),
+        chunks=(4,),
+        dtype=np.int32,
+        fill_value=0,
+        compressors=ZstdCodec(level=3),
+    )
+    arr[:4] = np.arange(1, 5, dtype=np.int32)
+    impl = arr._async_array.codec_pipeline.impl
+
+    chunk_spec = SimpleNamespace(shape=(4,), dtype=arr.dtype, fill_value=np.int32(0))
+    chunks = [
+        Basic(SimpleNamespace(store=store, path=path), chunk_spec)
+        for path in ["c/0", "c/1"]
+    ]
+    encoded, missing = impl.retrieve_chunks_encoded(chunks)
+    # The stored bytes are returned as is
+    assert encoded == (tmp_path / "array.zarr" / "c" / "0").read_bytes()
+    assert missing is None
+
+
+def test_store_chunks_encoded(tmp_path):
+    src = zarr.create_array(
+        LocalStore(tmp_path / "src.zarr"),
+        shape=(8,),
+        chunks=(4,),
+        dtype=np.int32,
+        fill_value=0,
+        compressors=ZstdCodec(level=3),
+    )
+    dst_store = LocalStore(tmp_path / "dst.zarr")
+    dst = zarr.create_array(
+        dst_store,
+        shape=(8,),
+        chunks=(4,),
+        dtype=np.int32,
+        fill_value=0,
+        compressors=ZstdCodec(level=3),
+    )
+    data = np.arange(8, dtype=np.int32)
+    src[:] = data
+
+    chunk_spec = SimpleNamespace(shape=(4,), dtype=src.dtype, fill_value=np.int32(0))
+    src_chunks = [
+        Basic(SimpleNamespace(store=src.store, path=path), chunk_spec)
+        for path in ["c/0", "c/1"]
+    ]
+    dst_chunks = [
+        Basic(SimpleNamespace(store=dst_store, path=path), chunk_spec)
+        for path in ["c/0", "c/1"]
+    ]
+    src_impl = src._async_array.codec_pipeline.impl
+    dst_impl = dst._async_array.codec_pipeline.impl
+
+    # Chunks are copied without recompressing them
+    encoded = src_impl.retrieve_chunks_encoded(src_chunks)
+    dst_impl.store_chunks_encoded(list(zip(dst_chunks, encoded)), validate=True)
+    assert np.array_equal(dst[:], data)
+
+    # Chunks which cannot be decoded are not stored
+    with pytest.raises(ValueError, match="c/1"):
+        dst_impl.store_chunks_encoded(
+            [(dst_chunks[0], encoded[0]), (dst_chunks[1], b"corrupt")], validate=True
+        )
+    assert np.array_equal(dst[:], data)
@@ -0,0 +1,46 @@
+#!/usr/bin/env python3
+
+import json
+
+import numpy as np
+import zarr
+from zarr.codecs import ZstdCodec
+from zarr.storage import LocalStore
+
+from zarrs._internal import CodecPipelineImpl
+
+
+def test_encoded_size_estimate():
+    bytes_codec = {"name": "bytes", "configuration": {"endian": "little"}}
+    impl = CodecPipelineImpl(json.dumps([bytes_codec]))
+    assert impl.encoded_size_estimate([4, 8], "float32") == 128
+    assert impl.encoded_size_estimate([4, 8], "<u2") == 64
+
+    # The checksum is appended to the chunk
+    impl = CodecPipelineImpl(json.dumps([bytes_codec, {"name": "crc32c"}]))
+    assert impl.encoded_size_estimate([16], "uint8") == 20
+
+    # A compressor bounds the size of the chunk by its worst case
+    zstd_codec = {"name": "zstd", "configuration": {"level": 0, "checksum": False}}
+    impl = CodecPipelineImpl(json.dumps([bytes_codec, zstd_codec]))
+    size = impl.encoded_size_estimate([1024], "int64")
+    assert size is None or size >= 8192
+
+
+def test_encoding_stats(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(64, 64),
+        chunks=(32, 32),
+        dtype=np.int32,
+        compressors=ZstdCodec(level=3),
+    )
+    impl = arr._async_array.codec_pipeline.impl
+    impl.reset_stats()
+    arr[:] = np.arange(64 * 64, dtype=np.int32).reshape(64, 64) % 8
+
+    stats = impl.stats()
+    assert stats.chunks_encoded == 4
+    assert stats.bytes_before_encoding == 64 * 64 * 4
+    assert 0 < stats.bytes_after_encoding < stats.bytes_before_encoding
+    assert stats.bytes_after_encoding == stats.bytes_written
@@ -1,11 +1,17 @@
+from pathlib import Path
+from types import SimpleNamespace
 from typing import Literal
 
 import numpy as np
 import pytest
-from zarr import AsyncArray
+import zarr
+from zarr import Array, AsyncArray, config
 from zarr.abc.store import Store
 from zarr.codecs import BytesCodec
-from zarr.storage import StorePath
+from zarr.core.buffer import default_buffer_prototype
+from zarr.storage import LocalStore, StorePath
+
+from zarrs._internal import Basic, WithSubset
 
 from .test_codecs import _AsyncArrayProxy
 
@@ -50,6 +56,104 @@ async def test_endian_write(
         codecs=[BytesCodec(endian=dtype_store_endian)],
     )
 
-    await _AsyncArrayProxy(a)[:, :].set(data)
+    # Inputs with a non-native byte order are swapped
+    with config.set({"codec_pipeline.swap_byte_order": True}):
+        await _AsyncArrayProxy(a)[:, :].set(data)
     readback_data = await _AsyncArrayProxy(a)[:, :].get()
     assert np.array_equal(data, readback_data)
+
+
+@pytest.mark.parametrize(
+    "dtype", ["int16", "uint32", "float32", "float64", "complex128"]
+)
+def test_endian_big_stored_bytes(tmp_path: Path, dtype: str) -> None:
+    data = (np.arange(0, 64) - 20).astype(dtype).reshape((8, 8))
+    a = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        chunks=(4, 8),
+        dtype=data.dtype,
+        fill_value=0,
+        serializer=BytesCodec(endian="big"),
+        compressors=None,
+    )
+    a[:] = data
+    # Chunks are stored big endian and decoded into native byte order outputs
+    chunk = (tmp_path / "c" / "1" / "0").read_bytes()
+    assert chunk == data[4:].astype(data.dtype.newbyteorder(">")).tobytes()
+    assert np.array_equal(a[:], data)
+    assert np.array_equal(a[1:7, 3:5], data[1:7, 3:5])
+    assert np.array_equal(a.oindex[[6, 1], [0, 7]], data[np.ix_([6, 1], [0, 7])])
+
+    # Partial writes update the big endian chunks
+    a[2:5, 1:3] = 1
+    data[2:5, 1:3] = 1
+    assert np.array_equal(a[:], data)
+    with config.set(
+        {"codec_pipeline.path": "zarr.core.codec_pipeline.BatchedCodecPipeline"}
+    ):
+        assert np.array_equal(zarr.open_array(LocalStore(tmp_path))[:], data)
+
+
+def test_endian_big_sharding(tmp_path: Path) -> None:
+    data = np.arange(0, 256, dtype="float64").reshape((16, 16))
+    a = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        shards=(16, 16),
+        chunks=(4, 4),
+        dtype=data.dtype,
+        fill_value=0,
+        serializer=BytesCodec(endian="big"),
+        compressors=None,
+    )
+    a[:] = data
+    # Inner chunks are partially decoded from the shard
+    assert np.array_equal(a[5:11, 2:14], data[5:11, 2:14])
+    assert np.array_equal(a[3, ::5], data[3, ::5])
+    a[6:9, 6:9] = -1
+    data[6:9, 6:9] = -1
+    assert np.array_equal(a[:], data)
+
+
+@pytest.mark.parametrize("store", ["local"], indirect=["store"])
+def test_endian_read_into_non_native(store: Store) -> None:
+    data = np.arange(0, 256, dtype="uint16").reshape((16, 16))
+    a = Array.create(
+        StorePath(store, "endian"),
+        shape=data.shape,
+        chunk_shape=(8, 8),
+        dtype=data.dtype,
+        fill_value=0,
+    )
+    a[:, :] = data
+    selection = (slice(2, 12), slice(3, 9))
+    out = default_buffer_prototype().nd_buffer.from_numpy_array(
+        np.zeros((10, 6), dtype=">u2")
+    )
+    with pytest.raises(RuntimeError, match="codec_pipeline.swap_byte_order"):
+        a.get_basic_selection(selection, out=out)
+    with pytest.raises(RuntimeError, match="codec_pipeline.swap_byte_order"):
+        a[selection] = np.zeros((10, 6), dtype=">u2")
+    assert np.array_equal(a[:, :], data)
+
+    with config.set({"codec_pipeline.swap_byte_order": True}):
+        a.get_basic_selection(selection, out=out)
+    assert np.array_equal(out.as_numpy_array(), data[selection])
+
+    # Arrays passed to the pipeline directly must have a native byte order
+    chunk_desc = WithSubset(
+        Basic(
+            SimpleNamespace(store=store, path="endian/c/0/0"),
+            SimpleNamespace(
+                shape=(8, 8), dtype=np.dtype(">u2"), fill_value=np.uint16(0)
+            ),
+        ),
+        [slice(None)],
+        [slice(None)],
+        (8, 8),
+    )
+    with pytest.raises(ValueError, match="non-native byte order"):
+        a._async_array.codec_pipeline.impl.retrieve_chunks_and_apply_index(
+            [chunk_desc], np.zeros((8, 8), dtype=">u2")
+        )
@@ -0,0 +1,59 @@
+import json
+
+import numpy as np
+import pytest
+import zarr
+from numcodecs import BitRound
+from numcodecs.zarr3 import BitRound as BitRoundCodec
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.codecs import BytesCodec, ZstdCodec
+from zarr.storage import StorePath
+
+from zarrs import experimental_codecs
+from zarrs._internal import CodecPipelineImpl
+
+
+def test_experimental_codecs():
+    assert {"bitround", "pcodec", "zfp", "zarrs.squeeze"} <= set(experimental_codecs())
+    assert "zstd" not in experimental_codecs()
+
+
+def test_experimental_codecs_disabled():
+    bitround = {"name": "bitround", "configuration": {"keepbits": 4}}
+    metadata = [bitround, {"name": "bytes", "configuration": {"endian": "little"}}]
+    CodecPipelineImpl(json.dumps(metadata))
+    with pytest.raises(TypeError, match="codec bitround is experimental"):
+        CodecPipelineImpl(json.dumps(metadata), experimental_codecs=False)
+
+    # Experimental codecs are also disabled within shards
+    sharding = {
+        "name": "sharding_indexed",
+        "configuration": {
+            "chunk_shape": [2],
+            "codecs": [{"name": "numcodecs.bitround", "configuration": {"keepbits": 4}}]
+            + metadata[1:],
+            "index_codecs": metadata[1:],
+        },
+    }
+    with pytest.raises(TypeError, match="codec bitround is experimental"):
+        CodecPipelineImpl(json.dumps([sharding]), experimental_codecs=False)
+    CodecPipelineImpl(json.dumps(metadata[1:]), experimental_codecs=False)
+
+
+def test_experimental_codecs_fall_back(store: Store) -> None:
+    data = np.linspace(-100, 100, 256, dtype="float32").reshape((16, 16))
+    with zarr.config.set({"codec_pipeline.experimental_codecs": False}):
+        a = Array.create(
+            StorePath(store, path="experimental"),
+            shape=data.shape,
+            chunk_shape=(8, 8),
+            dtype=data.dtype,
+            fill_value=0,
+            codecs=[BitRoundCodec(keepbits=4), BytesCodec(), ZstdCodec()],
+        )
+        # The array is encoded by zarr-python
+        assert a._async_array.codec_pipeline.impl is None
+
+        a[:, :] = data
+        assert np.array_equal(BitRound(keepbits=4).encode(data), a[:, :])
@@ -0,0 +1,76 @@
+#!/usr/bin/env python3
+
+import os
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+
+def test_memory_map(tmp_path):
+    data = np.arange(1000, dtype=np.float32).reshape(10, 100)
+    with zarr.config.set({"codec_pipeline.memory_map": True}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=data.shape,
+            chunks=(4, 40),
+            shards=(8, 80),
+            dtype=data.dtype,
+            compressors=None,
+        )
+        arr[:] = data
+        assert np.array_equal(arr[:], data)
+        assert np.array_equal(arr[3:7, 35:45], data[3:7, 35:45])
+
+        # chunks can be overwritten after they have been mapped
+        arr[:] = data + 1
+        assert np.array_equal(arr[:], data + 1)
+
+
+def test_memory_map_replaces_files(tmp_path):
+    with zarr.config.set({"codec_pipeline.memory_map": True}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(8,),
+            chunks=(4,),
+            dtype=np.uint16,
+            compressors=None,
+        )
+    arr[:] = 1
+    path = tmp_path / "array.zarr" / "c" / "0"
+    inode = path.stat().st_ino
+    assert np.array_equal(arr[:], np.ones(8, dtype=np.uint16))
+
+    # A mapped chunk file is replaced rather than truncated when the chunk is set
+    arr[:4] = 2
+    assert path.stat().st_ino != inode
+    # No temporary files are left behind
+    assert sorted(child.name for child in path.parent.iterdir()) == ["0", "1"]
+    assert np.array_equal(arr[:4], np.full(4, 2, dtype=np.uint16))
+
+
+def supports_direct_io(path) -> bool:
+    try:
+        fd = os.open(path / "direct_io", os.O_CREAT | os.O_WRONLY | os.O_DIRECT)
+    except (AttributeError, OSError):
+        return False
+    os.close(fd)
+    return True
+
+
+def test_direct_io(tmp_path):
+    if not supports_direct_io(tmp_path):
+        pytest.skip("the temporary directory does not support O_DIRECT")
+    data = np.arange(1000, dtype=np.uint16).reshape(10, 100)
+    with zarr.config.set({"codec_pipeline.direct_io": True}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=data.shape,
+            chunks=(3, 30),
+            dtype=data.dtype,
+        )
+        arr[:] = data
+        arr[2:5, 10:50] = 0
+        data[2:5, 10:50] = 0
+        assert np.array_equal(arr[:], data)
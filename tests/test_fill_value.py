@@ -0,0 +1,51 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import MemoryStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def read_empty_chunk(dtype: np.dtype, fill_value) -> np.ndarray:
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    chunk = Basic(
+        SimpleNamespace(store=MemoryStore(), path="c/0"),
+        SimpleNamespace(shape=(2,), dtype=dtype, fill_value=fill_value),
+    )
+    out = np.zeros(2, dtype=dtype)
+    impl.retrieve_chunks_and_apply_index(
+        [WithSubset(chunk, [slice(None)], [slice(None)], (2,))], out
+    )
+    return out
+
+
+@pytest.mark.parametrize(
+    ("dtype", "fill_value", "expected"),
+    [
+        ("float32", "NaN", np.float32(np.nan)),
+        ("float64", "Infinity", np.float64(np.inf)),
+        ("float64", "-Infinity", np.float64(-np.inf)),
+        ("float32", float("nan"), np.float32(np.nan)),
+        ("float32", "0x7fc00001", np.uint32(0x7FC00001).view(np.float32)),
+        ("int16", -3, np.int16(-3)),
+        ("uint64", 2**64 - 1, np.uint64(2**64 - 1)),
+        ("bool", True, np.True_),
+        ("complex64", [1.0, "-Infinity"], np.complex64(complex(1.0, -np.inf))),
+        ("complex128", complex(2.0, 0.5), np.complex128(complex(2.0, 0.5))),
+    ],
+)
+def test_metadata_fill_value(dtype, fill_value, expected):
+    out = read_empty_chunk(np.dtype(dtype), fill_value)
+    # Compare bits so NaN payloads are checked
+    assert out.tobytes() == np.array([expected, expected], dtype=dtype).tobytes()
+
+
+def test_invalid_metadata_fill_value():
+    with pytest.raises(ValueError):
+        read_empty_chunk(np.dtype("float32"), "not a number")
+    with pytest.raises(ValueError):
+        read_empty_chunk(np.dtype("int8"), 1000)
@@ -0,0 +1,29 @@
+import numpy as np
+import pytest
+from numcodecs import FixedScaleOffset
+from numcodecs.zarr3 import FixedScaleOffset as FixedScaleOffsetCodec
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.codecs import BytesCodec
+from zarr.storage import StorePath
+
+
+@pytest.mark.parametrize("astype", ["<i2", "<u1"])
+def test_fixedscaleoffset(*, store: Store, astype: str) -> None:
+    data = np.linspace(1000, 1025, 256, dtype="<f8").reshape((16, 16))
+    config = {"offset": 1000, "scale": 10, "dtype": "<f8", "astype": astype}
+
+    a = Array.create(
+        StorePath(store, path="fixedscaleoffset"),
+        shape=data.shape,
+        chunk_shape=(8, 8),
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=[FixedScaleOffsetCodec(**config), BytesCodec()],
+    )
+    assert a._async_array.codec_pipeline.impl is not None
+
+    a[:, :] = data
+    # Elements are packed and unpacked as by numcodecs
+    filter = FixedScaleOffset(**config)
+    assert np.array_equal(filter.decode(filter.encode(data)), a[:, :])
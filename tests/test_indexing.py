@@ -0,0 +1,471 @@
+#!/usr/bin/env python3
+
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, WithSubset
+
+strided_indices = [
+    pytest.param((slice(None, None, 2),), id="step_2"),
+    pytest.param((slice(1, 9, 3),), id="step_3_across_chunks"),
+    pytest.param((slice(None, None, 2), slice(1, None, 3)), id="2d_steps"),
+    pytest.param((slice(2, 8), slice(None, None, 4)), id="2d_inner_step"),
+    pytest.param((3, slice(None, None, 2)), id="int_and_step"),
+]
+
+int_array_indices = [
+    pytest.param((np.array([0, 3, 4, 8]),), id="discontiguous"),
+    pytest.param((np.array([7, 2, 5]),), id="unordered"),
+    pytest.param((np.array([1, 6]), slice(2, 9)), id="array_and_slice"),
+    pytest.param((slice(None, None, 3), np.array([9, 0, 4])), id="step_and_array"),
+    pytest.param((np.array([8, 1]), np.array([0, 2, 9])), id="2d_arrays"),
+]
+
+
+@pytest.fixture(params=[False, True], ids=["unsharded", "sharded"])
+def arr(request, tmp_path) -> zarr.Array:
+    arr = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=(10, 10),
+        chunks=(2, 2) if request.param else (5, 5),
+        shards=(10, 10) if request.param else None,
+        dtype=np.int32,
+        fill_value=-1,
+    )
+    arr[:] = np.arange(100, dtype=np.int32).reshape(10, 10)
+    return arr
+
+
+@pytest.mark.parametrize("index", strided_indices)
+def test_strided_read(arr: zarr.Array, index):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)[index]
+    assert np.array_equal(arr[index], expected)
+
+
+@pytest.mark.parametrize("index", strided_indices)
+def test_strided_write(arr: zarr.Array, index):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    values = -np.arange(expected[index].size, dtype=np.int32).reshape(
+        expected[index].shape
+    )
+    arr[index] = values
+    expected[index] = values
+    assert np.array_equal(arr[:], expected)
+
+    arr[index] = 7
+    expected[index] = 7
+    assert np.array_equal(arr[:], expected)
+
+
+@pytest.mark.parametrize("index", int_array_indices)
+def test_int_array_read(arr: zarr.Array, index):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    assert np.array_equal(arr.oindex[index], expected[np.ix_(*index_ranges(index))])
+
+
+@pytest.mark.parametrize("index", int_array_indices)
+def test_int_array_write(arr: zarr.Array, index):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    ix = np.ix_(*index_ranges(index))
+    values = -np.arange(expected[ix].size, dtype=np.int32).reshape(
+        expected[ix].shape
+    )
+    arr.oindex[index] = values
+    expected[ix] = values
+    assert np.array_equal(arr[:], expected)
+
+
+def test_int_array_read_repeated(arr: zarr.Array):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    index = np.array([4, 4, 0, 9, 4])
+    assert np.array_equal(arr.oindex[index, :], expected[index, :])
+
+
+@pytest.mark.parametrize(
+    "mask",
+    [
+        pytest.param(np.arange(100).reshape(10, 10) % 3 == 0, id="every_third"),
+        pytest.param(np.eye(10, dtype=bool), id="diagonal"),
+        pytest.param(np.zeros((10, 10), dtype=bool), id="empty"),
+    ],
+)
+def test_mask(arr: zarr.Array, mask):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    assert np.array_equal(arr.vindex[mask], expected[mask])
+
+    values = -np.arange(mask.sum(), dtype=np.int32)
+    arr.vindex[mask] = values
+    expected[mask] = values
+    assert np.array_equal(arr[:], expected)
+
+
+def test_dim_masks(arr: zarr.Array):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    rows = np.arange(10) % 4 == 1
+    cols = np.arange(10) > 6
+    assert np.array_equal(arr.oindex[rows, cols], expected[np.ix_(rows, cols)])
+
+    arr.oindex[rows, cols] = 0
+    expected[np.ix_(rows, cols)] = 0
+    assert np.array_equal(arr[:], expected)
+
+
+def index_ranges(index) -> list[np.ndarray]:
+    # The indices selected along each dimension by an orthogonal selection
+    return [
+        np.arange(10)[dim_index] if isinstance(dim_index, slice) else dim_index
+        for dim_index in index
+    ]
+
+
+@pytest.mark.parametrize(
+    "index",
+    [
+        pytest.param(
+            (np.array([5, 0, 3]), slice(1, 7), np.arange(6) % 2 == 0),
+            id="array_slice_mask",
+        ),
+        pytest.param(
+            (slice(None, None, 2), 4, np.array([5, 1])), id="step_int_array"
+        ),
+        pytest.param(
+            (np.array([True, False] * 3), np.array([6, 2]), slice(None)),
+            id="mask_array_full",
+        ),
+    ],
+)
+def test_oindex_3d(tmp_path, index):
+    expected = np.arange(6 * 8 * 6, dtype=np.float64).reshape(6, 8, 6)
+    arr = zarr.create_array(
+        LocalStore(tmp_path), shape=expected.shape, chunks=(4, 3, 5), dtype="f8"
+    )
+    arr[:] = expected
+    ix = orthogonal_ix(index, expected.shape)
+    assert np.array_equal(arr.oindex[index].ravel(), expected[ix].ravel())
+
+    values = -arr.oindex[index]
+    arr.oindex[index] = values
+    expected[ix] = values.reshape(expected[ix].shape)
+    assert np.array_equal(arr[:], expected)
+
+
+def orthogonal_ix(index, shape) -> tuple[np.ndarray, ...]:
+    # The numpy equivalent of an orthogonal selection
+    return np.ix_(
+        *(
+            np.arange(length)[dim_index]
+            if isinstance(dim_index, slice)
+            else np.atleast_1d(dim_index)
+            for dim_index, length in zip(index, shape)
+        )
+    )
+
+
+@pytest.mark.parametrize(
+    "coordinates",
+    [
+        pytest.param((np.array([9, 0, 4, 4]), np.array([1, 8, 4, 2])), id="unordered"),
+        pytest.param(
+            (np.array([[0, 9], [5, 2]]), np.array([[3, 3], [6, 0]])),
+            id="2d_coordinates",
+        ),
+    ],
+)
+def test_vindex(arr: zarr.Array, coordinates):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    assert np.array_equal(arr.vindex[coordinates], expected[coordinates])
+
+    values = -np.arange(expected[coordinates].size, dtype=np.int32).reshape(
+        expected[coordinates].shape
+    )
+    arr.vindex[coordinates] = values
+    expected[coordinates] = values
+    assert np.array_equal(arr[:], expected)
+
+
+def test_vindex_repeated(arr: zarr.Array):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    coordinates = (np.array([3, 8, 3]), np.array([7, 0, 7]))
+    assert np.array_equal(arr.vindex[coordinates], expected[coordinates])
+
+
+@pytest.fixture
+def single_chunk(tmp_path) -> tuple[zarr.Array, Basic]:
+    arr = zarr.create_array(
+        LocalStore(tmp_path), shape=(4, 4), chunks=(4, 4), dtype=np.int32
+    )
+    arr[:] = np.arange(16, dtype=np.int32).reshape(4, 4)
+    chunk = Basic(
+        SimpleNamespace(store=arr.store, path="c/0/0"),
+        SimpleNamespace(shape=(4, 4), dtype=np.dtype(np.int32), fill_value=np.int32(0)),
+    )
+    return arr, chunk
+
+
+def test_ellipsis_and_newaxis(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+
+    out = np.zeros((1, 4, 4), dtype=np.int32)
+    chunk_desc = WithSubset(chunk, [Ellipsis], [None, Ellipsis], out.shape)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out[0], arr[:])
+
+    out = np.zeros((2, 1, 4), dtype=np.int32)
+    chunk_desc = WithSubset(
+        chunk, [slice(1, 3)], [Ellipsis, None, slice(None)], out.shape
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out[:, 0], arr[1:3])
+
+    with pytest.raises(IndexError):
+        WithSubset(chunk, [Ellipsis, Ellipsis], [], out.shape)
+
+
+def test_newaxis_chunk_selection(single_chunk):
+    _, chunk = single_chunk
+    # numpy inserts an axis for None, so it cannot select chunk elements
+    with pytest.raises(IndexError, match="newaxis"):
+        WithSubset(chunk, [None, Ellipsis], [slice(None)] * 3, (1, 4, 4))
+    with pytest.raises(IndexError, match="newaxis"):
+        WithSubset(chunk, [slice(None), None], [slice(None)] * 2, (4, 4))
+
+
+def test_negative_slice_bounds(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((3, 2), dtype=np.int32)
+    chunk_desc = WithSubset(
+        chunk,
+        [slice(-3, None), slice(-100, -2)],
+        [slice(None), slice(-5, 2)],
+        out.shape,
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, arr[-3:, -100:-2])
+
+
+@pytest.mark.parametrize("dtype", ["int8", "int32", "intp", "uint16", "uint64"])
+def test_int_array_dtypes(single_chunk, dtype):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((2, 4), dtype=np.int32)
+    index = np.array([3, 1], dtype=dtype)
+    chunk_desc = WithSubset(chunk, [index], [slice(None)], out.shape)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, arr[[3, 1]])
+
+
+@pytest.mark.parametrize("dtype", ["int8", "int32", "intp", "uint16", "uint64"])
+def test_chunk_coordinates_dtypes(single_chunk, dtype):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros(3, dtype=np.int32)
+    coordinates = [np.array([3, 0, 1], dtype=dtype), np.array([2, 2, 0], dtype=dtype)]
+    chunk_desc = WithSubset(
+        chunk, [], [slice(None)], out.shape, chunk_coordinates=coordinates
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, arr.vindex[[3, 0, 1], [2, 2, 0]])
+
+
+def test_int_array_negative_indices(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((4, 2), dtype=np.int32)
+    # Negative indices count back from the end of the chunk, as in numpy
+    index = np.array([-1, 0], dtype=np.int32)
+    chunk_desc = WithSubset(chunk, [slice(None), index], [slice(None)], out.shape)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, np.asarray(arr[:])[:, [-1, 0]])
+
+    with pytest.raises(IndexError, match="index -5 is out of bounds"):
+        WithSubset(chunk, [np.array([-5])], [slice(None)], (1, 4))
+    with pytest.raises(TypeError, match="integer or boolean"):
+        WithSubset(chunk, [np.array([0.5])], [slice(None)], (1, 4))
+
+
+def test_tuple_slice_bounds(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((2, 2), dtype=np.int32)
+    # `(start, stop, step)` tuples select the same elements as slices
+    chunk_desc = WithSubset(
+        chunk, [(1, 3, 1), (None, None, 2)], [(0, 2, None), slice(None)], out.shape
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, arr[1:3, ::2])
+
+    impl.store_chunks_with_indices([chunk_desc], -out)
+    assert np.array_equal(arr[1:3, ::2], -out)
+
+
+@pytest.mark.parametrize(
+    ("selection", "match"),
+    [
+        pytest.param([slice(None), slice(3, 0, -1)], "axis 1", id="negative_step"),
+        pytest.param([slice(None, None, 0)], "axis 0", id="zero_step"),
+        pytest.param([(0, 4)], "axis 0", id="tuple_length"),
+        pytest.param([np.array([1, 4])], "axis 0", id="out_of_bounds"),
+    ],
+)
+def test_invalid_selection(single_chunk, selection, match):
+    _, chunk = single_chunk
+    with pytest.raises((IndexError, ValueError), match=match):
+        WithSubset(chunk, selection, [slice(None)], (4,))
+
+
+def test_mismatched_selection(single_chunk):
+    _, chunk = single_chunk
+    with pytest.raises(ValueError, match=r"\[2, 4\].*\[3, 4\].*chunk c/0/0"):
+        WithSubset(chunk, [slice(0, 2)], [slice(1, 4)], (4, 4))
+    with pytest.raises(IndexError, match="axis 0.*chunk c/0/0"):
+        WithSubset(chunk, [np.array([1, 4])], [slice(None)], (2, 4))
+
+
+def test_empty_selection(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((0, 4), dtype=np.int32)
+    chunk_desc = WithSubset(chunk, [slice(2, 2)], [slice(None)], out.shape)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    impl.store_chunks_with_indices([chunk_desc], out)
+    impl.retrieve_chunks_and_apply_index([], out)
+
+    assert arr[2:2].shape == (0, 4)
+    arr[1:1, :] = np.zeros((0, 4), dtype=np.int32)
+    assert np.array_equal(arr[:], np.arange(16, dtype=np.int32).reshape(4, 4))
+
+
+def test_zero_size_chunk_shape(single_chunk):
+    arr, _ = single_chunk
+    with pytest.raises(ValueError, match="zero-size"):
+        Basic(
+            SimpleNamespace(store=arr.store, path="c/0/0"),
+            SimpleNamespace(
+                shape=(0, 4), dtype=np.dtype(np.int32), fill_value=np.int32(0)
+            ),
+        )
+
+
+def test_coalesced_writes(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    value = -np.arange(8, dtype=np.int32).reshape(2, 4)
+    # The input is written to overlapping rows of the same chunk
+    chunk_descs = [
+        WithSubset(chunk, [slice(0, 2)], [slice(None)], value.shape),
+        WithSubset(chunk, [slice(1, 3)], [slice(None)], value.shape),
+    ]
+    impl.store_chunks_with_indices(chunk_descs, value)
+    expected = np.arange(16, dtype=np.int32).reshape(4, 4)
+    expected[0:2] = value
+    expected[1:3] = value
+    assert np.array_equal(arr[:], expected)
+
+
+@pytest.mark.parametrize("repeat", range(5))
+def test_duplicate_writes_last_wins(arr: zarr.Array, repeat):
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    coordinates = (np.array([3, 8, 3, 3]), np.array([7, 0, 7, 7]))
+    values = np.array([-1, -2, -3, -4], dtype=np.int32)
+    arr.vindex[coordinates] = values
+    expected[coordinates] = values
+    assert arr[3, 7] == -4
+    assert np.array_equal(arr[:], expected)
+
+    rows = np.array([6, 2, 6, 2])
+    row_values = -np.arange(40, dtype=np.int32).reshape(4, 10)
+    arr.oindex[rows, :] = row_values
+    expected[rows, :] = row_values
+    assert np.array_equal(arr[:], expected)
+
+
+@pytest.mark.parametrize(
+    "selection",
+    [
+        pytest.param((slice(None),), id="slice"),
+        pytest.param((np.array([3, 1, 0, 2]),), id="permuted_rows"),
+        pytest.param((np.array([1, 0, 3, 2, 1]), slice(None)), id="repeated_rows"),
+    ],
+)
+def test_whole_chunk_writes_skip_read(single_chunk, selection):
+    arr, _ = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    value = -np.arange(arr.oindex[selection].size, dtype=np.int32).reshape(
+        arr.oindex[selection].shape
+    )
+    expected = arr[:]
+    expected[selection] = value
+    impl.reset_stats()
+    arr.oindex[selection] = value
+    assert impl.stats().gets == 0
+    assert np.array_equal(arr[:], expected)
+
+
+def test_output_axes(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((3,), dtype=np.int32)
+    # The row selected with an integer is dropped from the output
+    chunk_desc = WithSubset(
+        chunk,
+        [slice(2, 3), np.array([3, 0, 3])],
+        [slice(None)],
+        out.shape,
+        output_axes=[None, 0],
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, arr[2, [3, 0, 3]])
+
+    value = np.array([-1, -2, -3], dtype=np.int32)
+    impl.store_chunks_with_indices([chunk_desc], value)
+    assert np.array_equal(arr[2], [-2, 9, 10, -3])
+
+
+@pytest.mark.parametrize(
+    ("output_axes", "match"),
+    [
+        pytest.param([0], "an output axis for each", id="too_few"),
+        pytest.param([0, None], "dropped axis 1", id="dropped_non_singleton"),
+        pytest.param([1, 0], "increasing", id="transposed"),
+        pytest.param([0, 0], "increasing", id="repeated"),
+        pytest.param([0, 2], "output axis 2", id="out_of_range"),
+    ],
+)
+def test_invalid_output_axes(single_chunk, output_axes, match):
+    _, chunk = single_chunk
+    with pytest.raises(ValueError, match=match):
+        WithSubset(
+            chunk,
+            [slice(0, 2), slice(1, 3)],
+            [slice(None), slice(None)],
+            (2, 2),
+            output_axes=output_axes,
+        )
+
+
+def test_huge_slice_bounds(single_chunk):
+    arr, chunk = single_chunk
+    impl = arr._async_array.codec_pipeline.impl
+    out = np.zeros((2, 4), dtype=np.int32)
+    # Bounds beyond 64-bit integers are clamped like any other out of bounds slice
+    chunk_desc = WithSubset(
+        chunk, [slice(-(2**70), 2), slice(None, 2**70, 2**70)], [slice(None)], (2, 1)
+    )
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out[:, :1])
+    assert np.array_equal(out[:, 0], arr[:2, 0])
+
+
+def test_huge_dimension(tmp_path):
+    length = 2**40
+    arr = zarr.create_array(
+        LocalStore(tmp_path), shape=(length,), chunks=(4,), dtype=np.int32
+    )
+    arr[length - 6 :] = np.arange(6, dtype=np.int32)
+    assert np.array_equal(arr[-5:], np.arange(1, 6))
+    assert np.array_equal(arr[length - 7 :: 3], [0, 2, 5])
@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+
+import json
+
+import numpy as np
+import pytest
+import zarr
+from zarr.codecs import ShardingCodec
+from zarr.storage import LocalStore
+
+from zarrs._internal import CodecPipelineImpl
+
+
+def test_io_concurrent_limit(tmp_path):
+    data = np.arange(64 * 64, dtype=np.uint16).reshape(64, 64)
+    with zarr.config.set({"codec_pipeline.io_concurrent_limit": 64}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=data.shape,
+            chunks=(8, 8),
+            dtype=data.dtype,
+        )
+        arr[:] = data
+        arr[3:13, 5] = 0
+        data[3:13, 5] = 0
+        assert np.array_equal(arr[:], data)
+        assert np.array_equal(arr[10:20, 30:40], data[10:20, 30:40])
+
+
+def test_io_concurrent_limit_sharding(tmp_path):
+    data = np.arange(64 * 64, dtype=np.uint16).reshape(64, 64)
+    with zarr.config.set({"codec_pipeline.io_concurrent_limit": 64}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=data.shape,
+            chunks=(32, 32),
+            shards=(64, 64),
+            dtype=data.dtype,
+        )
+        arr[:] = data
+        assert isinstance(arr.metadata.codecs[0], ShardingCodec)
+        assert np.array_equal(arr[5:40, 10:50], data[5:40, 10:50])
+
+
+@pytest.mark.parametrize("codec_concurrent_target", [1, 4])
+def test_codec_concurrent_target(tmp_path, codec_concurrent_target):
+    data = np.arange(64 * 64, dtype=np.uint16).reshape(64, 64)
+    with zarr.config.set(
+        {
+            "codec_pipeline.codec_concurrent_target": codec_concurrent_target,
+            "threading.max_workers": 4,
+        }
+    ):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=data.shape,
+            chunks=(8, 8),
+            shards=(32, 64),
+            dtype=data.dtype,
+        )
+        arr[:] = data
+        assert np.array_equal(arr[:], data)
+        assert np.array_equal(arr[5:40, 10:50], data[5:40, 10:50])
+
+
+def test_codec_concurrent_target_invalid():
+    with pytest.raises(ValueError, match="codec_concurrent_target"):
+        CodecPipelineImpl(json.dumps([{"name": "bytes"}]), codec_concurrent_target=0)
+
+
+@pytest.mark.parametrize(
+    ("options", "match"),
+    [
+        ({"multipart_part_size": 1024 * 1024}, "at least 5242880 bytes"),
+        ({"multipart_concurrency": 8}, "requires multipart_part_size"),
+    ],
+)
+def test_multipart_options_invalid(options, match):
+    with pytest.raises(ValueError, match=match):
+        CodecPipelineImpl(json.dumps([{"name": "bytes"}]), **options)
@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+
+@pytest.mark.parametrize(
+    ("key_transforms", "chunk_path"),
+    [
+        (["add_prefix:chunks/"], "chunks/c/1/0"),
+        (["v2_chunk_keys"], "1.0"),
+        (["v2_chunk_keys:/", "add_prefix:legacy/"], "legacy/1/0"),
+        (["add_prefix:x/", "strip_prefix:x/"], "c/1/0"),
+    ],
+)
+def test_key_transforms(tmp_path, key_transforms, chunk_path):
+    data = np.arange(64, dtype=np.uint8).reshape(8, 8)
+    with zarr.config.set({"codec_pipeline.key_transforms": key_transforms}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path), shape=(8, 8), chunks=(4, 4), dtype=data.dtype
+        )
+        arr[:] = data
+        assert (tmp_path / chunk_path).is_file()
+        assert np.array_equal(zarr.open_array(LocalStore(tmp_path))[:], data)
+
+
+def test_key_transforms_hash_directory(tmp_path):
+    data = np.arange(64, dtype=np.uint8)
+    with zarr.config.set({"codec_pipeline.key_transforms": ["hash_directory:2"]}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path), shape=(64,), chunks=(4,), dtype=data.dtype
+        )
+        arr[:] = data
+        assert np.array_equal(zarr.open_array(LocalStore(tmp_path))[:], data)
+    chunk_dirs = [path for path in tmp_path.iterdir() if path.is_dir()]
+    assert len(chunk_dirs) > 1
+    assert all(len(path.name) == 2 for path in chunk_dirs)
+
+
+def test_unsupported_key_transform(tmp_path):
+    with (
+        zarr.config.set({"codec_pipeline.key_transforms": ["hash_directory:0"]}),
+        pytest.raises(ValueError, match="Unsupported key transform"),
+    ):
+        zarr.create_array(LocalStore(tmp_path), shape=(8,), chunks=(4,), dtype="u1")
+
+
+def test_key_transforms_listing(tmp_path):
+    with zarr.config.set({"codec_pipeline.key_transforms": ["add_prefix:chunks/"]}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path), shape=(8,), chunks=(4,), dtype="u1"
+        )
+    arr[:] = 1
+    impl = arr._async_array.codec_pipeline.impl
+    # Transformed keys cannot be mapped back, so they are not listed
+    for list_keys in [
+        lambda: impl.list(arr.store),
+        lambda: impl.list_prefix(arr.store, "c/"),
+        lambda: impl.list_dir(arr.store, "c/"),
+    ]:
+        with pytest.raises(RuntimeError, match="not supported with key transforms"):
+            list_keys()
+
+
+def test_key_transforms_erase_prefix(tmp_path):
+    with zarr.config.set({"codec_pipeline.key_transforms": ["strip_prefix:c/"]}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path), shape=(8,), chunks=(4,), dtype="u1"
+        )
+    arr[:] = 1
+    impl = arr._async_array.codec_pipeline.impl
+    (tmp_path / "other").write_bytes(b"unrelated")
+    with pytest.raises(ValueError, match="cannot erase a prefix with key_transforms"):
+        impl.erase_prefix(arr.store, "c/")
+    # Neither the chunks nor unrelated keys are erased
+    assert (tmp_path / "other").read_bytes() == b"unrelated"
+    assert np.array_equal(arr[:], np.ones(8, dtype="u1"))
@@ -0,0 +1,44 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+
+@pytest.fixture
+def arr(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path),
+        name="array",
+        shape=(8, 8),
+        chunks=(4, 4),
+        dtype=np.uint8,
+    )
+    arr[:] = 1
+    return arr
+
+
+def test_list(arr):
+    impl = arr._async_array.codec_pipeline.impl
+    chunk_keys = ["array/c/0/0", "array/c/0/1", "array/c/1/0", "array/c/1/1"]
+    keys = impl.list(arr.store)
+    assert {*chunk_keys, "array/zarr.json"} <= set(keys)
+    assert impl.list_prefix(arr.store, "array/c/") == chunk_keys
+    assert impl.list_prefix(arr.store, "array/c") == chunk_keys
+
+
+def test_list_dir(arr):
+    impl = arr._async_array.codec_pipeline.impl
+    assert impl.list_dir(arr.store, "array") == (["array/zarr.json"], ["array/c/"])
+    assert impl.list_dir(arr.store, "array/c/0/") == (
+        ["array/c/0/0", "array/c/0/1"],
+        [],
+    )
+
+
+def test_erase_prefix(arr):
+    impl = arr._async_array.codec_pipeline.impl
+    impl.erase_prefix(arr.store, "array/c")
+    assert impl.list_prefix(arr.store, "array/") == ["array/zarr.json"]
+    assert np.all(arr[:] == arr.fill_value)
@@ -0,0 +1,48 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def chunk(tmp_path, path: str) -> Basic:
+    return Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path=path),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("int32"), fill_value=np.int32(0)),
+    )
+
+
+def test_retrieve_mask(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    # The first chunk is stored (with zeros equal to the fill value), the second is not
+    stored = chunk(tmp_path, "c/0")
+    (tmp_path / "c").mkdir()
+    (tmp_path / "c" / "0").write_bytes(np.array([0, 1, 0, 2], dtype=np.int32).tobytes())
+    missing = chunk(tmp_path, "c/1")
+
+    out = np.full(6, -1, dtype=np.int32)
+    mask = np.zeros(6, dtype=bool)
+    impl.retrieve_chunks_and_apply_index(
+        [
+            WithSubset(stored, [slice(1, 4)], [slice(0, 3)], (6,)),
+            WithSubset(missing, [np.array([3, 0])], [slice(3, 5)], (6,)),
+        ],
+        out,
+        mask=mask,
+    )
+    assert out.tolist() == [1, 0, 2, 0, 0, -1]
+    assert mask.tolist() == [True, True, True, False, False, False]
+
+
+def test_retrieve_mask_invalid(tmp_path):
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    chunk_desc = WithSubset(chunk(tmp_path, "c/0"), [slice(None)], [slice(None)], (4,))
+    out = np.zeros(4, dtype=np.int32)
+    for mask in [np.zeros(4, dtype=np.uint8), np.zeros(3, dtype=bool)]:
+        with pytest.raises(ValueError, match="mask must be a bool array"):
+            impl.retrieve_chunks_and_apply_index([chunk_desc], out, mask=mask)
@@ -0,0 +1,101 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.core.buffer import default_buffer_prototype
+from zarr.storage import LocalStore
+
+
+@pytest.fixture(params=["C", "F"])
+def arr(request, tmp_path) -> zarr.Array:
+    return zarr.create_array(
+        LocalStore(tmp_path),
+        shape=(10, 12),
+        chunks=(4, 5),
+        dtype=np.float32,
+        fill_value=0,
+        config={"order": request.param},
+    )
+
+
+def test_fortran_order_input(arr: zarr.Array):
+    data = np.asfortranarray(np.arange(120, dtype=np.float32).reshape(10, 12))
+    arr[:] = data
+    assert np.array_equal(arr[:], data)
+
+    arr[2:9, 1:7] = np.asfortranarray(-data[2:9, 1:7])
+    data[2:9, 1:7] *= -1
+    assert np.array_equal(arr[:], data)
+
+
+def test_fortran_order_output(arr: zarr.Array):
+    data = np.arange(120, dtype=np.float32).reshape(10, 12)
+    arr[:] = data
+    assert np.array_equal(arr[1:8, 3:11], data[1:8, 3:11])
+    assert np.array_equal(arr.oindex[[7, 2], 4:], data[[7, 2], 4:])
+
+
+@pytest.mark.parametrize(
+    "view",
+    [
+        pytest.param(lambda big: big[1:11, 2:14], id="subset"),
+        pytest.param(lambda big: big[::2, 1::2][:10, :12], id="steps"),
+        pytest.param(lambda big: big[::-1, ::-1][:10, :12], id="reversed"),
+        pytest.param(lambda big: big[:12, :10].T, id="transposed"),
+    ],
+)
+def test_strided_output(arr: zarr.Array, view):
+    data = np.arange(120, dtype=np.float32).reshape(10, 12)
+    arr[:] = data
+    big = np.full((24, 30), -1, dtype=np.float32)
+    out = view(big)
+    arr.get_basic_selection(
+        ..., out=default_buffer_prototype().nd_buffer.from_numpy_array(out)
+    )
+    assert np.array_equal(out, data)
+    # Elements outside of the view are untouched
+    assert (big == -1).sum() == big.size - data.size
+
+
+def test_reversed_output_indexed(arr: zarr.Array):
+    data = np.arange(120, dtype=np.float32).reshape(10, 12)
+    arr[:] = data
+    buffer = default_buffer_prototype().nd_buffer
+    rows, cols = np.array([9, 0, 4, 4]), np.array([11, 3, 0])
+
+    out = np.zeros((4, 3), dtype=np.float32)[::-1, ::-1]
+    arr.get_orthogonal_selection((rows, cols), out=buffer.from_numpy_array(out))
+    assert np.array_equal(out, data[np.ix_(rows, cols)])
+
+    out = np.zeros(3, dtype=np.float32)[::-1]
+    arr.get_coordinate_selection(
+        (rows[:3], cols), out=buffer.from_numpy_array(out)
+    )
+    assert np.array_equal(out, data[rows[:3], cols])
+
+
+@pytest.mark.parametrize(
+    "value",
+    [
+        pytest.param(np.float32(3), id="scalar"),
+        pytest.param(np.array([3], dtype=np.float32), id="size_1"),
+        pytest.param(np.broadcast_to(np.float32(3), (7, 8)), id="broadcast"),
+    ],
+)
+def test_constant_write(arr: zarr.Array, value):
+    data = np.arange(120, dtype=np.float32).reshape(10, 12)
+    arr[:] = data
+    arr[2:9, 1:9] = value
+    data[2:9, 1:9] = 3
+    assert np.array_equal(arr[:], data)
+
+
+def test_constant_fill_value_erases_chunks(arr: zarr.Array):
+    arr[:] = 1
+    arr[:8, :10] = 0
+    # The chunks entirely within the selection are erased, the others are updated
+    assert arr.nchunks_initialized == arr.nchunks - 4
+    expected = np.ones((10, 12), dtype=np.float32)
+    expected[:8, :10] = 0
+    assert np.array_equal(arr[:], expected)
@@ -0,0 +1,24 @@
+#!/usr/bin/env python3
+
+import gc
+import weakref
+
+import numpy as np
+import zarr
+from zarr.storage import MemoryStore
+
+
+def test_memory_store_not_kept_alive():
+    store = MemoryStore()
+    arr = zarr.create_array(store, shape=(8,), chunks=(4,), dtype=np.uint8)
+    arr[:] = 1
+    assert np.array_equal(arr[:], np.ones(8, dtype=np.uint8))
+    codec_pipeline = arr._async_array.codec_pipeline
+    store_ref = weakref.ref(store)
+
+    # The codec pipeline does not keep the store alive
+    del arr, store
+    gc.collect()
+    assert store_ref() is None
+    assert codec_pipeline.impl is not None
+
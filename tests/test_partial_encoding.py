@@ -0,0 +1,126 @@
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore, MemoryStore
+
+
+def test_partial_encoding_sharded(tmp_path):
+    with zarr.config.set({"codec_pipeline.partial_encoding": True}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(16, 16),
+            shards=(16, 16),
+            chunks=(4, 4),
+            dtype=np.int32,
+            fill_value=0,
+        )
+    expected = np.zeros((16, 16), dtype=np.int32)
+    # Each write updates some inner chunks of the shard, including ones written before
+    for i, region in enumerate(
+        [np.s_[:4, :4], np.s_[2:10, 3:7], np.s_[12:, :], np.s_[1, 1], np.s_[:4, :4]]
+    ):
+        arr[region] = i + 1
+        expected[region] = i + 1
+        assert np.array_equal(arr[:], expected)
+
+
+def test_partial_write_uncompressed(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(8, 8),
+        chunks=(8, 8),
+        dtype=np.uint16,
+        fill_value=0,
+        compressors=None,
+    )
+    impl = arr._async_array.codec_pipeline.impl
+    arr[:] = 1
+    expected = np.ones((8, 8), dtype=np.uint16)
+
+    # Only the bytes of the rows of the region are written, the chunk is not read
+    impl.reset_stats()
+    arr[2:4, 1:5] = 7
+    expected[2:4, 1:5] = 7
+    stats = impl.stats()
+    assert stats.bytes_read == 0
+    assert stats.bytes_written == 2 * 4 * 2
+    chunk = (tmp_path / "array.zarr" / "c" / "0" / "0").read_bytes()
+    assert chunk == expected.astype("<u2").tobytes()
+    assert np.array_equal(arr[:], expected)
+
+
+def test_partial_write_uncompressed_fill_value(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(8, 8),
+        chunks=(8, 8),
+        dtype=np.uint16,
+        fill_value=0,
+        compressors=None,
+    )
+    arr[:] = 1
+    arr[:4] = 0
+    # The chunk is updated entirely when written with the fill value, so it is erased
+    arr[4:] = 0
+    assert arr.nchunks_initialized == 0
+    assert np.array_equal(arr[:], np.zeros((8, 8), dtype=np.uint16))
+
+
+def test_partial_write_uncompressed_truncated(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(8, 8),
+        chunks=(8, 8),
+        dtype=np.uint16,
+        fill_value=0,
+        compressors=None,
+    )
+    arr[:] = 1
+    path = tmp_path / "array.zarr" / "c" / "0" / "0"
+    path.write_bytes(path.read_bytes()[:64])
+
+    # A chunk of the wrong size is not written in place, so the write fails on decoding
+    with pytest.raises(Exception):
+        arr[2:4, 1:5] = 7
+    assert len(path.read_bytes()) == 64
+
+
+def test_partial_write_uncompressed_memory_store():
+    arr = zarr.create_array(
+        MemoryStore(),
+        shape=(8, 8),
+        chunks=(8, 8),
+        dtype=np.uint16,
+        fill_value=0,
+        compressors=None,
+    )
+    impl = arr._async_array.codec_pipeline.impl
+    arr[:] = 1
+
+    # The memory store does not write in place, so the chunk is read and rewritten
+    impl.reset_stats()
+    arr[2:4, 1:5] = 7
+    assert impl.stats().bytes_read == 8 * 8 * 2
+    expected = np.ones((8, 8), dtype=np.uint16)
+    expected[2:4, 1:5] = 7
+    assert np.array_equal(arr[:], expected)
+
+
+def test_partial_write_compressed(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(8, 8),
+        chunks=(8, 8),
+        dtype=np.uint16,
+        fill_value=0,
+    )
+    impl = arr._async_array.codec_pipeline.impl
+    arr[:] = 1
+
+    # Compressed chunks are read and rewritten entirely
+    impl.reset_stats()
+    arr[2:4, 1:5] = 7
+    assert impl.stats().bytes_read > 0
+    expected = np.ones((8, 8), dtype=np.uint16)
+    expected[2:4, 1:5] = 7
+    assert np.array_equal(arr[:], expected)
@@ -0,0 +1,27 @@
+import numpy as np
+import pytest
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.storage import StorePath
+
+pytest.importorskip("pcodec")
+from numcodecs.zarr3 import PCodec  # noqa: E402
+
+
+@pytest.mark.parametrize("dtype", ["uint16", "int32", "float32", "float64"])
+@pytest.mark.parametrize("delta_spec", ["auto", "none"])
+def test_pcodec(*, store: Store, dtype: str, delta_spec: str) -> None:
+    data = np.arange(0, 256, dtype=dtype).reshape((16, 16))
+
+    a = Array.create(
+        StorePath(store, path="pcodec"),
+        shape=data.shape,
+        chunk_shape=(8, 8),
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=[PCodec(level=8, delta_spec=delta_spec)],
+    )
+    assert a._async_array.codec_pipeline.impl is not None
+
+    a[:, :] = data
+    assert np.array_equal(data, a[:, :])
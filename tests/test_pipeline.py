@@ -256,3 +256,16 @@ def test_roundtrip_read_only_zarrs(
     assert np.all(
         res == store_values,
     ), res
+
+
+def test_validate_once(tmp_path: Path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path), shape=(8, 8), chunks=(2, 2), dtype=np.float32
+    )
+    arr[:] = 1
+    assert np.array_equal(arr[:], np.ones((8, 8), dtype=np.float32))
+    arr[1:5, 3] = 2
+    # The codecs are validated once for the chunks of the array
+    codec_pipeline = arr._async_array.codec_pipeline
+    assert codec_pipeline.impl is not None
+    assert len(codec_pipeline.validated_chunk_specs) == 1
@@ -0,0 +1,64 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+from zarr.storage import LocalStore
+
+import zarrs
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def xor(data: bytes, configuration: dict) -> bytes:
+    return bytes(byte ^ configuration["key"] for byte in data)
+
+
+def test_register_codec(tmp_path):
+    zarrs.register_codec("test.xor", xor, xor)
+    impl = CodecPipelineImpl(
+        json.dumps(
+            [
+                {"name": "bytes", "configuration": {"endian": "little"}},
+                {"name": "test.xor", "configuration": {"key": 0xFF}},
+            ]
+        )
+    )
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("<u2"), fill_value=np.uint16(0)),
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+    data = np.array([1, 2, 3, 4], dtype="<u2")
+    impl.store_chunks_with_indices([chunk_desc], data)
+    assert (tmp_path / "c" / "0").read_bytes() == xor(data.tobytes(), {"key": 0xFF})
+    out = np.zeros(4, dtype="<u2")
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out)
+    assert np.array_equal(out, data)
+
+
+def test_register_codec_error(tmp_path):
+    def fail(data: bytes, configuration: None) -> bytes:
+        raise ValueError("broken codec")
+
+    zarrs.register_codec("test.fail", fail, fail)
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}, {"name": "test.fail"}]))
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("uint8"), fill_value=np.uint8(0)),
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+    with pytest.raises(RuntimeError, match="broken codec"):
+        impl.store_chunks_with_indices([chunk_desc], np.arange(1, 5, dtype=np.uint8))
+
+
+def test_unregistered_codec():
+    with pytest.raises(TypeError):
+        CodecPipelineImpl(json.dumps([{"name": "bytes"}, {"name": "test.missing"}]))
+
+
+def test_register_codec_zarrs_name():
+    with pytest.raises(ValueError, match="implemented by zarrs"):
+        zarrs.register_codec("zstd", xor, xor)
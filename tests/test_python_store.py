@@ -0,0 +1,70 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import FsspecStore, LocalStore, LoggingStore, WrapperStore
+
+
+def test_python_store_roundtrip(tmp_path):
+    # LoggingStore has no native equivalent, so chunks go through the Python store bridge
+    store = LoggingStore(LocalStore(tmp_path / "array.zarr"))
+    arr = zarr.create_array(
+        store, shape=(10, 10), chunks=(4, 4), dtype=np.uint16, fill_value=7
+    )
+    assert np.all(arr[:] == 7)
+
+    data = np.arange(100, dtype=np.uint16).reshape(10, 10)
+    arr[:] = data
+    arr[1:3, 2:9] = 0
+    data[1:3, 2:9] = 0
+    assert np.array_equal(arr[:], data)
+    assert np.array_equal(arr[5:, 3], data[5:, 3])
+
+    # chunks written through the bridge are visible to the wrapped store
+    arr_local = zarr.open_array(LocalStore(tmp_path / "array.zarr"))
+    assert np.array_equal(arr_local[:], data)
+    assert store.counter["set"] > 0
+
+
+def test_python_store_warning(tmp_path):
+    class BridgedStore(WrapperStore):
+        pass
+
+    store = BridgedStore(LocalStore(tmp_path / "array.zarr"))
+    arr = zarr.create_array(store, shape=(4,), chunks=(2,), dtype=np.uint8)
+    with pytest.warns(UserWarning, match="BridgedStore has no native zarrs equivalent"):
+        arr[:] = np.arange(4, dtype=np.uint8)
+    assert np.array_equal(arr[:], np.arange(4, dtype=np.uint8))
+
+
+def test_python_store_partial_reads(tmp_path):
+    store = LoggingStore(LocalStore(tmp_path / "array.zarr"))
+    arr = zarr.create_array(
+        store, shape=(64,), chunks=(4,), shards=(64,), dtype=np.uint16
+    )
+    data = np.arange(64, dtype=np.uint16)
+    arr[:] = data
+
+    # Inner chunks of shards are read as byte ranges, rather than whole shards
+    store.counter.clear()
+    assert np.array_equal(arr[8:12], data[8:12])
+    assert store.counter["get_partial_values"] > 0
+    assert store.counter["get"] == 0
+
+
+@pytest.mark.parametrize("protocol", ["file://", "simplecache::file://"])
+def test_fsspec_url_resolution(tmp_path, protocol):
+    data = np.arange(64, dtype=np.float64).reshape(8, 8)
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"),
+        shape=(8, 8),
+        chunks=(3, 3),
+        dtype=data.dtype,
+    )
+    arr[:] = data
+
+    store = FsspecStore.from_url(
+        f"{protocol}{tmp_path / 'array.zarr'}", read_only=True
+    )
+    assert np.array_equal(zarr.open_array(store)[:], data)
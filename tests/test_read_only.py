@@ -0,0 +1,51 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore, MemoryStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def test_read_only_pipeline(tmp_path):
+    arr = zarr.create_array(
+        LocalStore(tmp_path / "array.zarr"), shape=(8,), chunks=(4,), dtype=np.uint8
+    )
+    arr[:] = np.arange(8)
+
+    with zarr.config.set({"codec_pipeline.read_only": True}):
+        arr = zarr.open_array(LocalStore(tmp_path / "array.zarr"))
+        assert np.array_equal(arr[:], np.arange(8))
+        with pytest.raises(ValueError, match="read-only"):
+            arr[:] = 0
+
+    assert np.array_equal(zarr.open_array(tmp_path / "array.zarr")[:], np.arange(8))
+
+
+def test_read_only_memory_store_view():
+    store_dict = {}
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+
+    def chunk_description(store):
+        chunk = Basic(
+            SimpleNamespace(store=store, path="c/0"),
+            SimpleNamespace(
+                shape=(4,), dtype=np.dtype("uint8"), fill_value=np.uint8(0)
+            ),
+        )
+        return WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+    # A read-only view of the dictionary is not shared with a writable one
+    read_only = chunk_description(MemoryStore(store_dict, read_only=True))
+    out = np.ones(4, dtype="uint8")
+    impl.retrieve_chunks_and_apply_index([read_only], out)
+    assert np.array_equal(out, np.zeros(4, dtype="uint8"))
+    writable = chunk_description(MemoryStore(store_dict))
+    impl.store_chunks_with_indices([writable], np.arange(1, 5, dtype="uint8"))
+    assert "c/0" in store_dict
+    with pytest.raises(Exception, match="read"):
+        impl.store_chunks_with_indices([read_only], np.arange(4, dtype="uint8"))
@@ -0,0 +1,48 @@
+#!/usr/bin/env python3
+
+import os
+import socket
+import uuid
+from urllib.parse import urlparse
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+REDIS_ENDPOINT = os.environ.get("ZARRS_TEST_REDIS_ENDPOINT", "redis://127.0.0.1:6379")
+
+
+def redis_available() -> bool:
+    url = urlparse(REDIS_ENDPOINT)
+    try:
+        with socket.create_connection((url.hostname, url.port or 6379), timeout=1):
+            return True
+    except OSError:
+        return False
+
+
+pytestmark = pytest.mark.skipif(
+    not redis_available(), reason=f"requires a Redis server at {REDIS_ENDPOINT}"
+)
+
+
+def test_redis_store(tmp_path):
+    storage_options = {
+        "scheme": "redis",
+        "endpoint": REDIS_ENDPOINT,
+        "root": f"/zarrs-test-{uuid.uuid4()}",
+    }
+    data = np.arange(100, dtype=np.int32).reshape(10, 10)
+    with zarr.config.set({"codec_pipeline.storage_options": storage_options}):
+        arr = zarr.create_array(
+            LocalStore(tmp_path), shape=data.shape, chunks=(3, 3), dtype=data.dtype
+        )
+        arr[:] = data
+        arr[2:4, 5:9] = -1
+        data[2:4, 5:9] = -1
+        assert np.array_equal(arr[:], data)
+        arr_read = zarr.open_array(LocalStore(tmp_path))
+        assert np.array_equal(arr_read[1:7, 4], data[1:7, 4])
+    # The chunks are stored in Redis rather than the local store
+    assert not (tmp_path / "c").exists()
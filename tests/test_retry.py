@@ -0,0 +1,59 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore, WrapperStore
+
+
+class FlakyStore(WrapperStore):
+    """A store whose chunk reads fail on the first attempt."""
+
+    def __init__(self, store, error=OSError):
+        super().__init__(store)
+        self.error = error
+        self.failed = set()
+
+    async def get(self, key, prototype, byte_range=None):
+        if key.startswith("c/") and key not in self.failed:
+            self.failed.add(key)
+            raise self.error(f"transient failure reading {key}")
+        return await super().get(key, prototype, byte_range)
+
+
+def test_retry(tmp_path):
+    data = np.arange(64, dtype=np.int16).reshape(8, 8)
+    store = FlakyStore(LocalStore(tmp_path / "array.zarr"))
+    with zarr.config.set(
+        {
+            "codec_pipeline.retry_max_attempts": 2,
+            "codec_pipeline.retry_base_delay": 0.001,
+        }
+    ):
+        arr = zarr.create_array(store, shape=(8, 8), chunks=(4, 4), dtype=data.dtype)
+        arr[:] = data
+        assert np.array_equal(arr[:], data)
+    assert len(store.failed) == 4
+
+
+def test_retry_not_transient(tmp_path):
+    store = FlakyStore(LocalStore(tmp_path / "array.zarr"), error=ValueError)
+    with zarr.config.set(
+        {
+            "codec_pipeline.retry_max_attempts": 2,
+            "codec_pipeline.retry_base_delay": 0.001,
+        }
+    ):
+        arr = zarr.create_array(store, shape=(8, 8), chunks=(4, 4), dtype=np.int16)
+        arr[:] = 1
+        # Errors other than I/O errors are not retried
+        with pytest.raises(RuntimeError, match="transient failure"):
+            arr[:]
+
+
+def test_no_retry(tmp_path):
+    store = FlakyStore(LocalStore(tmp_path / "array.zarr"))
+    arr = zarr.create_array(store, shape=(8, 8), chunks=(4, 4), dtype=np.int16)
+    arr[:] = 1
+    with pytest.raises(RuntimeError, match="transient failure"):
+        arr[:]
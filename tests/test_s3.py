@@ -0,0 +1,183 @@
+#!/usr/bin/env python3
+
+import json
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import FsspecStore
+
+s3fs = pytest.importorskip("s3fs")
+moto_server = pytest.importorskip("moto.moto_server.threaded_moto_server")
+boto3 = pytest.importorskip("boto3")
+moto_settings = pytest.importorskip("moto.settings")
+
+PORT = 5555
+ENDPOINT_URL = f"http://127.0.0.1:{PORT}/"
+BUCKET = "test"
+STORAGE_OPTIONS = {
+    "key": "foo",
+    "secret": "bar",
+    "endpoint_url": ENDPOINT_URL,
+    "client_kwargs": {"region_name": "us-east-1"},
+}
+
+
+@pytest.fixture(scope="module")
+def s3_server():
+    server = moto_server.ThreadedMotoServer(ip_address="127.0.0.1", port=PORT)
+    server.start()
+    fs = s3fs.S3FileSystem(anon=False, **STORAGE_OPTIONS)
+    fs.mkdir(BUCKET)
+    yield
+    server.stop()
+
+
+def test_zarrs_s3(s3_server):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/array.zarr", storage_options=STORAGE_OPTIONS
+    )
+    arr = zarr.create_array(
+        store, shape=(8, 8), chunks=(4, 4), dtype=np.float32, fill_value=0
+    )
+    data = np.arange(64, dtype=np.float32).reshape(8, 8)
+    arr[:] = data
+    arr[2:6, 3] = -1
+    data[2:6, 3] = -1
+    assert np.array_equal(zarr.open_array(store)[:], data)
+
+
+def test_zarrs_s3_unsupported_storage_option(s3_server):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/unsupported.zarr",
+        storage_options={**STORAGE_OPTIONS, "requester_pays": True},
+    )
+    arr = zarr.create_array(store, shape=(8,), chunks=(4,), dtype=np.uint8)
+    with pytest.raises(
+        ValueError, match="Unsupported storage option for S3FileSystem"
+    ):
+        arr[:] = 1
+
+
+def test_zarrs_s3_storage_options(s3_server):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/options.zarr", storage_options=STORAGE_OPTIONS
+    )
+    data = np.arange(8, dtype=np.uint8)
+    # Storage options override the configuration of the store
+    with zarr.config.set(
+        {"codec_pipeline.storage_options": {"root": "/options_chunks.zarr"}}
+    ):
+        arr = zarr.create_array(store, shape=(8,), chunks=(4,), dtype=data.dtype)
+        arr[:] = data
+        assert np.array_equal(arr[:], data)
+    fs = s3fs.S3FileSystem(anon=False, skip_instance_cache=True, **STORAGE_OPTIONS)
+    assert fs.exists(f"{BUCKET}/options_chunks.zarr/c/0")
+    assert not fs.exists(f"{BUCKET}/options.zarr/c/0")
+
+
+def test_zarrs_s3_storage_options_unsupported(s3_server):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/options_unsupported.zarr", storage_options=STORAGE_OPTIONS
+    )
+    arr = zarr.create_array(store, shape=(8,), chunks=(4,), dtype=np.uint8)
+    with (
+        zarr.config.set({"codec_pipeline.storage_options": {"regoin": "us-east-1"}}),
+        pytest.raises(ValueError, match="Unsupported storage option for the S3 store"),
+    ):
+        arr[:] = 1
+
+
+@pytest.fixture
+def s3_authenticated(s3_server):
+    """Authenticate requests to the server, so anonymous requests are denied.
+
+    Yields the storage options of a user with access to the bucket.
+    """
+    iam = boto3.client(
+        "iam",
+        endpoint_url=ENDPOINT_URL,
+        aws_access_key_id=STORAGE_OPTIONS["key"],
+        aws_secret_access_key=STORAGE_OPTIONS["secret"],
+        region_name="us-east-1",
+    )
+    iam.create_user(UserName="zarrs")
+    iam.put_user_policy(
+        UserName="zarrs",
+        PolicyName="s3",
+        PolicyDocument=json.dumps(
+            {
+                "Version": "2012-10-17",
+                "Statement": [{"Effect": "Allow", "Action": "s3:*", "Resource": "*"}],
+            }
+        ),
+    )
+    access_key = iam.create_access_key(UserName="zarrs")["AccessKey"]
+    moto_settings.INITIAL_NO_AUTH_ACTION_COUNT = 0
+    try:
+        yield {
+            **STORAGE_OPTIONS,
+            "key": access_key["AccessKeyId"],
+            "secret": access_key["SecretAccessKey"],
+        }
+    finally:
+        moto_settings.INITIAL_NO_AUTH_ACTION_COUNT = float("inf")
+
+
+def test_zarrs_s3_anonymous_fallback(s3_authenticated):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/fallback.zarr", storage_options=s3_authenticated
+    )
+    data = np.arange(16, dtype=np.uint16)
+    arr = zarr.create_array(store, shape=(16,), chunks=(4,), dtype=data.dtype)
+    arr[:] = data
+
+    # Anonymous reads are denied, so the reads succeed only if retried with credentials
+    fs = s3fs.S3FileSystem(
+        anon=True,
+        endpoint_url=ENDPOINT_URL,
+        client_kwargs={"region_name": "us-east-1"},
+        skip_instance_cache=True,
+    )
+    with pytest.raises(PermissionError):
+        fs.cat(f"{BUCKET}/fallback.zarr/c/0")
+    with zarr.config.set(
+        {"codec_pipeline.storage_options": {"anonymous_fallback": True}}
+    ):
+        assert np.array_equal(zarr.open_array(store)[:], data)
+
+
+def test_zarrs_s3_disk_cache(s3_server, tmp_path):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/disk_cache.zarr", storage_options=STORAGE_OPTIONS
+    )
+    data = np.arange(16, dtype=np.uint16)
+    arr = zarr.create_array(store, shape=(16,), chunks=(4,), dtype=data.dtype)
+    arr[:] = data
+    with zarr.config.set({"codec_pipeline.disk_cache_directory": str(tmp_path)}):
+        assert np.array_equal(zarr.open_array(store)[:], data)
+        cached = [path for path in tmp_path.rglob("*") if path.is_file()]
+        assert len(cached) == 4
+
+        # Overwritten chunks have a new ETag, so they are not read from the cache
+        arr[:4] = 0
+        data[:4] = 0
+        assert np.array_equal(zarr.open_array(store)[:], data)
+
+
+def test_zarrs_s3_multipart(s3_server):
+    store = FsspecStore.from_url(
+        f"s3://{BUCKET}/multipart.zarr", storage_options=STORAGE_OPTIONS
+    )
+    # A single 12 MiB chunk is uploaded in three parts
+    data = np.arange(3 * 1024 * 1024, dtype=np.uint32)
+    with zarr.config.set({"codec_pipeline.multipart_part_size": 5 * 1024 * 1024}):
+        arr = zarr.create_array(
+            store,
+            shape=data.shape,
+            chunks=data.shape,
+            dtype=data.dtype,
+            compressors=None,
+        )
+        arr[:] = data
+        assert np.array_equal(zarr.open_array(store)[:], data)
@@ -0,0 +1,98 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+import zarrs
+
+orthogonal_selections = [
+    pytest.param((slice(None),), id="all"),
+    pytest.param((slice(3, 17), slice(None, None, 4)), id="slices_and_step"),
+    pytest.param((5, slice(2, 9)), id="int_and_slice"),
+    pytest.param((-1, -3), id="negative_ints"),
+    pytest.param((np.array([19, 0, 7, 7]), Ellipsis), id="unordered_array"),
+    pytest.param(
+        (np.arange(20) % 3 == 0, np.array([1, -2, 5])), id="mask_and_array"
+    ),
+    pytest.param((slice(4, 4),), id="empty"),
+]
+
+
+@pytest.fixture(
+    params=[
+        pytest.param((3, {}), id="v3"),
+        pytest.param((2, {}), id="v2"),
+        pytest.param((3, {"shards": (10, 12)}), id="sharded"),
+    ]
+)
+def arr(request, tmp_path) -> zarr.Array:
+    zarr_format, kwargs = request.param
+    group = zarr.open_group(LocalStore(tmp_path), zarr_format=zarr_format)
+    arr = group.create_array(
+        "nested/array",
+        shape=(20, 15),
+        chunks=(5, 4),
+        dtype=np.int32,
+        fill_value=-1,
+        **kwargs,
+    )
+    arr[:] = np.arange(300, dtype=np.int32).reshape(20, 15)
+    return arr
+
+
+@pytest.mark.parametrize("selection", orthogonal_selections)
+def test_get_orthogonal_selection(arr: zarr.Array, selection):
+    expected = arr.oindex[selection]
+    result = zarrs.get_orthogonal_selection(arr, selection)
+    assert result.shape == expected.shape
+    assert np.array_equal(result, expected)
+
+
+@pytest.mark.parametrize("selection", orthogonal_selections)
+def test_set_orthogonal_selection(arr: zarr.Array, selection):
+    shape = arr.oindex[selection].shape
+    value = -np.arange(np.prod(shape), dtype=np.int32).reshape(shape)
+    expected = zarr.create_array(
+        {}, shape=arr.shape, chunks=(3, 3), dtype=arr.dtype, fill_value=-1
+    )
+    expected[:] = arr[:]
+    expected.oindex[selection] = value
+    zarrs.set_orthogonal_selection(arr, selection, value)
+    assert np.array_equal(arr[:], expected[:])
+
+
+def test_set_orthogonal_selection_scalar(arr: zarr.Array):
+    zarrs.set_orthogonal_selection(arr, (slice(None), np.array([0, 14])), -1)
+    expected = np.arange(300, dtype=np.int32).reshape(20, 15)
+    expected[:, [0, 14]] = -1
+    assert np.array_equal(arr[:], expected)
+
+
+def test_selection_fallback(tmp_path):
+    # Zero-dimensional arrays are read and written by zarr-python
+    arr = zarr.create_array(LocalStore(tmp_path), shape=(), dtype=np.int32)
+    zarrs.set_orthogonal_selection(arr, (), 7)
+    assert zarrs.get_orthogonal_selection(arr, ()) == 7
+
+
+def test_selection_out_of_bounds(arr: zarr.Array):
+    with pytest.raises(IndexError):
+        zarrs.get_orthogonal_selection(arr, (20,))
+    with pytest.raises(IndexError, match="axis 1"):
+        zarrs.get_orthogonal_selection(arr, (slice(None), np.array([15])))
+
+
+def test_selection_fields(tmp_path):
+    # Structured data types are read and written by zarr-python
+    dtype = np.dtype([("temperature", np.float32), ("count", np.int32)])
+    arr = zarr.create_array(
+        LocalStore(tmp_path), shape=(10,), chunks=(4,), dtype=dtype, zarr_format=2
+    )
+    zarrs.set_orthogonal_selection(
+        arr, (slice(2, 8),), np.arange(6), fields="temperature"
+    )
+    result = zarrs.get_orthogonal_selection(arr, (slice(None),), fields="temperature")
+    assert np.array_equal(result[2:8], np.arange(6, dtype=np.float32))
+    assert result.dtype == np.float32
@@ -0,0 +1,27 @@
+#!/usr/bin/env python3
+
+import pickle
+
+import numpy as np
+import pytest
+import zarr
+
+from zarrs.storage import SQLiteStore
+
+
+def test_sqlite_store(tmp_path):
+    data = np.arange(100, dtype=np.int32).reshape(10, 10)
+    store = SQLiteStore.from_url(f"sqlite://{tmp_path / 'data.db'}")
+    arr = zarr.create_array(store, shape=data.shape, chunks=(3, 3), dtype=data.dtype)
+    arr[:] = data
+    arr[2:4, 5:9] = -1
+    data[2:4, 5:9] = -1
+    assert np.array_equal(arr[:], data)
+
+    arr_read = zarr.open_array(SQLiteStore(tmp_path / "data.db", read_only=True))
+    assert np.array_equal(arr_read[:], data)
+    assert np.array_equal(arr_read[1:7, 4], data[1:7, 4])
+    with pytest.raises((RuntimeError, ValueError), match=r"read.only"):
+        arr_read[:] = 0
+
+    assert pickle.loads(pickle.dumps(store)) == store
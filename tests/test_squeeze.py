@@ -0,0 +1,72 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.codecs import ShardingCodec
+from zarr.storage import LocalStore
+
+from zarrs import SqueezeCodec
+
+
+@pytest.fixture
+def data() -> np.ndarray:
+    return np.arange(16 * 64 * 8, dtype=np.uint16).reshape(16, 64, 8)
+
+
+def test_squeeze(tmp_path, data):
+    arr = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        chunks=(1, 64, 1),
+        dtype=data.dtype,
+        filters=[SqueezeCodec()],
+        compressors=None,
+    )
+    arr[:] = data
+    assert np.array_equal(arr[:], data)
+    assert np.array_equal(arr[3:9, 10:40, 2:5], data[3:9, 10:40, 2:5])
+    assert np.array_equal(arr.oindex[[5, 0], :, [7, 1]], data[[5, 0]][:, :, [7, 1]])
+
+    # Chunks are encoded as 1D arrays
+    chunk = (tmp_path / "c" / "2" / "0" / "3").read_bytes()
+    assert chunk == data[2, :, 3].tobytes()
+    codecs = arr._async_array.codec_pipeline.impl.codecs()
+    assert [codec.name for codec in codecs] == ["zarrs.squeeze", "bytes"]
+
+    arr[4, 8:24, 1:3] = 0
+    data[4, 8:24, 1:3] = 0
+    assert np.array_equal(arr[:], data)
+
+
+def test_squeeze_sharding(tmp_path, data):
+    arr = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        shards=(4, 64, 8),
+        chunks=(1, 64, 1),
+        dtype=data.dtype,
+        filters=[SqueezeCodec()],
+    )
+    # The squeeze codec encodes the inner chunks of shards
+    assert isinstance(arr.metadata.codecs[0], ShardingCodec)
+    arr[:] = data
+    assert np.array_equal(arr[:], data)
+    assert np.array_equal(arr[1:6, 5, ::3], data[1:6, 5, ::3])
+
+
+def test_squeeze_zarr_python_compatibility(tmp_path, data):
+    arr = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        chunks=(1, 64, 1),
+        dtype=data.dtype,
+        filters=[SqueezeCodec()],
+    )
+    arr[:] = data
+    # Chunks written by zarrs are read by zarr-python, which has the same codec
+    with zarr.config.set(
+        {"codec_pipeline.path": "zarr.core.codec_pipeline.BatchedCodecPipeline"}
+    ):
+        arr = zarr.open_array(LocalStore(tmp_path), mode="r")
+        assert np.array_equal(arr[:], data)
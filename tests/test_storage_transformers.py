@@ -0,0 +1,37 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+
+def test_performance_metrics(tmp_path):
+    with zarr.config.set(
+        {"codec_pipeline.storage_transformers": ["performance_metrics"]}
+    ):
+        arr = zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(8, 8),
+            chunks=(4, 4),
+            dtype=np.uint8,
+            compressors=None,
+        )
+        arr[:] = 1
+        assert np.all(arr[:] == 1)
+
+    metrics = arr._async_array.codec_pipeline.impl.performance_metrics()
+    assert metrics.writes == 4
+    assert metrics.bytes_written == 64
+    assert metrics.reads == 4
+    assert metrics.bytes_read == 64
+
+
+def test_unsupported_storage_transformer(tmp_path):
+    with (
+        zarr.config.set({"codec_pipeline.storage_transformers": ["unknown"]}),
+        pytest.raises(ValueError, match="Unsupported storage transformer"),
+    ):
+        zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"), shape=(8,), chunks=(4,), dtype="u1"
+        )
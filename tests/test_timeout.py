@@ -0,0 +1,37 @@
+#!/usr/bin/env python3
+
+import asyncio
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore, WrapperStore
+
+
+class SlowStore(WrapperStore):
+    """A store whose chunk reads stall."""
+
+    async def get(self, key, prototype, byte_range=None):
+        if key.startswith("c/"):
+            await asyncio.sleep(1)
+        return await super().get(key, prototype, byte_range)
+
+
+def test_store_timeout(tmp_path):
+    store = SlowStore(LocalStore(tmp_path / "array.zarr"))
+    with zarr.config.set({"codec_pipeline.store_timeout": 0.05}):
+        arr = zarr.create_array(store, shape=(8,), chunks=(4,), dtype=np.uint8)
+        arr[:] = 1
+        with pytest.raises(TimeoutError, match="timed out"):
+            arr[:]
+
+
+def test_timeout(tmp_path):
+    store = SlowStore(LocalStore(tmp_path / "array.zarr"))
+    with zarr.config.set(
+        {"codec_pipeline.timeout": 0.05, "codec_pipeline.chunk_concurrent_maximum": 1}
+    ):
+        arr = zarr.create_array(store, shape=(8,), chunks=(1,), dtype=np.uint8)
+        arr[:] = 1
+        with pytest.raises(TimeoutError, match="timed out"):
+            arr[:]
@@ -0,0 +1,77 @@
+#!/usr/bin/env python3
+
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import zarr
+from zarr.codecs import ZstdCodec
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic
+
+
+def test_transcode(tmp_path):
+    store = LocalStore(tmp_path / "array.zarr")
+    arr = zarr.create_array(
+        store,
+        shape=(12,),
+        chunks=(4,),
+        dtype=np.uint16,
+        fill_value=0,
+        compressors=ZstdCodec(level=3),
+    )
+    data = np.arange(8, dtype=np.uint16)
+    arr[:8] = data
+    impl = arr._async_array.codec_pipeline.impl
+
+    chunk_spec = SimpleNamespace(shape=(4,), dtype=arr.dtype, fill_value=np.uint16(0))
+    chunks = [
+        Basic(SimpleNamespace(store=store, path=f"c/{i}"), chunk_spec)
+        for i in range(3)
+    ]
+    codecs = [{"name": "bytes", "configuration": {"endian": "big"}}]
+    impl.transcode(chunks, json.dumps(codecs))
+
+    # The chunks are re-encoded in place, and missing chunks stay missing
+    root = tmp_path / "array.zarr"
+    assert (root / "c" / "0").read_bytes() == data[:4].astype(">u2").tobytes()
+    assert (root / "c" / "1").read_bytes() == data[4:].astype(">u2").tobytes()
+    assert not (root / "c" / "2").exists()
+
+
+def test_transcode_to_another_array(tmp_path):
+    src = zarr.create_array(
+        LocalStore(tmp_path / "src.zarr"),
+        shape=(8,),
+        chunks=(4,),
+        dtype=np.int32,
+        fill_value=0,
+        compressors=None,
+    )
+    dst_store = LocalStore(tmp_path / "dst.zarr")
+    dst = zarr.create_array(
+        dst_store,
+        shape=(8,),
+        chunks=(4,),
+        dtype=np.int32,
+        fill_value=0,
+        compressors=ZstdCodec(level=5),
+    )
+    data = np.arange(8, dtype=np.int32)
+    src[:] = data
+
+    chunk_spec = SimpleNamespace(shape=(4,), dtype=src.dtype, fill_value=np.int32(0))
+    src_chunks = [
+        Basic(SimpleNamespace(store=src.store, path=f"c/{i}"), chunk_spec)
+        for i in range(2)
+    ]
+    dst_chunks = [
+        Basic(SimpleNamespace(store=dst_store, path=f"c/{i}"), chunk_spec)
+        for i in range(2)
+    ]
+    dst_codecs = [codec.to_dict() for codec in dst.metadata.codecs]
+    src._async_array.codec_pipeline.impl.transcode(
+        src_chunks, json.dumps(dst_codecs), dst_descriptions=dst_chunks
+    )
+    assert np.array_equal(dst[:], data)
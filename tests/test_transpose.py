@@ -1,12 +1,17 @@
+import json
 from typing import TYPE_CHECKING
 
 import numpy as np
 import pytest
+import zarr
 from zarr import Array, AsyncArray, config
 from zarr.abc.store import Store
 from zarr.codecs import BytesCodec, ShardingCodec, TransposeCodec
+from zarr.core.buffer import default_buffer_prototype
 from zarr.core.common import MemoryOrder
-from zarr.storage import StorePath
+from zarr.storage import LocalStore, StorePath
+
+from zarrs._internal import CodecPipelineImpl
 
 from .test_codecs import _AsyncArrayProxy
 
@@ -102,3 +107,75 @@ def test_transpose_invalid(
                 chunk_key_encoding=("v2", "."),
                 codecs=[TransposeCodec(order=order), BytesCodec()],
             )
+
+
+@pytest.mark.parametrize("with_sharding", [True, False])
+def test_transpose_subsets(store: Store, *, with_sharding: bool) -> None:
+    data = np.arange(0, 6 * 8 * 10, dtype="uint16").reshape((6, 8, 10))
+    codecs_: list[Codec] = [TransposeCodec(order=(1, 2, 0)), BytesCodec()]
+    if with_sharding:
+        codecs_ = [ShardingCodec(chunk_shape=(3, 4, 5), codecs=codecs_)]
+    a = Array.create(
+        StorePath(store, "transpose_subsets"),
+        shape=data.shape,
+        chunk_shape=(6, 8, 10),
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=codecs_,
+    )
+    a[:] = data
+    for selection in [np.s_[1:5, 2:7, 3:9], np.s_[0, :, 4], np.s_[::2, 5, 1:]]:
+        assert np.array_equal(a[selection], data[selection])
+    assert np.array_equal(a.oindex[[4, 1], :, [9, 0]], data[[4, 1]][:, :, [9, 0]])
+
+    # Decoding into a F ordered output
+    out = np.asfortranarray(np.zeros_like(data))
+    buffer = default_buffer_prototype().nd_buffer
+    a.get_basic_selection(..., out=buffer.from_numpy_array(out))
+    assert np.array_equal(out, data)
+
+    # Writing a subset of a transposed chunk
+    a[2:4, 1:3, 5:] = 7
+    data[2:4, 1:3, 5:] = 7
+    assert np.array_equal(a[:], data)
+
+
+def test_transpose_memory_order(tmp_path) -> None:
+    data = np.arange(0, 24, dtype="<u2").reshape((2, 3, 4))
+    a = zarr.create_array(
+        LocalStore(tmp_path),
+        shape=data.shape,
+        chunks=data.shape,
+        dtype=data.dtype,
+        fill_value=0,
+        filters=[TransposeCodec(order=(2, 0, 1))],
+        compressors=None,
+    )
+    a[:] = data
+    impl = a._async_array.codec_pipeline.impl
+    assert impl.memory_order(3) == [2, 0, 1]
+    # The chunk is stored with the dimensions in memory order
+    chunk = (tmp_path / "c" / "0" / "0" / "0").read_bytes()
+    assert chunk == data.transpose(2, 0, 1).tobytes()
+    with pytest.raises(ValueError, match="order"):
+        impl.memory_order(2)
+
+
+def test_transpose_memory_order_sharding() -> None:
+    codecs = [
+        {"name": "transpose", "configuration": {"order": [1, 0]}},
+        {
+            "name": "sharding_indexed",
+            "configuration": {
+                "chunk_shape": [2, 2],
+                "codecs": [
+                    {"name": "transpose", "configuration": {"order": [1, 0]}},
+                    {"name": "bytes", "configuration": {"endian": "little"}},
+                ],
+                "index_codecs": [{"name": "bytes"}, {"name": "crc32c"}],
+            },
+        },
+    ]
+    assert CodecPipelineImpl(json.dumps(codecs)).memory_order(2) == [0, 1]
+    assert CodecPipelineImpl(json.dumps(codecs[1:])).memory_order(2) == [1, 0]
+    assert CodecPipelineImpl(json.dumps([{"name": "bytes"}])).memory_order(2) == [0, 1]
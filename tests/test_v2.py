@@ -1,6 +1,7 @@
 import json
 from collections.abc import Iterator
 from pathlib import Path
+from types import SimpleNamespace
 from typing import Any, Literal
 
 import numcodecs.vlen
@@ -9,7 +10,7 @@ import pytest
 import zarr
 import zarr.core.buffer
 import zarr.storage
-from numcodecs import Delta
+from numcodecs import Delta, Shuffle
 from numcodecs.blosc import Blosc
 from numcodecs.zstd import Zstd
 from zarr import config
@@ -18,6 +19,14 @@ from zarr.core.buffer.core import default_buffer_prototype
 from zarr.core.sync import sync
 from zarr.storage import LocalStore, StorePath
 
+import zarrs
+from zarrs._internal import (
+    Basic,
+    CodecPipelineImpl,
+    WithSubset,
+    codec_metadata_v2_to_v3,
+)
+
 
 @pytest.fixture
 async def store(tmp_path) -> Iterator[StorePath]:
@@ -344,3 +353,231 @@ def test_structured_dtype_roundtrip(fill_value, tmp_path) -> None:
     za[...] = a
     za = zarr.open_array(store=array_path)
     assert (a == za[:]).all()
+
+
+def test_structured_dtype_indexing(tmp_path) -> None:
+    dtype = np.dtype([("foo", "S3"), ("bar", "<i4"), ("baz", "<f8")])
+    a = np.array([(b"a", 1, 1.5), (b"bb", 2, 2.5), (b"ccc", 3, 3.5)] * 2, dtype=dtype)
+    za = zarr.create(
+        shape=a.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(4,),
+        zarr_format=2,
+        dtype=dtype,
+    )
+    impl = za._async_array.codec_pipeline.impl
+    za[...] = a
+    assert impl.stats().sets == 2
+    a[3] = (b"ddd", 4, 4.5)
+    za[3] = a[3]
+    assert (za[:] == a).all()
+    assert (za[2:5] == a[2:5]).all()
+    assert (za[np.array([5, 0, 3])] == a[[5, 0, 3]]).all()
+    assert (za[:]["bar"] == a["bar"]).all()
+
+
+@pytest.mark.parametrize(
+    "dtype", ["datetime64[s]", "datetime64[ns]", "timedelta64[ms]"]
+)
+def test_datetime_dtype_roundtrip(dtype, tmp_path) -> None:
+    a = np.array([0, 1, 2, 3, 4], dtype="int64").astype(dtype)
+    a[2] = np.array("NaT", dtype=dtype)
+    za = zarr.create(
+        shape=a.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(2,),
+        zarr_format=2,
+        dtype=dtype,
+    )
+    za[1:] = a[1:]
+    assert np.isnat(za[0])
+    assert np.array_equal(za[1:], a[1:], equal_nan=True)
+    assert za[np.array([4, 1])].dtype == np.dtype(dtype)
+
+
+@pytest.mark.parametrize("itemsize", [1, 3, 8])
+def test_void_dtype_roundtrip(itemsize, tmp_path) -> None:
+    dtype = np.dtype(f"V{itemsize}")
+    a = np.frombuffer(bytes(range(5 * itemsize)), dtype=dtype)
+    za = zarr.create(
+        shape=a.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(2,),
+        fill_value=b"\x07" * itemsize,
+        zarr_format=2,
+        dtype=dtype,
+    )
+    assert za[:].dtype == dtype
+    assert (za[:] == np.full(5, b"\x07" * itemsize, dtype=dtype)).all()
+    za[...] = a
+    assert (za[:] == a).all()
+    assert (za[np.array([4, 1])] == a[[4, 1]]).all()
+
+
+@pytest.mark.parametrize("dtype", ["<i4", ">i4", "<u2", "<f8", ">f8"])
+def test_v2_delta_shuffle_filters(dtype: str, tmp_path: Path) -> None:
+    data = np.arange(100, dtype=dtype) * 3
+    filters = [Delta(dtype=dtype), Shuffle(elementsize=np.dtype(dtype).itemsize)]
+    compressor = Blosc(shuffle=Blosc.NOSHUFFLE)
+    za = zarr.create(
+        shape=data.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(40,),
+        dtype=dtype,
+        zarr_format=2,
+        filters=filters,
+        compressor=compressor,
+    )
+    assert za._async_array.codec_pipeline.impl is not None
+    # Big endian arrays are read into and written from by swapping their bytes
+    with zarr.config.set({"codec_pipeline.swap_byte_order": True}):
+        za[:] = data
+        assert np.array_equal(za[:], data)
+
+    # Chunks are encoded as by numcodecs
+    decoded = compressor.decode((tmp_path / "data.zarr" / "0").read_bytes())
+    for filter in reversed(filters):
+        decoded = filter.decode(decoded)
+    assert np.array_equal(np.frombuffer(decoded, dtype=dtype), data[:40])
+
+
+def test_codec_pipeline_impl_metadata_v2(tmp_path: Path) -> None:
+    metadata = {"filters": None, "compressor": None, "dtype": ">i4", "shape": [4]}
+    impl = CodecPipelineImpl(json.dumps(metadata))
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="0"),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("=i4"), fill_value=np.int32(0)),
+        data_type=">i4",
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+    impl.store_chunks_with_indices([chunk_desc], np.arange(4, dtype="=i4"))
+    # Elements are encoded with the byte order of the data type
+    assert (tmp_path / "0").read_bytes() == np.arange(4, dtype=">i4").tobytes()
+
+    with pytest.raises(ValueError, match="requires the shape"):
+        CodecPipelineImpl(json.dumps({**metadata, "order": "F", "shape": None}))
+    with pytest.raises(ValueError, match="order"):
+        CodecPipelineImpl(json.dumps({**metadata, "order": "K"}))
+
+
+def test_v2_order_f(tmp_path: Path) -> None:
+    data = np.arange(12, dtype="<i4").reshape(3, 4)
+    za = zarr.create(
+        shape=data.shape,
+        chunks=data.shape,
+        store=tmp_path / "data.zarr",
+        dtype=data.dtype,
+        order="F",
+        zarr_format=2,
+        compressor=None,
+    )
+    za[:] = data
+    assert np.array_equal(za[:], data)
+    # The chunk is encoded in F order, as by zarr-python
+    assert (tmp_path / "data.zarr" / "0.0").read_bytes() == data.tobytes(order="F")
+
+
+def test_codec_metadata_v2_to_v3_endianness() -> None:
+    compressor = json.dumps({"id": "zstd", "level": 0})
+    for dtype, endian in [(">i4", "big"), ("<f8", "little")]:
+        codecs = codec_metadata_v2_to_v3(None, compressor, dtype)
+        codecs = [json.loads(codec) for codec in codecs]
+        assert codecs[0] == {"name": "bytes", "configuration": {"endian": endian}}
+        assert codecs[1]["name"] == "zstd"
+    with pytest.raises(ValueError):
+        codec_metadata_v2_to_v3(None, compressor, "<q9")
+
+
+@pytest.mark.parametrize("data_type", ["<i4", ">i4", "|i1"])
+def test_v2_data_type_strings(data_type: str, tmp_path: Path) -> None:
+    dtype = np.dtype(data_type).newbyteorder("=")
+    impl = CodecPipelineImpl(json.dumps([{"name": "bytes"}]))
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=dtype, fill_value=dtype.type(0)),
+        data_type=data_type,
+    )
+    chunk_desc = WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+    data = np.arange(4, dtype=dtype)
+    impl.store_chunks_with_indices([chunk_desc], data)
+    out = np.zeros(4, dtype=np.float32)
+    impl.retrieve_chunks_and_apply_index([chunk_desc], out, astype="<f4")
+    assert np.array_equal(out, data.astype(np.float32))
+
+
+@pytest.mark.parametrize("fill_value", [None, "", "n/a"])
+def test_fixed_length_unicode_dtype(fill_value, tmp_path: Path) -> None:
+    a = np.array(["a", "bb", "ccccc", "dé", "€uro", ""], dtype="<U5")
+    za = zarr.create(
+        shape=a.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(4,),
+        fill_value=fill_value,
+        zarr_format=2,
+        dtype=a.dtype,
+    )
+    impl = za._async_array.codec_pipeline.impl
+    expected_fill = "" if fill_value is None else fill_value
+    assert (za[:] == np.full(6, expected_fill, dtype=a.dtype)).all()
+    za[...] = a
+    assert impl.stats().sets == 2
+    assert (za[:] == a).all()
+    assert (za[np.array([5, 2, 3])] == a[[5, 2, 3]]).all()
+    assert (zarrs.get_orthogonal_selection(za, (slice(1, 4),)) == a[1:4]).all()
+
+
+@pytest.mark.parametrize(
+    ("object_codec", "data"),
+    [
+        (numcodecs.vlen.VLenUTF8(), ["a", "bb", "", "dé", "€uro"]),
+        (numcodecs.vlen.VLenBytes(), [b"a", b"bb", b"", b"\x00\xff", b"ccc"]),
+    ],
+)
+def test_v2_vlen(object_codec: Any, data: list[Any], tmp_path: Path) -> None:
+    expected = np.array(data, dtype=object)
+    za = zarr.create(
+        shape=expected.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(2,),
+        zarr_format=2,
+        dtype=object,
+        filters=[object_codec],
+        compressor=Zstd(level=1),
+    )
+    impl = za._async_array.codec_pipeline.impl
+    za[:] = expected
+    # The chunks are encoded in Rust
+    assert impl.stats().sets == 3
+    np.testing.assert_array_equal(za[:], expected)
+    za[1:4] = expected[::-1][1:4]
+    expected[1:4] = expected[::-1][1:4].copy()
+    np.testing.assert_array_equal(za[:], expected)
+
+
+def test_v2_vlen_array(tmp_path: Path) -> None:
+    expected = np.empty(4, dtype=object)
+    for index, length in enumerate([3, 0, 3, 1]):
+        expected[index] = np.arange(length, dtype="<i4") + index
+    za = zarr.create(
+        shape=expected.shape,
+        store=tmp_path / "data.zarr",
+        chunks=(3,),
+        zarr_format=2,
+        dtype=object,
+        filters=[numcodecs.VLenArray("<i4")],
+    )
+    impl = za._async_array.codec_pipeline.impl
+    za[:] = expected
+    assert impl.stats().sets == 2
+    result = za[:]
+    assert all(
+        np.array_equal(element, expected_element)
+        for element, expected_element in zip(result, expected, strict=True)
+    )
+    # Updating a chunk keeps its other arrays
+    value = np.empty(1, dtype=object)
+    value[0] = np.array([7, 8], dtype="<i4")
+    za[1:2] = value
+    result = za[:]
+    assert np.array_equal(result[1], [7, 8])
+    assert np.array_equal(result[0], expected[0])
@@ -0,0 +1,40 @@
+#!/usr/bin/env python3
+
+from types import SimpleNamespace
+
+import numpy as np
+import zarr
+from zarr.codecs import BytesCodec, Crc32cCodec
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic
+
+
+def test_verify_chunks(tmp_path):
+    store = LocalStore(tmp_path / "array.zarr")
+    arr = zarr.create_array(
+        store,
+        shape=(16,),
+        chunks=(4,),
+        dtype=np.uint32,
+        fill_value=0,
+        serializer=BytesCodec(),
+        compressors=Crc32cCodec(),
+    )
+    arr[:12] = np.arange(1, 13, dtype=np.uint32)
+    root = tmp_path / "array.zarr"
+    # Corrupt the checksum of a chunk, and truncate another
+    chunk1 = (root / "c" / "1").read_bytes()
+    (root / "c" / "1").write_bytes(chunk1[:-4] + b"\x00" * 4)
+    (root / "c" / "2").write_bytes(b"\x00" * 6)
+
+    chunk_spec = SimpleNamespace(shape=(4,), dtype=arr.dtype, fill_value=np.uint32(0))
+    chunks = [
+        Basic(SimpleNamespace(store=store, path=f"c/{i}"), chunk_spec)
+        for i in range(4)
+    ]
+    report = arr._async_array.codec_pipeline.impl.verify_chunks(chunks)
+    assert report.valid == 1
+    assert report.missing == ["c/3"]
+    assert [key for key, _ in report.corrupt] == ["c/1"]
+    assert [key for key, _ in report.undecodable] == ["c/2"]
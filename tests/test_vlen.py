@@ -10,8 +10,6 @@ from zarr.core.metadata.v3 import ArrayV3Metadata, DataType
 from zarr.core.strings import _NUMPY_SUPPORTS_VLEN_STRING
 from zarr.storage import StorePath
 
-pytest.skip(allow_module_level=True)
-
 numpy_str_dtypes: list[type | str | None] = [None, str, "str", np.dtypes.StrDType]
 expected_zarr_string_dtype: np.dtype[Any]
 if _NUMPY_SUPPORTS_VLEN_STRING:
@@ -104,3 +102,41 @@ def test_vlen_bytes(
     assert np.array_equal(data, b[:, :])
     assert b.metadata.data_type == DataType.bytes
     assert a.dtype == "O"
+
+
+@pytest.mark.parametrize("store", ["memory", "local"], indirect=["store"])
+def test_vlen_string_partial(store: Store) -> None:
+    a = Array.create(
+        StorePath(store, path="string"),
+        shape=(5, 5),
+        chunk_shape=(2, 3),
+        dtype=str,
+        fill_value="",
+    )
+    expected = np.full((5, 5), "", dtype=object)
+    a[1:4, 2] = ["a", "bb", "ccc"]
+    expected[1:4, 2] = ["a", "bb", "ccc"]
+    a[4, :] = "é"
+    expected[4, :] = "é"
+    assert np.array_equal(a[:, :].astype(object), expected)
+    assert np.array_equal(a[3, 1:3].astype(object), expected[3, 1:3])
+    assert np.array_equal(
+        a.oindex[[4, 1], [2, 0]].astype(object), expected[[4, 1]][:, [2, 0]]
+    )
+
+
+@pytest.mark.parametrize("store", ["memory", "local"], indirect=["store"])
+def test_vlen_bytes_partial(store: Store) -> None:
+    a = Array.create(
+        StorePath(store, path="bytes"),
+        shape=(4, 4),
+        chunk_shape=(3, 3),
+        dtype="|S5",
+        fill_value=b"",
+    )
+    expected = np.full((4, 4), b"", dtype=object)
+    a[2, 1:4] = [b"\x00", b"\xff\xfe", b"abc"]
+    expected[2, 1:4] = [b"\x00", b"\xff\xfe", b"abc"]
+    assert a.metadata.data_type == DataType.bytes
+    assert np.array_equal(a[:, :], expected)
+    assert np.array_equal(a[1:3, 3], expected[1:3, 3])
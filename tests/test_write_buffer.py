@@ -0,0 +1,128 @@
+#!/usr/bin/env python3
+
+import gc
+import json
+from types import SimpleNamespace
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import LocalStore
+
+from zarrs._internal import Basic, CodecPipelineImpl, WithSubset
+
+
+def create_array(tmp_path, write_buffer_max_bytes: int) -> zarr.Array:
+    with zarr.config.set(
+        {"codec_pipeline.write_buffer_max_bytes": write_buffer_max_bytes}
+    ):
+        return zarr.create_array(
+            LocalStore(tmp_path / "array.zarr"),
+            shape=(8, 8),
+            chunks=(4, 4),
+            dtype=np.int32,
+            fill_value=0,
+        )
+
+
+def chunk_description(tmp_path):
+    chunk = Basic(
+        SimpleNamespace(store=LocalStore(tmp_path), path="c/0"),
+        SimpleNamespace(shape=(4,), dtype=np.dtype("uint16"), fill_value=np.uint16(0)),
+    )
+    return WithSubset(chunk, [slice(None)], [slice(None)], (4,))
+
+
+def test_write_buffer_coalesces_writes(tmp_path):
+    arr = create_array(tmp_path, 1 << 20)
+    impl = arr._async_array.codec_pipeline.impl
+    data = np.zeros((8, 8), dtype=np.int32)
+    for i in range(8):
+        arr[i, :] = i + 1
+        data[i, :] = i + 1
+    assert impl.stats().sets == 0
+
+    arr._async_array.codec_pipeline.flush()
+    assert impl.stats().sets == 4
+    assert np.array_equal(arr[:], data)
+
+
+def test_write_buffer_flushed_before_read(tmp_path):
+    arr = create_array(tmp_path, 1 << 20)
+    arr[1:3, 2:6] = 7
+    expected = np.zeros((8, 8), dtype=np.int32)
+    expected[1:3, 2:6] = 7
+    assert np.array_equal(arr[:], expected)
+    assert np.array_equal(arr.oindex[[2, 1], :], expected[[2, 1], :])
+
+
+def test_write_buffer_max_bytes(tmp_path):
+    # A decoded chunk is 64 bytes, so the buffer is flushed when a second chunk is buffered
+    arr = create_array(tmp_path, 64)
+    impl = arr._async_array.codec_pipeline.impl
+    arr[0, 0] = 1
+    assert impl.stats().sets == 0
+    arr[0, 4] = 2
+    assert impl.stats().sets == 2
+
+
+def test_write_buffer_fill_value(tmp_path):
+    arr = create_array(tmp_path, 1 << 20)
+    impl = arr._async_array.codec_pipeline.impl
+    arr[:] = 1
+    arr[:4, :4] = 0
+    arr._async_array.codec_pipeline.flush()
+    # The chunk that only holds the fill value is erased rather than stored
+    assert impl.stats().sets == 3
+    assert sorted(impl.list_prefix(arr.store, "c/")) == ["c/0/1", "c/1/0", "c/1/1"]
+
+
+def test_write_buffer_flushed_on_drop(tmp_path):
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes"}]), write_buffer_max_bytes=1 << 20
+    )
+    data = np.arange(1, 5, dtype="uint16")
+    impl.store_chunks_with_indices([chunk_description(tmp_path)], data)
+    path = tmp_path / "c" / "0"
+    assert not path.exists()
+
+    del impl
+    gc.collect()
+    assert path.read_bytes() == data.tobytes()
+
+
+@pytest.mark.parametrize("store_empty_chunks", [True, False])
+def test_write_buffer_store_empty_chunks_override(tmp_path, store_empty_chunks):
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes"}]),
+        store_empty_chunks=not store_empty_chunks,
+        write_buffer_max_bytes=1 << 20,
+    )
+    chunk_desc = chunk_description(tmp_path)
+    path = tmp_path / "c" / "0"
+    path.parent.mkdir()
+    path.write_bytes(np.arange(1, 5, dtype="uint16").tobytes())
+
+    # The chunk is flushed with the options of the write that buffered it
+    empty = np.zeros(4, dtype="uint16")
+    impl.store_chunks_with_indices(
+        [chunk_desc], empty, store_empty_chunks=store_empty_chunks
+    )
+    impl.flush()
+    assert path.exists() == store_empty_chunks
+
+
+def test_write_buffer_keeps_unstored_chunks(tmp_path):
+    impl = CodecPipelineImpl(
+        json.dumps([{"name": "bytes"}]), write_buffer_max_bytes=1 << 20
+    )
+    data = np.arange(1, 5, dtype="uint16")
+    impl.store_chunks_with_indices([chunk_description(tmp_path)], data)
+
+    # A file in place of the chunk directory fails the flush, and the chunk stays buffered
+    (tmp_path / "c").write_bytes(b"")
+    with pytest.raises(Exception):
+        impl.flush()
+    (tmp_path / "c").unlink()
+    impl.flush()
+    assert (tmp_path / "c" / "0").read_bytes() == data.tobytes()
@@ -36,3 +36,32 @@ def test_zarrs_http_kwargs():
     arr = zarr.open(store)
     assert arr.shape == (8, 8)
     assert np.allclose(arr[:], ARR_REF, equal_nan=True)
+
+
+def test_zarrs_http_options():
+    with zarr.config.set(
+        {
+            "codec_pipeline.http_options": {
+                "headers": {"X-Request-Source": "zarrs-python"},
+                "user_agent": "zarrs-python-tests",
+            }
+        }
+    ):
+        arr = zarr.open(URL)
+        assert np.allclose(arr[:], ARR_REF, equal_nan=True)
+
+
+def test_zarrs_http_storage_options_unsupported():
+    with (
+        zarr.config.set({"codec_pipeline.storage_options": {"bucket": "test"}}),
+        pytest.raises(ValueError, match="storage option for the HTTP store"),
+    ):
+        zarr.open(URL)[:]
+
+
+def test_zarrs_http_options_unsupported():
+    with (
+        zarr.config.set({"codec_pipeline.http_options": {"timeout": 1}}),
+        pytest.raises(ValueError, match="Unsupported HTTP option"),
+    ):
+        zarr.open(URL)
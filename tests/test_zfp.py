@@ -0,0 +1,34 @@
+import numpy as np
+import pytest
+from zarr import Array
+from zarr.abc.store import Store
+from zarr.storage import StorePath
+
+pytest.importorskip("zfpy")
+from numcodecs.zarr3 import ZFPY  # noqa: E402
+
+
+@pytest.mark.parametrize(
+    "codec",
+    [
+        ZFPY(mode=2, rate=16),
+        ZFPY(mode=3, precision=24),
+        ZFPY(mode=4, tolerance=1e-3),
+    ],
+    ids=["fixed_rate", "fixed_precision", "fixed_accuracy"],
+)
+def test_zfp(*, store: Store, codec: ZFPY) -> None:
+    data = np.linspace(0, 1, 256, dtype="float64").reshape((16, 16))
+
+    a = Array.create(
+        StorePath(store, path="zfp"),
+        shape=data.shape,
+        chunk_shape=(8, 8),
+        dtype=data.dtype,
+        fill_value=0,
+        codecs=[codec],
+    )
+    assert a._async_array.codec_pipeline.impl is not None
+
+    a[:, :] = data
+    assert np.allclose(data, a[:, :], atol=1e-3)
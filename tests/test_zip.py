@@ -0,0 +1,41 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.storage import ZipStore
+
+
+@pytest.fixture
+def zip_path(tmp_path):
+    path = tmp_path / "data.zarr.zip"
+    data = np.arange(100, dtype=np.int32).reshape(10, 10)
+    with zarr.config.set(
+        {"codec_pipeline.path": "zarr.core.codec_pipeline.BatchedCodecPipeline"}
+    ):
+        store = ZipStore(path, mode="w")
+        arr = zarr.create_array(
+            store, shape=data.shape, chunks=(4, 4), dtype=data.dtype, fill_value=-1
+        )
+        arr[:] = data
+        store.close()
+    return path
+
+
+def test_zip_read(zip_path):
+    store = ZipStore(zip_path, mode="r")
+    arr = zarr.open_array(store)
+    expected = np.arange(100, dtype=np.int32).reshape(10, 10)
+    assert np.array_equal(arr[:], expected)
+    assert np.array_equal(arr[3:7, 2:9], expected[3:7, 2:9])
+
+
+def test_zip_write(tmp_path):
+    path = tmp_path / "write.zarr.zip"
+    store = ZipStore(path, mode="w")
+    arr = zarr.create_array(store, shape=(4,), chunks=(2,), dtype=np.uint8)
+    arr[:] = np.arange(4, dtype=np.uint8)
+    store.close()
+    assert np.array_equal(
+        zarr.open_array(ZipStore(path, mode="r"))[:], np.arange(4, dtype=np.uint8)
+    )
@@ -0,0 +1,57 @@
+#!/usr/bin/env python3
+
+import numpy as np
+import pytest
+import zarr
+from zarr.codecs import ZstdCodec
+from zarr.storage import LocalStore
+
+import zarrs
+from zarrs import ZstdDictionaryCodec
+
+
+def stored_bytes(path):
+    return sum(file.stat().st_size for file in (path / "c").rglob("*"))
+
+
+def test_zstd_dictionary(tmp_path):
+    # Many small chunks with the same structure
+    rng = np.random.default_rng(0)
+    pattern = np.tile(np.arange(16, dtype=np.uint8), 8)
+    data = pattern + rng.integers(0, 2, size=(512, 128), dtype=np.uint8)
+
+    dictionary = zarrs.train_zstd_dictionary(
+        [row.tobytes() for row in data[::2]], max_size=4096
+    )
+    assert 0 < len(dictionary) <= 4096
+
+    arrays = {}
+    for name, compressor in [
+        ("zstd", ZstdCodec(level=3)),
+        ("zstd_dictionary", ZstdDictionaryCodec(dictionary=dictionary, level=3)),
+    ]:
+        arr = zarr.create_array(
+            LocalStore(tmp_path / f"{name}.zarr"),
+            shape=data.shape,
+            chunks=(1, 128),
+            dtype=data.dtype,
+            compressors=compressor,
+        )
+        arr[:] = data
+        arrays[name] = arr
+
+    # The dictionary is stored in the array metadata
+    arr = zarr.open_array(LocalStore(tmp_path / "zstd_dictionary.zarr"), mode="r")
+    assert arr.metadata.codecs[1] == ZstdDictionaryCodec(dictionary=dictionary, level=3)
+    assert np.array_equal(arr[:], data)
+    assert stored_bytes(tmp_path / "zstd_dictionary.zarr") < stored_bytes(
+        tmp_path / "zstd.zarr"
+    )
+
+
+def test_zstd_dictionary_invalid():
+    with pytest.raises(TypeError, match="dictionary"):
+        zarrs._internal.CodecPipelineImpl(
+            '[{"name": "bytes"}, {"name": "zarrs_python.zstd_dictionary", '
+            '"configuration": {"dictionary": "not base64!"}}]'
+        )